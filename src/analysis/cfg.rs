@@ -0,0 +1,300 @@
+//! Control-flow graph of a [`Program`].
+//!
+//! The program is split into [basic blocks](BasicBlock): maximal runs of instructions which are
+//! always executed one after another. Blocks are connected with [edges](Edge) according to the
+//! semantics of the last instruction of the block:
+//! * most instructions (including checks like `Cc*`, `Cell*`, `Var*` and [`Empty`] cells) fall
+//!   through to the next cell. The last cell of a row falls through to the next row, the last cell
+//!   of a page - to the next page. After the last cell of the program the execution starts over,
+//!   so it has a [`Restart`](EdgeKind::Restart) edge to the first cell instead
+//! * [`GoTo`] jumps to its label
+//! * [`GoSub`], [`GoSub1`] and [`GoSubF`] call their label and continue with the next cell after
+//!   the matching [`Return`], [`Return1`] or [`ReturnF`]. Returns have no successors in the graph,
+//!   a [`CallReturn`](EdgeKind::CallReturn) edge goes from the call to its continuation instead
+//! * [`IfGoTo`] and [`IfNotGoTo`] either jump to their label or fall through, depending on the
+//!   result of the previous checks
+//! * [`OnResp`] falls through and registers its label as a handler, which is reachable too
+//! * [`Back`] and [`Restart`] restart the program from the first cell
+//! * [`End`] stops the program
+//!
+//! Jumps to undefined labels (see [labels](crate::analysis::labels)) have no edges.
+//!
+//! [`Empty`]: InstructionId::Empty
+//! [`GoTo`]: InstructionId::GoTo
+//! [`GoSub`]: InstructionId::GoSub
+//! [`GoSub1`]: InstructionId::GoSub1
+//! [`GoSubF`]: InstructionId::GoSubF
+//! [`Return`]: InstructionId::Return
+//! [`Return1`]: InstructionId::Return1
+//! [`ReturnF`]: InstructionId::ReturnF
+//! [`IfGoTo`]: InstructionId::IfGoTo
+//! [`IfNotGoTo`]: InstructionId::IfNotGoTo
+//! [`OnResp`]: InstructionId::OnResp
+//! [`Back`]: InstructionId::Back
+//! [`Restart`]: InstructionId::Restart
+//! [`End`]: InstructionId::End
+
+use crate::analysis::diagnostics::{Diagnostics, UnreachableCode};
+use crate::analysis::labels::LabelAnalysis;
+use crate::formats::internal::{InstructionId, InstructionPosition, Program};
+
+/// A maximal run of instructions which are always executed one after another.
+///
+/// Only the last instruction of a block can transfer control somewhere else than to the next
+/// cell, and only the first one can be a target of such a transfer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BasicBlock {
+    start: InstructionPosition,
+    end: InstructionPosition,
+}
+
+impl BasicBlock {
+    /// Returns position of the first instruction of this block.
+    pub fn start(&self) -> InstructionPosition {
+        self.start
+    }
+    /// Returns position of the last instruction of this block.
+    pub fn end(&self) -> InstructionPosition {
+        self.end
+    }
+    /// Returns the number of instructions in this block.
+    pub fn len(&self) -> usize {
+        self.end.index() - self.start.index() + 1
+    }
+    /// Always returns `false`, as every block contains at least one instruction.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+    /// Checks if this block contains the instruction at the given `position`.
+    pub fn contains(&self, position: InstructionPosition) -> bool {
+        self.start <= position && position <= self.end
+    }
+}
+
+/// How control is transferred along an [`Edge`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EdgeKind {
+    /// To the next cell.
+    FallThrough,
+    /// An unconditional jump ([`GoTo`](InstructionId::GoTo)).
+    Jump,
+    /// A taken conditional jump ([`IfGoTo`](InstructionId::IfGoTo),
+    /// [`IfNotGoTo`](InstructionId::IfNotGoTo)). The not taken one is a
+    /// [`FallThrough`](Self::FallThrough).
+    Branch,
+    /// A call of a subroutine.
+    Call,
+    /// From a call to the next cell, where the subroutine returns to.
+    CallReturn,
+    /// To an [`OnResp`](InstructionId::OnResp) handler.
+    Handler,
+    /// To the start of the program ([`Back`](InstructionId::Back),
+    /// [`Restart`](InstructionId::Restart) and falling through the last cell of the program).
+    Restart,
+}
+
+/// A directed edge between two [basic blocks](BasicBlock), given by their indexes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Edge {
+    /// Index of the block the edge goes from.
+    pub from: usize,
+    /// Index of the block the edge goes to.
+    pub to: usize,
+    /// How control is transferred.
+    pub kind: EdgeKind,
+}
+
+/// Control-flow graph of a [`Program`].
+///
+/// The entry block (the one with the first cell of the program) has index `0`.
+///
+/// # Examples
+///
+/// ```
+/// use m3c::analysis::cfg::{ControlFlowGraph, EdgeKind};
+/// use m3c::formats::internal::literals::LabelIdentifierLiteral;
+/// use m3c::formats::internal::{Instruction, InstructionId, Program};
+///
+/// let label = LabelIdentifierLiteral::new_from_array(*b"a\0\0\0").unwrap();
+/// let mut program = Program::default();
+/// program[0] = Instruction::new_label(InstructionId::Label, label).unwrap();
+/// program[1] = Instruction::new_simple(InstructionId::MoveW).unwrap();
+/// program[2] = Instruction::new_label(InstructionId::GoTo, label).unwrap();
+/// program[3] = Instruction::new_simple(InstructionId::MoveS).unwrap();
+///
+/// let cfg = ControlFlowGraph::new(&program);
+///
+/// // `Label`, `MoveW`, `GoTo` and the unreachable rest of the program
+/// assert_eq!(2, cfg.blocks().len());
+/// assert_eq!(EdgeKind::Jump, cfg.successors(0).next().unwrap().kind);
+/// assert_eq!(1, cfg.diagnostics().len());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ControlFlowGraph {
+    blocks: Vec<BasicBlock>,
+    // sorted by `from`
+    edges: Vec<Edge>,
+    reachable: Vec<bool>,
+    // positions of not empty unreachable instructions by blocks
+    unreachable: Vec<InstructionPosition>,
+}
+
+impl ControlFlowGraph {
+    /// Builds the graph of the given `program`.
+    pub fn new(program: &Program) -> Self {
+        let labels = LabelAnalysis::new(program);
+        let positions: Vec<InstructionPosition> =
+            program.instruction_positions().map(|(p, _)| p).collect();
+
+        // find leaders
+        let mut is_leader = vec![false; positions.len()];
+        is_leader[0] = true;
+        for (position, instruction) in program.instruction_positions() {
+            let i = position.index();
+            if instruction.id() == InstructionId::Label {
+                is_leader[i] = true;
+            }
+            if Self::is_terminator(instruction.id()) && i + 1 < positions.len() {
+                is_leader[i + 1] = true;
+            }
+        }
+
+        // split into blocks
+        let mut blocks = vec![];
+        let mut block_of = vec![0; positions.len()];
+        for (i, &position) in positions.iter().enumerate() {
+            if is_leader[i] {
+                blocks.push(BasicBlock {
+                    start: position,
+                    end: position,
+                });
+            }
+            let last = blocks.len() - 1;
+            blocks[last].end = position;
+            block_of[i] = last;
+        }
+
+        // connect blocks
+        let mut edges = vec![];
+        for (from, block) in blocks.iter().enumerate() {
+            let i = block.end.index();
+            // the execution starts over after the last cell
+            let (next, fall_through) = match block_of.get(i + 1) {
+                Some(&next) => (Some(next), EdgeKind::FallThrough),
+                None => (Some(0), EdgeKind::Restart),
+            };
+            let target = labels.target(block.end).map(|t| block_of[t.index()]);
+            let mut push = |to: Option<usize>, kind| {
+                if let Some(to) = to {
+                    edges.push(Edge { from, to, kind });
+                }
+            };
+            match program[block.end].id() {
+                InstructionId::End
+                | InstructionId::Return
+                | InstructionId::Return1
+                | InstructionId::ReturnF => {}
+                InstructionId::Back | InstructionId::Restart => push(Some(0), EdgeKind::Restart),
+                InstructionId::GoTo => push(target, EdgeKind::Jump),
+                InstructionId::GoSub | InstructionId::GoSub1 | InstructionId::GoSubF => {
+                    push(target, EdgeKind::Call);
+                    push(next, EdgeKind::CallReturn);
+                }
+                InstructionId::IfGoTo | InstructionId::IfNotGoTo => {
+                    push(target, EdgeKind::Branch);
+                    push(next, fall_through);
+                }
+                InstructionId::OnResp => {
+                    push(next, fall_through);
+                    push(target, EdgeKind::Handler);
+                }
+                _ => push(next, fall_through),
+            }
+        }
+
+        // find reachable blocks
+        let mut reachable = vec![false; blocks.len()];
+        let mut stack = vec![0];
+        while let Some(block) = stack.pop() {
+            if std::mem::replace(&mut reachable[block], true) {
+                continue;
+            }
+            stack.extend(edges.iter().filter(|e| e.from == block).map(|e| e.to));
+        }
+
+        let unreachable = blocks
+            .iter()
+            .zip(&reachable)
+            .filter(|(_, &reachable)| !reachable)
+            .filter_map(|(block, _)| {
+                positions[block.start.index()..=block.end.index()]
+                    .iter()
+                    .copied()
+                    .find(|&p| program[p].id() != InstructionId::Empty)
+            })
+            .collect();
+
+        Self {
+            blocks,
+            edges,
+            reachable,
+            unreachable,
+        }
+    }
+    /// Checks if an instruction with the given `id` ends a basic block.
+    fn is_terminator(id: InstructionId) -> bool {
+        matches!(
+            id,
+            InstructionId::End
+                | InstructionId::Back
+                | InstructionId::Restart
+                | InstructionId::GoTo
+                | InstructionId::GoSub
+                | InstructionId::GoSub1
+                | InstructionId::GoSubF
+                | InstructionId::Return
+                | InstructionId::Return1
+                | InstructionId::ReturnF
+                | InstructionId::IfGoTo
+                | InstructionId::IfNotGoTo
+                | InstructionId::OnResp
+        )
+    }
+    /// Returns all the blocks in order of the program.
+    pub fn blocks(&self) -> &[BasicBlock] {
+        &self.blocks
+    }
+    /// Returns all the edges sorted by the block they go from.
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+    /// Returns the edges going from the given `block`.
+    pub fn successors(&self, block: usize) -> impl Iterator<Item = &Edge> {
+        self.edges.iter().filter(move |edge| edge.from == block)
+    }
+    /// Returns the edges going to the given `block`.
+    pub fn predecessors(&self, block: usize) -> impl Iterator<Item = &Edge> {
+        self.edges.iter().filter(move |edge| edge.to == block)
+    }
+    /// Returns index of the block containing the instruction at the given `position`.
+    pub fn block_of(&self, position: InstructionPosition) -> usize {
+        self.blocks.partition_point(|block| block.end < position)
+    }
+    /// Checks if the block with the given index can be reached from the start of the program.
+    pub fn is_block_reachable(&self, block: usize) -> bool {
+        self.reachable[block]
+    }
+    /// Checks if the instruction at the given `position` can be reached from the start of the
+    /// program.
+    pub fn is_reachable(&self, position: InstructionPosition) -> bool {
+        self.reachable[self.block_of(position)]
+    }
+    /// Returns [`UnreachableCode`] diagnostics for every unreachable block containing not only
+    /// empty cells.
+    pub fn diagnostics(&self) -> Vec<Diagnostics> {
+        self.unreachable
+            .iter()
+            .map(|&position| UnreachableCode::new(position).into())
+            .collect()
+    }
+}
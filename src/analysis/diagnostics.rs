@@ -0,0 +1,253 @@
+//! Module for analyses' diagnostics.
+
+use crate::formats::internal::literals::LabelIdentifierLiteral;
+use crate::formats::internal::InstructionPosition;
+
+// region: general
+
+/// A trait for diagnostic objects.
+pub trait Diagnostic {
+    /// Returns this diagnostic's [id](DiagnosticId).
+    fn id(&self) -> DiagnosticId;
+    /// Returns this diagnostic's [id](DiagnosticId) prefixed with letter `L` (stands for Lint).
+    fn prefixed_id(&self) -> String {
+        let id: u8 = self.id().into();
+        format_args!("L{:0>2}", id).to_string()
+    }
+    /// Returns this diagnostic's message.
+    fn what(&self) -> String;
+    /// Returns position of the instruction this diagnostic points to.
+    fn position(&self) -> InstructionPosition;
+}
+
+/// Represents all id of [Diagnostic].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum DiagnosticId {
+    UndefinedLabel = 1,
+    DuplicateLabel = 2,
+    UnusedLabel = 3,
+    UnreachableCode = 4,
+}
+
+impl From<DiagnosticId> for u8 {
+    fn from(id: DiagnosticId) -> Self {
+        id as u8
+    }
+}
+
+/// An enumeration of all [Diagnostic]s.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Diagnostics {
+    UndefinedLabel(UndefinedLabel),
+    DuplicateLabel(DuplicateLabel),
+    UnusedLabel(UnusedLabel),
+    UnreachableCode(UnreachableCode),
+}
+
+macro_rules! impl_trait_for_diagnostics {
+    ($method:ident, $rtype:ident) => {
+        fn $method(&self) -> $rtype {
+            match self {
+                Self::UndefinedLabel(x) => x.$method(),
+                Self::DuplicateLabel(x) => x.$method(),
+                Self::UnusedLabel(x) => x.$method(),
+                Self::UnreachableCode(x) => x.$method(),
+            }
+        }
+    };
+}
+
+impl Diagnostic for Diagnostics {
+    impl_trait_for_diagnostics!(id, DiagnosticId);
+    impl_trait_for_diagnostics!(prefixed_id, String);
+    impl_trait_for_diagnostics!(what, String);
+    impl_trait_for_diagnostics!(position, InstructionPosition);
+}
+
+// endregion: general
+
+/// A jump to a label which no `Label` instruction defines.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UndefinedLabel {
+    position: InstructionPosition,
+    label: LabelIdentifierLiteral,
+}
+
+impl UndefinedLabel {
+    pub const ID: DiagnosticId = DiagnosticId::UndefinedLabel;
+    pub fn new(position: InstructionPosition, label: LabelIdentifierLiteral) -> Self {
+        Self { position, label }
+    }
+    /// Returns the undefined label.
+    pub fn label(&self) -> LabelIdentifierLiteral {
+        self.label
+    }
+}
+
+impl Diagnostic for UndefinedLabel {
+    fn id(&self) -> DiagnosticId {
+        Self::ID
+    }
+    fn what(&self) -> String {
+        format_args!(
+            "{}: [{}] label '{}' is not defined\n",
+            self.position.custom_format(),
+            self.prefixed_id(),
+            self.label.as_str()
+        )
+        .to_string()
+    }
+    /// Returns position of the jump.
+    fn position(&self) -> InstructionPosition {
+        self.position
+    }
+}
+
+impl From<UndefinedLabel> for Diagnostics {
+    fn from(x: UndefinedLabel) -> Self {
+        Diagnostics::UndefinedLabel(x)
+    }
+}
+
+/// A label which is already defined by another `Label` instruction.
+///
+/// Jumps to such a label go to the first definition.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DuplicateLabel {
+    position: InstructionPosition,
+    first: InstructionPosition,
+    label: LabelIdentifierLiteral,
+}
+
+impl DuplicateLabel {
+    pub const ID: DiagnosticId = DiagnosticId::DuplicateLabel;
+    pub fn new(
+        position: InstructionPosition,
+        first: InstructionPosition,
+        label: LabelIdentifierLiteral,
+    ) -> Self {
+        Self {
+            position,
+            first,
+            label,
+        }
+    }
+    /// Returns position of the first definition.
+    pub fn first(&self) -> InstructionPosition {
+        self.first
+    }
+    /// Returns the duplicate label.
+    pub fn label(&self) -> LabelIdentifierLiteral {
+        self.label
+    }
+}
+
+impl Diagnostic for DuplicateLabel {
+    fn id(&self) -> DiagnosticId {
+        Self::ID
+    }
+    fn what(&self) -> String {
+        format_args!(
+            "{}: [{}] label '{}' is already defined at {}\n",
+            self.position.custom_format(),
+            self.prefixed_id(),
+            self.label.as_str(),
+            self.first.custom_format()
+        )
+        .to_string()
+    }
+    /// Returns position of the duplicate definition.
+    fn position(&self) -> InstructionPosition {
+        self.position
+    }
+}
+
+impl From<DuplicateLabel> for Diagnostics {
+    fn from(x: DuplicateLabel) -> Self {
+        Diagnostics::DuplicateLabel(x)
+    }
+}
+
+/// A label which no jump refers to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnusedLabel {
+    position: InstructionPosition,
+    label: LabelIdentifierLiteral,
+}
+
+impl UnusedLabel {
+    pub const ID: DiagnosticId = DiagnosticId::UnusedLabel;
+    pub fn new(position: InstructionPosition, label: LabelIdentifierLiteral) -> Self {
+        Self { position, label }
+    }
+    /// Returns the unused label.
+    pub fn label(&self) -> LabelIdentifierLiteral {
+        self.label
+    }
+}
+
+impl Diagnostic for UnusedLabel {
+    fn id(&self) -> DiagnosticId {
+        Self::ID
+    }
+    fn what(&self) -> String {
+        format_args!(
+            "{}: [{}] label '{}' is never used\n",
+            self.position.custom_format(),
+            self.prefixed_id(),
+            self.label.as_str()
+        )
+        .to_string()
+    }
+    /// Returns position of the label definition.
+    fn position(&self) -> InstructionPosition {
+        self.position
+    }
+}
+
+impl From<UnusedLabel> for Diagnostics {
+    fn from(x: UnusedLabel) -> Self {
+        Diagnostics::UnusedLabel(x)
+    }
+}
+
+/// Instructions which can't be reached from the start of the program.
+///
+/// Reported once per [basic block](crate::analysis::cfg::BasicBlock), for its first not empty
+/// instruction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnreachableCode {
+    position: InstructionPosition,
+}
+
+impl UnreachableCode {
+    pub const ID: DiagnosticId = DiagnosticId::UnreachableCode;
+    pub fn new(position: InstructionPosition) -> Self {
+        Self { position }
+    }
+}
+
+impl Diagnostic for UnreachableCode {
+    fn id(&self) -> DiagnosticId {
+        Self::ID
+    }
+    fn what(&self) -> String {
+        format_args!(
+            "{}: [{}] unreachable instruction\n",
+            self.position.custom_format(),
+            self.prefixed_id()
+        )
+        .to_string()
+    }
+    /// Returns position of the first unreachable instruction.
+    fn position(&self) -> InstructionPosition {
+        self.position
+    }
+}
+
+impl From<UnreachableCode> for Diagnostics {
+    fn from(x: UnreachableCode) -> Self {
+        Diagnostics::UnreachableCode(x)
+    }
+}
@@ -0,0 +1,140 @@
+//! Graphviz DOT export of a program's [control-flow graph](crate::analysis::cfg).
+//!
+//! Every [basic block](BasicBlock) is a node labelled with its instructions (assembly mnemonics
+//! and `page:row:column` positions), every [edge](Edge) is labelled with its type. Runs of empty
+//! cells are collapsed into one line, unreachable blocks are gray. Unreachable blocks of empty
+//! cells only are omitted.
+
+use std::io;
+
+use crate::analysis::cfg::{BasicBlock, ControlFlowGraph, Edge, EdgeKind};
+use crate::formats::internal::{InstructionId, Program};
+
+/// Returns the DOT graph of the `program`'s control flow.
+///
+/// See [`write_dot`] for writing to a writer.
+///
+/// # Examples
+///
+/// ```
+/// use m3c::analysis::dot::to_dot;
+/// use m3c::formats::internal::{Instruction, InstructionId, Program};
+///
+/// let mut program = Program::default();
+/// program[0] = Instruction::new_simple(InstructionId::MoveW).unwrap();
+/// program[1] = Instruction::new_simple(InstructionId::End).unwrap();
+///
+/// let dot = to_dot(&program);
+/// assert!(dot.starts_with("digraph program {\n"));
+/// assert!(dot.contains(r#"b0 [label="0:0:0  MOVE_W\l0:0:1  END\l"];"#));
+/// ```
+pub fn to_dot(program: &Program) -> String {
+    let mut buf = vec![];
+    write_dot(program, &mut buf).expect("writing to Vec never fails");
+    String::from_utf8(buf).expect("the graph is UTF-8")
+}
+
+/// Writes the DOT graph of the `program`'s control flow to the `writer`.
+///
+/// # Errors
+///
+/// See the [`write_all`]'s `Errors` sections.
+///
+/// [`write_all`]: io::Write::write_all
+pub fn write_dot<W>(program: &Program, writer: &mut W) -> io::Result<()>
+where
+    W: io::Write,
+{
+    let cfg = ControlFlowGraph::new(program);
+    let shown: Vec<bool> = cfg
+        .blocks()
+        .iter()
+        .enumerate()
+        .map(|(i, block)| cfg.is_block_reachable(i) || !is_empty_block(program, block))
+        .collect();
+
+    writer.write_all(b"digraph program {\n")?;
+    writer.write_all(b"    node [shape=box, fontname=\"monospace\"];\n")?;
+    for (i, block) in cfg.blocks().iter().enumerate() {
+        if !shown[i] {
+            continue;
+        }
+        let style = if cfg.is_block_reachable(i) {
+            ""
+        } else {
+            ", color=gray, fontcolor=gray"
+        };
+        writeln!(
+            writer,
+            "    b{} [label=\"{}\"{}];",
+            i,
+            block_label(program, block),
+            style
+        )?;
+    }
+    for &Edge { from, to, kind } in cfg.edges() {
+        if !shown[from] || !shown[to] {
+            continue;
+        }
+        let (label, style) = edge_style(kind);
+        writeln!(
+            writer,
+            "    b{} -> b{} [label=\"{}\"{}];",
+            from, to, label, style
+        )?;
+    }
+    writer.write_all(b"}\n")
+}
+
+/// Checks if the `block` consists of empty cells only.
+fn is_empty_block(program: &Program, block: &BasicBlock) -> bool {
+    (block.start().index()..=block.end().index()).all(|i| program[i].id() == InstructionId::Empty)
+}
+
+/// Returns the node label: one left-justified line per instruction.
+fn block_label(program: &Program, block: &BasicBlock) -> String {
+    let mut label = String::new();
+    let mut positions = program
+        .instruction_positions()
+        .skip(block.start().index())
+        .take(block.len())
+        .peekable();
+    while let Some((position, instruction)) = positions.next() {
+        label.push_str(&position.custom_format());
+        label.push_str("  ");
+        if instruction.id() == InstructionId::Empty {
+            let mut empty = 1;
+            while positions
+                .next_if(|(_, ins)| ins.id() == InstructionId::Empty)
+                .is_some()
+            {
+                empty += 1;
+            }
+            if empty == 1 {
+                label.push_str("EMPTY");
+            } else {
+                label.push_str(&format!("EMPTY x{}", empty));
+            }
+        } else {
+            let mut mnemonic = String::new();
+            instruction.dumps_to(&mut mnemonic, "");
+            // string literals can contain any non-control chars, DOT only needs `\` and `"` escaped
+            label.push_str(&mnemonic.replace('\\', "\\\\").replace('"', "\\\""));
+        }
+        label.push_str("\\l");
+    }
+    label
+}
+
+/// Returns the label and the extra attributes of an edge of the given `kind`.
+fn edge_style(kind: EdgeKind) -> (&'static str, &'static str) {
+    match kind {
+        EdgeKind::FallThrough => ("fall-through", ""),
+        EdgeKind::Jump => ("jump", ", style=bold"),
+        EdgeKind::Branch => ("conditional", ", style=dashed, color=darkorange"),
+        EdgeKind::Call => ("call", ", color=blue"),
+        EdgeKind::CallReturn => ("return", ", style=dotted, color=blue"),
+        EdgeKind::Handler => ("handler", ", style=dashed"),
+        EdgeKind::Restart => ("restart", ", style=bold, color=red"),
+    }
+}
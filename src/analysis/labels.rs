@@ -0,0 +1,152 @@
+//! Label resolution and validation.
+//!
+//! Jump instructions ([`GoTo`], [`GoSub`], [`GoSub1`], [`GoSubF`], [`IfGoTo`], [`IfNotGoTo`] and
+//! [`OnResp`]) refer to labels defined by [`Label`] instructions. [`LabelAnalysis`] builds a symbol
+//! table of the labels, resolves every jump and reports:
+//! * [jumps to undefined labels](UndefinedLabel)
+//! * [labels defined more than once](DuplicateLabel)
+//! * [labels no jump refers to](UnusedLabel)
+//!
+//! [`GoTo`]: InstructionId::GoTo
+//! [`GoSub`]: InstructionId::GoSub
+//! [`GoSub1`]: InstructionId::GoSub1
+//! [`GoSubF`]: InstructionId::GoSubF
+//! [`IfGoTo`]: InstructionId::IfGoTo
+//! [`IfNotGoTo`]: InstructionId::IfNotGoTo
+//! [`OnResp`]: InstructionId::OnResp
+//! [`Label`]: InstructionId::Label
+
+use std::collections::{HashMap, HashSet};
+
+use crate::analysis::diagnostics::{
+    Diagnostic, Diagnostics, DuplicateLabel, UndefinedLabel, UnusedLabel,
+};
+use crate::formats::internal::literals::LabelIdentifierLiteral;
+use crate::formats::internal::{InstructionData, InstructionId, InstructionPosition, Program};
+
+/// A jump instruction and its resolved target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Jump {
+    /// Position of the jump instruction.
+    pub position: InstructionPosition,
+    /// Id of the jump instruction.
+    pub id: InstructionId,
+    /// The label the jump refers to.
+    pub label: LabelIdentifierLiteral,
+    /// Position of the [`Label`](InstructionId::Label) instruction the jump goes to. Value `None`
+    /// stands for the label isn't defined.
+    pub target: Option<InstructionPosition>,
+}
+
+/// The result of label resolution over a [`Program`].
+///
+/// # Examples
+///
+/// ```
+/// use m3c::analysis::labels::LabelAnalysis;
+/// use m3c::formats::internal::literals::LabelIdentifierLiteral;
+/// use m3c::formats::internal::{Instruction, InstructionId, InstructionPosition, Program};
+///
+/// let label = LabelIdentifierLiteral::new_from_array(*b"abc\0").unwrap();
+/// let mut program = Program::default();
+/// program[0] = Instruction::new_label(InstructionId::GoTo, label).unwrap();
+/// program[5] = Instruction::new_label(InstructionId::Label, label).unwrap();
+///
+/// let analysis = LabelAnalysis::new(&program);
+///
+/// let target = InstructionPosition::new(0, 0, 5).unwrap();
+/// assert_eq!(Some(target), analysis.label(label));
+/// assert_eq!(Some(target), analysis.target(InstructionPosition::default()));
+/// assert!(analysis.diagnostics().is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct LabelAnalysis {
+    // the first definition of every label
+    labels: HashMap<LabelIdentifierLiteral, InstructionPosition>,
+    // in order of the program
+    jumps: Vec<Jump>,
+    // in order of the program
+    diagnostics: Vec<Diagnostics>,
+}
+
+impl LabelAnalysis {
+    /// Analyzes the given `program`.
+    pub fn new(program: &Program) -> Self {
+        let mut labels = HashMap::new();
+        let mut jumps = vec![];
+        let mut diagnostics: Vec<Diagnostics> = vec![];
+
+        for (position, instruction) in program.instruction_positions() {
+            let InstructionData::Label(label) = instruction.data() else {
+                continue;
+            };
+            match instruction.id() {
+                InstructionId::Label => {
+                    if let Some(&first) = labels.get(&label) {
+                        diagnostics.push(DuplicateLabel::new(position, first, label).into());
+                    } else {
+                        labels.insert(label, position);
+                    }
+                }
+                id => jumps.push(Jump {
+                    position,
+                    id,
+                    label,
+                    target: None,
+                }),
+            }
+        }
+
+        let mut used = HashSet::new();
+        for jump in &mut jumps {
+            jump.target = labels.get(&jump.label).copied();
+            match jump.target {
+                Some(_) => {
+                    used.insert(jump.label);
+                }
+                None => diagnostics.push(UndefinedLabel::new(jump.position, jump.label).into()),
+            }
+        }
+        for (&label, &position) in &labels {
+            if !used.contains(&label) {
+                diagnostics.push(UnusedLabel::new(position, label).into());
+            }
+        }
+        // there is at most one diagnostic per position
+        diagnostics.sort_by_key(|diagnostic| diagnostic.position());
+
+        Self {
+            labels,
+            jumps,
+            diagnostics,
+        }
+    }
+    /// Returns position of the (first) definition of the `label`.
+    pub fn label(&self, label: LabelIdentifierLiteral) -> Option<InstructionPosition> {
+        self.labels.get(&label).copied()
+    }
+    /// Returns all the defined labels and their positions in order of the program.
+    pub fn labels(&self) -> Vec<(LabelIdentifierLiteral, InstructionPosition)> {
+        let mut labels: Vec<_> = self.labels.iter().map(|(&l, &p)| (l, p)).collect();
+        labels.sort_by_key(|&(_, position)| position);
+        labels
+    }
+    /// Returns all the jumps in order of the program.
+    pub fn jumps(&self) -> &[Jump] {
+        &self.jumps
+    }
+    /// Returns the resolved target of the jump at the given `position`.
+    ///
+    /// Returns `None` if there is no jump at the `position` or its label isn't defined.
+    pub fn target(&self, position: InstructionPosition) -> Option<InstructionPosition> {
+        let i = self
+            .jumps
+            .binary_search_by_key(&position, |jump| jump.position)
+            .ok()?;
+        self.jumps[i].target
+    }
+    /// Returns all the diagnostics in order of the program.
+    pub fn diagnostics(&self) -> &[Diagnostics] {
+        &self.diagnostics
+    }
+}
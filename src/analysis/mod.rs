@@ -0,0 +1,12 @@
+//! Static analyses of [internal](crate::formats::internal) programs.
+//!
+//! Available submodules:
+//! * [cfg](mod@cfg) - control-flow graph and unreachable code detection
+//! * [diagnostics] - diagnostics for all the analyses
+//! * [dot] - Graphviz DOT export of the control-flow graph
+//! * [labels] - label resolution and validation
+
+pub mod cfg;
+pub mod diagnostics;
+pub mod dot;
+pub mod labels;
@@ -0,0 +1,224 @@
+//! Command-line arguments parsing.
+
+use std::{error::Error, fmt, fs, io, io::Read, io::Write, path::Path};
+
+/// Options which take a value.
+const VALUE_OPTIONS: [&str; 7] = [
+    "--from", "--to", "-o", "--output", "--color", "--world", "--at",
+];
+/// Options which don't take a value.
+const FLAG_OPTIONS: [&str; 2] = ["--json", "--all"];
+
+// region: errors
+
+/// An error which stops the command.
+#[derive(Debug)]
+pub enum CliError {
+    /// Illegal command-line arguments.
+    Usage(String),
+    /// Failed to read or write the file (`None` stands for stdin or stdout).
+    Io(Option<String>, io::Error),
+}
+
+impl CliError {
+    pub fn usage(details: impl Into<String>) -> Self {
+        Self::Usage(details.into())
+    }
+    /// Whether this error is caused by illegal command-line arguments.
+    pub fn is_usage(&self) -> bool {
+        matches!(self, Self::Usage(_))
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Usage(details) => write!(f, "{}", details),
+            Self::Io(Some(path), e) => write!(f, "{}: {}", path, e),
+            Self::Io(None, e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for CliError {}
+
+// endregion: errors
+
+/// A program format supported by the tool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// New Text format.
+    Ntf,
+    /// Assembly-like format.
+    Asm,
+    /// Share code of the binary format.
+    Share,
+}
+
+impl Format {
+    /// Parses the `--from`/`--to` option value.
+    fn from_name(name: &str) -> Result<Self, CliError> {
+        match name {
+            "ntf" => Ok(Self::Ntf),
+            "asm" => Ok(Self::Asm),
+            "share" => Ok(Self::Share),
+            _ => Err(CliError::usage(format!("unknown format `{}`", name))),
+        }
+    }
+    /// Guesses the format by the file extension.
+    fn from_path(path: &str) -> Option<Self> {
+        match Path::new(path).extension()?.to_str()? {
+            "ntf" => Some(Self::Ntf),
+            "m3a" | "asm" => Some(Self::Asm),
+            _ => None,
+        }
+    }
+}
+
+/// When to use colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Auto,
+    Always,
+    Never,
+}
+
+/// Parsed arguments of a command (the command itself excluded).
+#[derive(Debug, Default)]
+pub struct Args {
+    input: Option<String>,
+    options: Vec<(String, String)>,
+    flags: Vec<String>,
+}
+
+impl Args {
+    /// Parses the arguments following the command.
+    ///
+    /// Accepts at most one positional argument (the input). Both `--opt value` and `--opt=value`
+    /// are allowed.
+    pub fn parse(mut argv: impl Iterator<Item = String>) -> Result<Self, CliError> {
+        let mut args = Self::default();
+        while let Some(arg) = argv.next() {
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) if name.starts_with('-') => (name.to_string(), Some(value)),
+                _ => (arg.clone(), None),
+            };
+            if VALUE_OPTIONS.contains(&name.as_str()) {
+                let value = match inline_value {
+                    Some(value) => value.to_string(),
+                    None => argv
+                        .next()
+                        .ok_or_else(|| CliError::usage(format!("`{}` requires a value", name)))?,
+                };
+                args.options.push((name, value));
+            } else if FLAG_OPTIONS.contains(&name.as_str()) && inline_value.is_none() {
+                args.flags.push(name);
+            } else if arg.starts_with('-') && arg != "-" {
+                return Err(CliError::usage(format!("unknown option `{}`", arg)));
+            } else if args.input.replace(arg).is_some() {
+                return Err(CliError::usage("only one input is allowed"));
+            }
+        }
+        Ok(args)
+    }
+    /// Returns the last value of the option (any of `names`).
+    fn option(&self, names: &[&str]) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(name, _)| names.contains(&name.as_str()))
+            .map(|(_, value)| value.as_str())
+    }
+    /// Whether the flag is set.
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|flag| flag == name)
+    }
+    /// Fails if any of the options not in `allowed` is set.
+    pub fn allow(&self, allowed: &[&str]) -> Result<(), CliError> {
+        let names = self.options.iter().map(|(name, _)| name).chain(&self.flags);
+        match names
+            .into_iter()
+            .find(|name| !allowed.contains(&name.as_str()))
+        {
+            Some(name) => Err(CliError::usage(format!(
+                "option `{}` isn't allowed here",
+                name
+            ))),
+            None => Ok(()),
+        }
+    }
+    /// Returns the `--from` format or guesses it by the input file extension. Defaults to
+    /// [`Format::Ntf`].
+    pub fn from(&self) -> Result<Format, CliError> {
+        match self.option(&["--from"]) {
+            Some(name) => Format::from_name(name),
+            None => Ok(self
+                .input
+                .as_deref()
+                .and_then(Format::from_path)
+                .unwrap_or(Format::Ntf)),
+        }
+    }
+    /// Returns the required `--to` format.
+    pub fn to(&self) -> Result<Format, CliError> {
+        let name = self
+            .option(&["--to"])
+            .ok_or_else(|| CliError::usage("`--to` is required"))?;
+        Format::from_name(name)
+    }
+    /// Returns the `--color` option value.
+    pub fn color(&self) -> Result<Color, CliError> {
+        match self.option(&["--color"]) {
+            None | Some("auto") => Ok(Color::Auto),
+            Some("always") => Ok(Color::Always),
+            Some("never") => Ok(Color::Never),
+            Some(when) => Err(CliError::usage(format!(
+                "unknown `--color` value `{}`",
+                when
+            ))),
+        }
+    }
+    /// Returns the `--world` map path.
+    pub fn world(&self) -> Option<&str> {
+        self.option(&["--world"])
+    }
+    /// Returns the `--at` coordinates (`x,y`). Defaults to `(0, 0)`.
+    pub fn at(&self) -> Result<(i32, i32), CliError> {
+        let Some(at) = self.option(&["--at"]) else {
+            return Ok((0, 0));
+        };
+        at.split_once(',')
+            .and_then(|(x, y)| Some((x.trim().parse().ok()?, y.trim().parse().ok()?)))
+            .ok_or_else(|| CliError::usage(format!("illegal `--at` value `{}`", at)))
+    }
+    /// Whether the input is stdin.
+    pub fn is_stdin_input(&self) -> bool {
+        matches!(self.input.as_deref(), None | Some("-"))
+    }
+    /// Reads the whole input (a file or stdin).
+    pub fn read_input(&self) -> Result<String, CliError> {
+        match self.input.as_deref() {
+            None | Some("-") => {
+                let mut s = String::new();
+                io::stdin()
+                    .read_to_string(&mut s)
+                    .map_err(|e| CliError::Io(None, e))?;
+                Ok(s)
+            }
+            Some(path) => {
+                fs::read_to_string(path).map_err(|e| CliError::Io(Some(path.to_string()), e))
+            }
+        }
+    }
+    /// Writes the whole output (to the `--output` file or stdout).
+    pub fn write_output(&self, data: &[u8]) -> Result<(), CliError> {
+        match self.option(&["-o", "--output"]) {
+            None | Some("-") => io::stdout()
+                .write_all(data)
+                .map_err(|e| CliError::Io(None, e)),
+            Some(path) => {
+                fs::write(path, data).map_err(|e| CliError::Io(Some(path.to_string()), e))
+            }
+        }
+    }
+}
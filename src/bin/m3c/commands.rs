@@ -0,0 +1,274 @@
+//! Implementations of the commands.
+//!
+//! Every command returns `Ok(false)` if the input has errors (diagnostics are already printed).
+
+use std::fs;
+use std::io::{self, IsTerminal};
+
+use m3c::analysis::dot::to_dot;
+use m3c::formats::custom::assembly::diagnostics::Diagnostic as _;
+use m3c::formats::internal::{InstructionId, Program};
+use m3c::formats::native::new::diagnostics::{to_json, Diagnostic, Renderer, Severity};
+use m3c::serialization::custom::assembly;
+use m3c::serialization::custom::binary::share;
+use m3c::serialization::native::new::{TextFormatDeserializerV2, TextFormatSerializer};
+use m3c::simulation::debugger::Debugger;
+use m3c::simulation::robot::Robot;
+use m3c::simulation::world::{Cell, World};
+use m3c::simulation::Simulator;
+
+use crate::args::{Args, CliError, Color, Format};
+use crate::debug;
+
+/// Indent of instructions in assembly-like format.
+const ASM_INDENT: &str = "        ";
+/// Width and height of the world `m3c debug` uses without `--world`.
+const DEFAULT_WORLD_SIZE: usize = 32;
+
+/// `m3c convert`.
+pub fn convert(args: Args) -> Result<bool, CliError> {
+    args.allow(&["--from", "--to", "-o", "--output"])?;
+    let (from, to) = (args.from()?, args.to()?);
+    let source = args.read_input()?;
+    let Some(program) = load(from, &source, use_color(Color::Auto, &io::stderr())) else {
+        return Ok(false);
+    };
+    let Some(output) = save(to, &program) else {
+        return Ok(false);
+    };
+    args.write_output(&output)?;
+    Ok(true)
+}
+
+/// `m3c check`.
+pub fn check(args: Args) -> Result<bool, CliError> {
+    args.allow(&["--json", "--color"])?;
+    let color = args.color()?;
+    let source = args.read_input()?;
+
+    let mut program = Program::default();
+    let diagnostics = TextFormatDeserializerV2::new(&source).deserialize(&mut program);
+    let errors = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.severity() == Severity::Error)
+        .count();
+
+    if args.flag("--json") {
+        println!("{}", to_json(&source, &diagnostics));
+    } else if !diagnostics.is_empty() {
+        let renderer = Renderer::new(&source).with_color(use_color(color, &io::stdout()));
+        println!("{}", renderer.render(&diagnostics));
+        println!(
+            "{} error(s), {} warning(s)",
+            errors,
+            diagnostics.len() - errors
+        );
+    }
+    Ok(errors == 0)
+}
+
+/// `m3c fmt`.
+pub fn fmt(args: Args) -> Result<bool, CliError> {
+    args.allow(&["-o", "--output"])?;
+    let source = args.read_input()?;
+    let Some(program) = load(Format::Ntf, &source, use_color(Color::Auto, &io::stderr())) else {
+        return Ok(false);
+    };
+    let Some(output) = save(Format::Ntf, &program) else {
+        return Ok(false);
+    };
+    args.write_output(&output)?;
+    Ok(true)
+}
+
+/// `m3c dump`.
+pub fn dump(args: Args) -> Result<bool, CliError> {
+    args.allow(&["--from", "--all"])?;
+    let from = args.from()?;
+    let source = args.read_input()?;
+    let Some(program) = load(from, &source, use_color(Color::Auto, &io::stderr())) else {
+        return Ok(false);
+    };
+    print!("{}", grid(&program, args.flag("--all")));
+    Ok(true)
+}
+
+/// `m3c dot`.
+pub fn dot(args: Args) -> Result<bool, CliError> {
+    args.allow(&["--from", "-o", "--output"])?;
+    let from = args.from()?;
+    let source = args.read_input()?;
+    let Some(program) = load(from, &source, use_color(Color::Auto, &io::stderr())) else {
+        return Ok(false);
+    };
+    args.write_output(to_dot(&program).as_bytes())?;
+    Ok(true)
+}
+
+/// `m3c debug`.
+pub fn debug(args: Args) -> Result<bool, CliError> {
+    args.allow(&["--from", "--world", "--at"])?;
+    if args.is_stdin_input() {
+        return Err(CliError::usage(
+            "`debug` reads commands from stdin, INPUT must be a file",
+        ));
+    }
+    let from = args.from()?;
+    let (x, y) = args.at()?;
+    let source = args.read_input()?;
+    let Some(program) = load(from, &source, use_color(Color::Auto, &io::stderr())) else {
+        return Ok(false);
+    };
+    let world = match args.world() {
+        None => World::new(DEFAULT_WORLD_SIZE, DEFAULT_WORLD_SIZE),
+        Some(path) => {
+            let map =
+                fs::read_to_string(path).map_err(|e| CliError::Io(Some(path.to_string()), e))?;
+            match map.parse() {
+                Ok(world) => world,
+                Err(e) => {
+                    eprintln!("m3c: {}: {}", path, e);
+                    return Ok(false);
+                }
+            }
+        }
+    };
+    if world.get(x, y) == Cell::Dead {
+        return Err(CliError::usage(format!(
+            "the robot at ({}, {}) is beyond the world edges",
+            x, y
+        )));
+    }
+
+    let mut debugger = Debugger::new(Simulator::new(&program, world, Robot::new(x, y)));
+    let prompt = if io::stdin().is_terminal() {
+        "(m3c) "
+    } else {
+        ""
+    };
+    debug::run(&mut debugger, io::stdin().lock(), &mut io::stdout(), prompt)
+        .map_err(|e| CliError::Io(None, e))?;
+    Ok(true)
+}
+
+/// Deserializes the program printing diagnostics to stderr.
+///
+/// Returns `None` if there are errors.
+fn load(format: Format, source: &str, color: bool) -> Option<Program> {
+    let mut program = Program::default();
+    match format {
+        Format::Ntf => {
+            let diagnostics = TextFormatDeserializerV2::new(source).deserialize(&mut program);
+            if !diagnostics.is_empty() {
+                eprintln!(
+                    "{}",
+                    Renderer::new(source).with_color(color).render(&diagnostics)
+                );
+            }
+            if diagnostics
+                .iter()
+                .any(|diagnostic| diagnostic.severity() == Severity::Error)
+            {
+                return None;
+            }
+        }
+        Format::Asm => {
+            let diagnostics = assembly::Deserializer::new(source).deserialize(&mut program);
+            for diagnostic in &diagnostics {
+                eprint!("{}", diagnostic.what());
+            }
+            if !diagnostics.is_empty() {
+                return None;
+            }
+        }
+        Format::Share => {
+            if let Err(e) = share::decode(source, &mut program) {
+                eprintln!("error: illegal share code: {}", e);
+                return None;
+            }
+        }
+    }
+    Some(program)
+}
+
+/// Serializes the program. Prints the error and returns `None` if the format can't represent it.
+fn save(format: Format, program: &Program) -> Option<Vec<u8>> {
+    Some(match format {
+        Format::Ntf => {
+            let mut buf = vec![];
+            if let Err(e) = TextFormatSerializer::new(program).serialize(&mut buf) {
+                eprintln!("error: {}", e);
+                return None;
+            }
+            buf
+        }
+        Format::Asm => {
+            let mut s = String::new();
+            assembly::Serializer::new(program).serialize_to_string(&mut s, ASM_INDENT);
+            s.into_bytes()
+        }
+        Format::Share => format!("{}\n", share::encode(program)).into_bytes(),
+    })
+}
+
+/// Whether to use colors for the given stream.
+fn use_color(color: Color, stream: &impl IsTerminal) -> bool {
+    match color {
+        Color::Always => true,
+        Color::Never => false,
+        Color::Auto => stream.is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+    }
+}
+
+/// Returns a page/row grid view of the program.
+///
+/// Every instruction is shown in New Text format, empty ones are shown as `.` and ones without a
+/// spelling in New Text format are shown in assembly-like format. Empty pages are skipped unless
+/// `all` (the first page is always shown).
+fn grid(program: &Program, all: bool) -> String {
+    let cells: Vec<String> = program
+        .instruction_positions()
+        .map(|(_, ins)| match ins.id() {
+            InstructionId::Empty => ".".to_string(),
+            _ => {
+                let mut buf = vec![];
+                match TextFormatSerializer::serialize_instruction(&ins, &mut buf) {
+                    Ok(()) => String::from_utf8(buf).expect("New Text format is UTF-8"),
+                    Err(_) => ins.to_string(),
+                }
+            }
+        })
+        .collect();
+    let width = cells
+        .iter()
+        .map(|cell| cell.chars().count())
+        .max()
+        .unwrap_or(1)
+        .max(2);
+    let per_page = Program::ROWS_PER_PAGE * Program::INSTRUCTIONS_PER_ROW;
+
+    let mut out = String::new();
+    for (page, page_cells) in cells.chunks(per_page).enumerate() {
+        if !all && page != 0 && page_cells.iter().all(|cell| cell == ".") {
+            continue;
+        }
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out += &format!("page {}\n   |", page);
+        for column in 0..Program::INSTRUCTIONS_PER_ROW {
+            out += &format!(" {:<width$}", column, width = width);
+        }
+        out.truncate(out.trim_end().len());
+        out.push('\n');
+        for (row, row_cells) in page_cells.chunks(Program::INSTRUCTIONS_PER_ROW).enumerate() {
+            out += &format!("{:>2} |", row);
+            for cell in row_cells {
+                out += &format!(" {:<width$}", cell, width = width);
+            }
+            out.truncate(out.trim_end().len());
+            out.push('\n');
+        }
+    }
+    out
+}
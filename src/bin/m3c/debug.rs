@@ -0,0 +1,285 @@
+//! The interactive session of `m3c debug`.
+
+use std::io::{self, BufRead, Write};
+
+use m3c::formats::internal::literals::{Literal, StringLiteral};
+use m3c::formats::internal::{Instruction, InstructionPosition};
+use m3c::interpreter::BoolMode;
+use m3c::serialization::native::new::TextFormatSerializer;
+use m3c::simulation::debugger::{Debugger, Stop};
+use m3c::simulation::robot::Direction;
+use m3c::simulation::Status;
+
+/// The maximum number of instructions `continue` executes.
+const MAX_RESUME_STEPS: usize = 1_000_000;
+
+const HELP: &str = "\
+Commands:
+  s, step [N]         execute N (default: 1) instructions
+  c, continue         execute instructions up to a breakpoint
+  b, break [WHERE]    add a breakpoint at WHERE or list the breakpoints
+  d, delete WHERE     remove the breakpoint at WHERE
+  w, where            print the next instruction
+  bt, stack           print the return positions and the response handler
+  vars                print the variables
+  modes               print the modes and the condition
+  world               print the world and the robot
+  q, quit             stop debugging
+WHERE is a `page:row:column` position or a `DEBUG_BREAK` tag.
+";
+
+/// A breakpoint given in a command.
+enum Location {
+    Position(InstructionPosition),
+    Tag(StringLiteral),
+}
+
+/// Runs commands from the `input` until it ends or `quit`.
+///
+/// Prints `prompt` before every command.
+pub fn run<R, W>(debugger: &mut Debugger, input: R, output: &mut W, prompt: &str) -> io::Result<()>
+where
+    R: BufRead,
+    W: Write,
+{
+    writeln!(output, "{}", location(debugger))?;
+    let mut lines = input.lines();
+    loop {
+        write!(output, "{}", prompt)?;
+        output.flush()?;
+        let Some(line) = lines.next().transpose()? else {
+            return Ok(());
+        };
+        let mut words = line.split_whitespace();
+        let Some(command) = words.next() else {
+            continue;
+        };
+        let argument = words.next();
+        let reply = match command {
+            "s" | "step" => match argument.map(str::parse).unwrap_or(Ok(1)) {
+                Ok(count) => {
+                    let stop = debugger.step(count);
+                    stopped(debugger, stop)
+                }
+                Err(_) => "the count must be a number".to_string(),
+            },
+            "c" | "continue" => {
+                let stop = debugger.resume(MAX_RESUME_STEPS);
+                stopped(debugger, stop)
+            }
+            "b" | "break" => match argument.map(parse_location) {
+                None => breakpoints(debugger),
+                Some(Some(Location::Position(position))) => {
+                    debugger.add_breakpoint(position);
+                    format!("breakpoint at {}", position.custom_format())
+                }
+                Some(Some(Location::Tag(tag))) => {
+                    debugger.add_tag(tag);
+                    format!("breakpoint at `{}`", literal(tag))
+                }
+                Some(None) => "illegal breakpoint".to_string(),
+            },
+            "d" | "delete" => match argument.and_then(parse_location) {
+                Some(Location::Position(position)) if debugger.remove_breakpoint(position) => {
+                    format!("removed {}", position.custom_format())
+                }
+                Some(Location::Tag(tag)) if debugger.remove_tag(tag) => {
+                    format!("removed `{}`", literal(tag))
+                }
+                _ => "no such breakpoint".to_string(),
+            },
+            "w" | "where" => location(debugger),
+            "bt" | "stack" => stack(debugger),
+            "vars" => variables(debugger),
+            "modes" => modes(debugger),
+            "world" => world(debugger),
+            "h" | "help" => HELP.trim_end().to_string(),
+            "q" | "quit" => return Ok(()),
+            _ => format!("unknown command `{}`, type `help`", command),
+        };
+        writeln!(output, "{}", reply)?;
+    }
+}
+
+/// Parses a `page:row:column` position or a tag.
+fn parse_location(s: &str) -> Option<Location> {
+    if s.contains(':') {
+        let parts: Vec<u8> = s
+            .split(':')
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .ok()?;
+        let &[page, row, column] = &parts[..] else {
+            return None;
+        };
+        return InstructionPosition::new(page, row, column)
+            .ok()
+            .map(Location::Position);
+    }
+    s.parse::<StringLiteral>().ok().map(Location::Tag)
+}
+
+/// Returns the literal as a string.
+fn literal(literal: impl Literal) -> String {
+    let mut s = String::new();
+    literal.dumps_to(&mut s);
+    s
+}
+
+/// Returns the instruction in New Text format, or in assembly-like format if it has no spelling
+/// in New Text format.
+fn instruction(instruction: &Instruction) -> String {
+    let mut buf = vec![];
+    match TextFormatSerializer::serialize_instruction(instruction, &mut buf) {
+        Ok(()) => String::from_utf8(buf).expect("New Text format is UTF-8"),
+        Err(_) => instruction.to_string(),
+    }
+}
+
+/// Returns the next instruction and its position.
+fn location(debugger: &Debugger) -> String {
+    let (position, next) = debugger.next_instruction();
+    format!(
+        "{}  {}  (tick {})",
+        position.custom_format(),
+        instruction(&next),
+        debugger.simulator().ticks()
+    )
+}
+
+/// Returns why the debugger stopped and where.
+fn stopped(debugger: &Debugger, stop: Stop) -> String {
+    match stop {
+        Stop::Step => location(debugger),
+        Stop::Breakpoint(_) => format!("breakpoint\n{}", location(debugger)),
+        Stop::Tag(tag) => format!("breakpoint `{}`\n{}", literal(tag), location(debugger)),
+        Stop::Halted(Status::Finished) => "the program is finished".to_string(),
+        Stop::Halted(_) => "the robot is destroyed".to_string(),
+    }
+}
+
+/// Returns the list of the breakpoints.
+fn breakpoints(debugger: &Debugger) -> String {
+    let mut tags: Vec<String> = debugger
+        .tags()
+        .map(|tag| format!("`{}`", literal(tag)))
+        .collect();
+    tags.sort();
+    let list: Vec<String> = debugger
+        .breakpoints()
+        .map(|position| position.custom_format())
+        .chain(tags)
+        .collect();
+    if list.is_empty() {
+        "no breakpoints".to_string()
+    } else {
+        list.join("\n")
+    }
+}
+
+/// Returns the return positions (the last one first) and the response handler.
+fn stack(debugger: &Debugger) -> String {
+    let interpreter = debugger.simulator().interpreter();
+    let positions = |positions: &mut dyn Iterator<Item = InstructionPosition>| {
+        let list: Vec<String> = positions.map(|p| p.custom_format()).collect();
+        if list.is_empty() {
+            "-".to_string()
+        } else {
+            list.join(" ")
+        }
+    };
+    format!(
+        "GOSUB:   {}\nGOSUBF:  {}\nGOSUB1:  {}\nON_RESP: {}",
+        positions(&mut interpreter.calls().iter().rev().copied()),
+        positions(&mut interpreter.calls_f().iter().rev().copied()),
+        positions(&mut interpreter.call1().into_iter()),
+        positions(&mut interpreter.handler().into_iter()),
+    )
+}
+
+/// Returns the variables sorted by name.
+fn variables(debugger: &Debugger) -> String {
+    let mut list: Vec<(String, i32)> = debugger
+        .simulator()
+        .interpreter()
+        .variables()
+        .map(|(name, value)| (literal(name), value))
+        .collect();
+    if list.is_empty() {
+        return "no variables".to_string();
+    }
+    list.sort();
+    list.iter()
+        .map(|(name, value)| format!("{} = {}", name, value))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns the mode flags, the condition and the debug tag.
+fn modes(debugger: &Debugger) -> String {
+    let (robot, interpreter) = (
+        debugger.simulator().robot(),
+        debugger.simulator().interpreter(),
+    );
+    let on = |on: bool| if on { "on" } else { "off" };
+    let inverted: Vec<&str> = Direction::ALL
+        .iter()
+        .filter(|&&direction| robot.is_inverted(direction))
+        .map(|direction| match direction {
+            Direction::W => "W",
+            Direction::A => "A",
+            Direction::S => "S",
+            Direction::D => "D",
+        })
+        .collect();
+    let condition = match interpreter.condition() {
+        None => "-",
+        Some(true) => "true",
+        Some(false) => "false",
+    };
+    let bool_mode = match interpreter.bool_mode() {
+        BoolMode::Or => "OR",
+        BoolMode::And => "AND",
+    };
+    format!(
+        "autodigg:   {}\naggression: {}\nhand mode:  {}\ninverted:   {}\nbool mode:  {}\ncondition:  {}\ndebug tag:  {}",
+        on(robot.autodigg()),
+        on(robot.aggression()),
+        on(robot.hand_mode()),
+        if inverted.is_empty() { "-".to_string() } else { inverted.join(" ") },
+        bool_mode,
+        condition,
+        interpreter.debug_tag().map_or("-".to_string(), literal),
+    )
+}
+
+/// Returns the world map with the robot as `@` and the robot's state.
+fn world(debugger: &Debugger) -> String {
+    let simulator = debugger.simulator();
+    let robot = simulator.robot();
+    let (x, y) = robot.position();
+    let mut map: Vec<Vec<char>> = simulator
+        .world()
+        .to_string()
+        .lines()
+        .map(|line| line.chars().collect())
+        .collect();
+    if let Some(cell) = usize::try_from(y)
+        .ok()
+        .and_then(|y| map.get_mut(y))
+        .zip(usize::try_from(x).ok())
+        .and_then(|(row, x)| row.get_mut(x))
+    {
+        *cell = '@';
+    }
+    let map: Vec<String> = map.into_iter().map(String::from_iter).collect();
+    format!(
+        "{}\nrobot at ({}, {}) facing {:?}, {} hp, {} crystals",
+        map.join("\n"),
+        x,
+        y,
+        robot.facing(),
+        robot.hp(),
+        robot.crystals()
+    )
+}
@@ -0,0 +1,83 @@
+//! `m3c` - a command-line tool to convert, check, format, inspect and debug programs.
+//!
+//! Run `m3c help` to see the usage.
+
+use std::process::ExitCode;
+
+mod args;
+mod commands;
+mod debug;
+
+use args::{Args, CliError};
+
+const USAGE: &str = "\
+Usage: m3c <COMMAND> [OPTIONS] [INPUT]
+
+Reads INPUT (a file path, `-` or nothing for stdin) and writes the result to stdout.
+
+Commands:
+  convert  Converts a program between formats
+             --from <FORMAT>    format of INPUT (default: by extension, else `ntf`)
+             --to <FORMAT>      format of the output (required)
+             -o, --output <PATH>  write to PATH instead of stdout
+  check    Prints New Text format diagnostics, fails if there are errors
+             --json             print diagnostics as a JSON array
+             --color <WHEN>     `auto` (default), `always` or `never`
+  fmt      Canonicalizes a New Text format program
+             -o, --output <PATH>  write to PATH instead of stdout
+  dump     Prints a page/row grid view of a program
+             --from <FORMAT>    format of INPUT (default: by extension, else `ntf`)
+             --all              print empty pages too
+  dot      Prints the control-flow graph in Graphviz DOT format
+             --from <FORMAT>    format of INPUT (default: by extension, else `ntf`)
+             -o, --output <PATH>  write to PATH instead of stdout
+  debug    Debugs a program in a simulated world, reads commands (`help`) from stdin
+             --from <FORMAT>    format of INPUT (default: by extension, else `ntf`)
+             --world <PATH>     the world map (default: an empty 32x32 world)
+             --at <X,Y>         the robot coordinates (default: `0,0`)
+  help     Prints this message
+
+Formats:
+  ntf      New Text format (`$...`)
+  asm      assembly-like format (`.m3a` files)
+  share    share code: the binary format in URL-safe base64 with a checksum
+";
+
+/// Exit code for diagnostics with errors.
+const EXIT_FAILURE: u8 = 1;
+/// Exit code for illegal command-line arguments.
+const EXIT_USAGE: u8 = 2;
+
+fn main() -> ExitCode {
+    let mut argv = std::env::args().skip(1);
+    let Some(command) = argv.next() else {
+        eprint!("{}", USAGE);
+        return ExitCode::from(EXIT_USAGE);
+    };
+    let result = Args::parse(argv).and_then(|args| match command.as_str() {
+        "convert" => commands::convert(args),
+        "check" => commands::check(args),
+        "fmt" => commands::fmt(args),
+        "dump" => commands::dump(args),
+        "dot" => commands::dot(args),
+        "debug" => commands::debug(args),
+        "help" | "-h" | "--help" => {
+            print!("{}", USAGE);
+            Ok(true)
+        }
+        _ => Err(CliError::usage(format!("unknown command `{}`", command))),
+    });
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(EXIT_FAILURE),
+        Err(e) => {
+            eprintln!("m3c: {}", e);
+            if e.is_usage() {
+                eprintln!("Run `m3c help` for usage.");
+                ExitCode::from(EXIT_USAGE)
+            } else {
+                ExitCode::from(EXIT_FAILURE)
+            }
+        }
+    }
+}
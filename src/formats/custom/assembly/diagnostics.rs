@@ -0,0 +1,257 @@
+//! Module for assembly-like format's diagnostics.
+
+use crate::utils::CharPosition;
+
+// region: general
+
+/// A trait for diagnostic objects.
+pub trait Diagnostic {
+    /// Returns this diagnostic's [id](DiagnosticId).
+    fn id(&self) -> DiagnosticId;
+    /// Returns this diagnostic's [id](DiagnosticId) prefixed with letter `A` (stands for Assembly
+    /// format).
+    fn prefixed_id(&self) -> String {
+        let id: u8 = self.id().into();
+        format_args!("A{:0>2}", id).to_string()
+    }
+    /// Returns this diagnostic's message.
+    fn what(&self) -> String;
+    /// Returns this diagnostic's position in source code.
+    ///
+    /// Returns the start position for diagnostics with a span.
+    fn position(&self) -> CharPosition;
+}
+
+/// Represents all id of [Diagnostic].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum DiagnosticId {
+    UnknownMnemonic = 1,
+    IllegalOperand = 2,
+    IllegalLiteral = 3,
+    ProgramOverflow = 4,
+}
+
+impl From<DiagnosticId> for u8 {
+    fn from(id: DiagnosticId) -> Self {
+        id as u8
+    }
+}
+
+/// An enumeration of all [Diagnostic]s.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Diagnostics {
+    UnknownMnemonic(UnknownMnemonic),
+    IllegalOperand(IllegalOperand),
+    IllegalLiteral(IllegalLiteral),
+    ProgramOverflow(ProgramOverflow),
+}
+
+macro_rules! impl_trait_for_diagnostics {
+    ($method:ident, $rtype:ident) => {
+        fn $method(&self) -> $rtype {
+            match self {
+                Self::UnknownMnemonic(x) => x.$method(),
+                Self::IllegalOperand(x) => x.$method(),
+                Self::IllegalLiteral(x) => x.$method(),
+                Self::ProgramOverflow(x) => x.$method(),
+            }
+        }
+    };
+}
+
+impl Diagnostic for Diagnostics {
+    impl_trait_for_diagnostics!(id, DiagnosticId);
+    impl_trait_for_diagnostics!(prefixed_id, String);
+    impl_trait_for_diagnostics!(what, String);
+    impl_trait_for_diagnostics!(position, CharPosition);
+}
+
+/// Formats the given span as `line:col` or `line:col-line:col`.
+fn format_span(start: CharPosition, end: CharPosition) -> String {
+    if start == end {
+        start.custom_format()
+    } else {
+        format_args!("{}-{}", start.custom_format(), end.custom_format()).to_string()
+    }
+}
+
+// endregion: general
+
+/// The word at the start of the line is neither a known mnemonic nor a label definition.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnknownMnemonic {
+    start: CharPosition,
+    end: CharPosition,
+}
+
+impl UnknownMnemonic {
+    pub const ID: DiagnosticId = DiagnosticId::UnknownMnemonic;
+    pub fn new(start: CharPosition, end: CharPosition) -> Self {
+        Self { start, end }
+    }
+    /// Returns position of the first char of the mnemonic.
+    pub fn start(&self) -> CharPosition {
+        self.start
+    }
+    /// Returns position of the last char of the mnemonic.
+    pub fn end(&self) -> CharPosition {
+        self.end
+    }
+}
+
+impl Diagnostic for UnknownMnemonic {
+    fn id(&self) -> DiagnosticId {
+        Self::ID
+    }
+    fn what(&self) -> String {
+        format_args!(
+            "{}: [{}] unknown mnemonic found\n",
+            format_span(self.start, self.end),
+            self.prefixed_id()
+        )
+        .to_string()
+    }
+    /// Returns position of the first char of the mnemonic.
+    fn position(&self) -> CharPosition {
+        self.start()
+    }
+}
+
+impl From<UnknownMnemonic> for Diagnostics {
+    fn from(x: UnknownMnemonic) -> Self {
+        Diagnostics::UnknownMnemonic(x)
+    }
+}
+
+/// Operands are missing, malformed or unexpected for the instruction.
+///
+/// The span covers everything after the mnemonic up to the end of the line (or the comment).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IllegalOperand {
+    start: CharPosition,
+    end: CharPosition,
+}
+
+impl IllegalOperand {
+    pub const ID: DiagnosticId = DiagnosticId::IllegalOperand;
+    pub fn new(start: CharPosition, end: CharPosition) -> Self {
+        Self { start, end }
+    }
+    /// Returns position of the first char of the operands.
+    pub fn start(&self) -> CharPosition {
+        self.start
+    }
+    /// Returns position of the last char of the operands.
+    pub fn end(&self) -> CharPosition {
+        self.end
+    }
+}
+
+impl Diagnostic for IllegalOperand {
+    fn id(&self) -> DiagnosticId {
+        Self::ID
+    }
+    fn what(&self) -> String {
+        format_args!(
+            "{}: [{}] illegal operands found\n",
+            format_span(self.start, self.end),
+            self.prefixed_id()
+        )
+        .to_string()
+    }
+    /// Returns position of the first char of the operands.
+    fn position(&self) -> CharPosition {
+        self.start()
+    }
+}
+
+impl From<IllegalOperand> for Diagnostics {
+    fn from(x: IllegalOperand) -> Self {
+        Diagnostics::IllegalOperand(x)
+    }
+}
+
+/// A literal is well-formed, but its value isn't allowed (it's too long or out of range).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IllegalLiteral {
+    start: CharPosition,
+    end: CharPosition,
+}
+
+impl IllegalLiteral {
+    pub const ID: DiagnosticId = DiagnosticId::IllegalLiteral;
+    pub fn new(start: CharPosition, end: CharPosition) -> Self {
+        Self { start, end }
+    }
+    /// Returns position of the first char of the literal.
+    pub fn start(&self) -> CharPosition {
+        self.start
+    }
+    /// Returns position of the last char of the literal.
+    pub fn end(&self) -> CharPosition {
+        self.end
+    }
+}
+
+impl Diagnostic for IllegalLiteral {
+    fn id(&self) -> DiagnosticId {
+        Self::ID
+    }
+    fn what(&self) -> String {
+        format_args!(
+            "{}: [{}] illegal literal found\n",
+            format_span(self.start, self.end),
+            self.prefixed_id()
+        )
+        .to_string()
+    }
+    /// Returns position of the first char of the literal.
+    fn position(&self) -> CharPosition {
+        self.start()
+    }
+}
+
+impl From<IllegalLiteral> for Diagnostics {
+    fn from(x: IllegalLiteral) -> Self {
+        Diagnostics::IllegalLiteral(x)
+    }
+}
+
+/// There are more instructions than the program can contain.
+///
+/// Points to the first instruction that doesn't fit into the program.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProgramOverflow {
+    position: CharPosition,
+}
+
+impl ProgramOverflow {
+    pub const ID: DiagnosticId = DiagnosticId::ProgramOverflow;
+    pub fn new(position: CharPosition) -> Self {
+        Self { position }
+    }
+}
+
+impl Diagnostic for ProgramOverflow {
+    fn id(&self) -> DiagnosticId {
+        Self::ID
+    }
+    fn what(&self) -> String {
+        format_args!(
+            "{}: [{}] the program is full, the rest of instructions is ignored\n",
+            self.position.custom_format(),
+            self.prefixed_id()
+        )
+        .to_string()
+    }
+    fn position(&self) -> CharPosition {
+        self.position
+    }
+}
+
+impl From<ProgramOverflow> for Diagnostics {
+    fn from(x: ProgramOverflow) -> Self {
+        Diagnostics::ProgramOverflow(x)
+    }
+}
@@ -0,0 +1,6 @@
+//! Module for representation of assembly-like format.
+//!
+//! Available submodules:
+//! * [diagnostics] - diagnostics for assembly-like format.
+
+pub mod diagnostics;
@@ -0,0 +1,6 @@
+//! Module for representations of custom formats.
+//!
+//! Available submodules:
+//! * [assembly] - assembly-like format.
+
+pub mod assembly;
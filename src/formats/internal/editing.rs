@@ -0,0 +1,473 @@
+//! Editing operations on a [`Program`]: inserting, deleting and moving cells, rows, pages and
+//! rectangular regions.
+//!
+//! Inserting shifts the following cells forward, so the cells at the end of the
+//! [scope](Scope) fall off. Deleting shifts them backward and fills the end of the scope with
+//! [Empty](InstructionId::Empty) instructions. Every operation which can lose instructions
+//! returns them as [`Removed`].
+//!
+//! # Examples
+//!
+//! ```
+//! use m3c::formats::internal::editing::Scope;
+//! use m3c::formats::internal::{Instruction, InstructionId, InstructionPosition, Program};
+//!
+//! let move_w = Instruction::new_simple(InstructionId::MoveW).unwrap();
+//! let end = Instruction::new_simple(InstructionId::End).unwrap();
+//!
+//! let mut program = Program::default();
+//! program[15] = end;
+//!
+//! let position = InstructionPosition::new(0, 0, 0).unwrap();
+//! let removed = program.insert_cell(position, move_w, Scope::Row);
+//! assert_eq!(move_w, program[0]);
+//! assert_eq!(vec![(InstructionPosition::new(0, 0, 15).unwrap(), end)], removed);
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+
+use super::{Instruction, InstructionId, InstructionPosition, Program};
+
+// region: errors
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RegionOutOfPageError {}
+
+impl RegionOutOfPageError {
+    const DETAILS: &'static str = "the region doesn't fit into the page";
+}
+
+impl fmt::Display for RegionOutOfPageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", Self::DETAILS)
+    }
+}
+
+impl Error for RegionOutOfPageError {}
+
+// endregion: errors
+
+/// Non-empty instructions lost by an edit with their positions before the edit, in the program
+/// order.
+pub type Removed = Vec<(InstructionPosition, Instruction)>;
+
+/// The part of the program cells are shifted within.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Scope {
+    /// Up to the end of the row.
+    Row,
+    /// Up to the end of the page.
+    Page,
+    /// Up to the end of the program.
+    Program,
+}
+
+impl Scope {
+    /// Returns the "flat index" the scope containing the cell at the given `index` ends at.
+    fn end(self, index: usize) -> usize {
+        let len = match self {
+            Self::Row => Program::INSTRUCTIONS_PER_ROW,
+            Self::Page => Program::INSTRUCTIONS_PER_PAGE,
+            Self::Program => Program::INSTRUCTIONS_PER_PROGRAM,
+        };
+        (index / len + 1) * len
+    }
+}
+
+/// A rectangular part of a page copied by [`Program::copy_region`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Region {
+    rows: usize,
+    columns: usize,
+    /// The instructions row by row.
+    instructions: Vec<Instruction>,
+}
+
+impl Region {
+    /// Returns the number of rows.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+    /// Returns the number of columns.
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+    /// Returns the instruction at the given `row` and `column` relative to the region, or `None`
+    /// if they are out of the region.
+    pub fn get(&self, row: usize, column: usize) -> Option<Instruction> {
+        if row >= self.rows || column >= self.columns {
+            return None;
+        }
+        Some(self.instructions[row * self.columns + column])
+    }
+}
+
+impl Program {
+    /// Number of instructions per page.
+    const INSTRUCTIONS_PER_PAGE: usize = Self::INSTRUCTIONS_PER_ROW * Self::ROWS_PER_PAGE;
+
+    /// Inserts the `instruction` at the given `position`, shifting the following cells of the
+    /// `scope` forward. The last cell of the `scope` falls off.
+    pub fn insert_cell(
+        &mut self,
+        position: InstructionPosition,
+        instruction: Instruction,
+        scope: Scope,
+    ) -> Removed {
+        let index = position.index();
+        let removed = self.insert_cells(index, 1, scope.end(index));
+        self.instructions[index] = instruction;
+        removed
+    }
+    /// Deletes the cell at the given `position`, shifting the following cells of the `scope`
+    /// backward. The deleted instruction is returned if it isn't empty.
+    pub fn delete_cell(&mut self, position: InstructionPosition, scope: Scope) -> Removed {
+        let index = position.index();
+        self.delete_cells(index, 1, scope.end(index))
+    }
+    /// Inserts an empty row before the given `row` of the `page`, shifting the following rows of
+    /// the `scope` forward. The last row of the `scope` falls off.
+    ///
+    /// With [`Scope::Row`] nothing is shifted, so the row is just cleared.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `page` or the `row` is out of range.
+    pub fn insert_row(&mut self, page: u8, row: u8, scope: Scope) -> Removed {
+        let index = row_index(page, row);
+        self.insert_cells(index, Self::INSTRUCTIONS_PER_ROW, scope.end(index))
+    }
+    /// Deletes the given `row` of the `page`, shifting the following rows of the `scope`
+    /// backward. The deleted instructions are returned.
+    ///
+    /// With [`Scope::Row`] nothing is shifted, so the row is just cleared.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `page` or the `row` is out of range.
+    pub fn delete_row(&mut self, page: u8, row: u8, scope: Scope) -> Removed {
+        let index = row_index(page, row);
+        self.delete_cells(index, Self::INSTRUCTIONS_PER_ROW, scope.end(index))
+    }
+    /// Inserts an empty page before the given `page`, shifting the following pages forward. The
+    /// last page falls off.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `page` is out of range.
+    pub fn insert_page(&mut self, page: u8) -> Removed {
+        self.insert_cells(
+            row_index(page, 0),
+            Self::INSTRUCTIONS_PER_PAGE,
+            Self::INSTRUCTIONS_PER_PROGRAM,
+        )
+    }
+    /// Deletes the given `page`, shifting the following pages backward. The deleted instructions
+    /// are returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `page` is out of range.
+    pub fn delete_page(&mut self, page: u8) -> Removed {
+        self.delete_cells(
+            row_index(page, 0),
+            Self::INSTRUCTIONS_PER_PAGE,
+            Self::INSTRUCTIONS_PER_PROGRAM,
+        )
+    }
+    /// Swaps the pages `a` and `b`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `a` or the `b` is out of range.
+    pub fn swap_pages(&mut self, a: u8, b: u8) {
+        let (a, b) = (row_index(a, 0), row_index(b, 0));
+        let (low, high) = (a.min(b), a.max(b));
+        if low == high {
+            return;
+        }
+        let (head, tail) = self.instructions.split_at_mut(high);
+        head[low..low + Self::INSTRUCTIONS_PER_PAGE]
+            .swap_with_slice(&mut tail[..Self::INSTRUCTIONS_PER_PAGE]);
+    }
+    /// Copies the region of the given number of `rows` and `columns` starting at the `origin`.
+    ///
+    /// # Errors
+    ///
+    /// If the region crosses the edge of the page a [`RegionOutOfPageError`] will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use m3c::formats::internal::{Instruction, InstructionId, InstructionPosition, Program};
+    ///
+    /// let mut program = Program::default();
+    /// program[17] = Instruction::new_simple(InstructionId::MoveW).unwrap();
+    ///
+    /// let region = program
+    ///     .copy_region(InstructionPosition::new(0, 1, 1).unwrap(), 2, 3)
+    ///     .unwrap();
+    /// let mut copy = Program::default();
+    /// copy.paste_region(InstructionPosition::new(5, 10, 13).unwrap(), &region)
+    ///     .unwrap();
+    /// assert_eq!(program[17], copy[InstructionPosition::new(5, 10, 13).unwrap()]);
+    ///
+    /// assert!(program
+    ///     .copy_region(InstructionPosition::new(0, 11, 0).unwrap(), 2, 1)
+    ///     .is_err());
+    /// ```
+    pub fn copy_region(
+        &self,
+        origin: InstructionPosition,
+        rows: usize,
+        columns: usize,
+    ) -> Result<Region, RegionOutOfPageError> {
+        let cells = region_cells(origin, rows, columns)?;
+        Ok(Region {
+            rows,
+            columns,
+            instructions: cells.map(|index| self.instructions[index]).collect(),
+        })
+    }
+    /// Pastes the `region` with its top-left corner at the given `position`. The overwritten
+    /// instructions are returned.
+    ///
+    /// # Errors
+    ///
+    /// If the region crosses the edge of the page a [`RegionOutOfPageError`] will be returned and
+    /// the program won't be changed.
+    pub fn paste_region(
+        &mut self,
+        position: InstructionPosition,
+        region: &Region,
+    ) -> Result<Removed, RegionOutOfPageError> {
+        let mut removed = Removed::new();
+        for (index, &instruction) in
+            region_cells(position, region.rows, region.columns)?.zip(&region.instructions)
+        {
+            removed.extend(self.non_empty(index..index + 1));
+            self.instructions[index] = instruction;
+        }
+        Ok(removed)
+    }
+
+    /// Shifts the cells from the `index` up to the `end` forward by `len` and fills the gap with
+    /// empty instructions.
+    fn insert_cells(&mut self, index: usize, len: usize, end: usize) -> Removed {
+        let len = len.min(end - index);
+        let removed = self.non_empty(end - len..end);
+        self.instructions[index..end].rotate_right(len);
+        self.instructions[index..index + len].fill(Instruction::default());
+        removed
+    }
+    /// Shifts the cells from the `index` up to the `end` backward by `len` and fills the end with
+    /// empty instructions.
+    fn delete_cells(&mut self, index: usize, len: usize, end: usize) -> Removed {
+        let len = len.min(end - index);
+        let removed = self.non_empty(index..index + len);
+        self.instructions[index..end].rotate_left(len);
+        self.instructions[end - len..end].fill(Instruction::default());
+        removed
+    }
+    /// Returns the non-empty instructions in the `range` with their positions.
+    fn non_empty(&self, range: Range<usize>) -> Removed {
+        range
+            .filter(|&index| self.instructions[index].id() != InstructionId::Empty)
+            .map(|index| {
+                let position = InstructionPosition::try_from(index)
+                    .expect("the index is always within the program");
+                (position, self.instructions[index])
+            })
+            .collect()
+    }
+}
+
+/// Returns the "flat index" of the first cell of the `row` of the `page`.
+///
+/// # Panics
+///
+/// Panics if the `page` or the `row` is out of range.
+fn row_index(page: u8, row: u8) -> usize {
+    InstructionPosition::new(page, row, 0)
+        .expect("the page or the row is out of range")
+        .index()
+}
+
+/// Returns the "flat indexes" of the cells of the region row by row.
+fn region_cells(
+    origin: InstructionPosition,
+    rows: usize,
+    columns: usize,
+) -> Result<impl Iterator<Item = usize>, RegionOutOfPageError> {
+    if origin.row() as usize + rows > Program::ROWS_PER_PAGE
+        || origin.column() as usize + columns > Program::INSTRUCTIONS_PER_ROW
+    {
+        return Err(RegionOutOfPageError {});
+    }
+    let start = origin.index();
+    Ok((0..rows).flat_map(move |row| {
+        let row_start = start + row * Program::INSTRUCTIONS_PER_ROW;
+        row_start..row_start + columns
+    }))
+}
+
+// region: test
+
+#[cfg(test)]
+mod tests {
+    use super::{RegionOutOfPageError, Removed, Scope};
+    use crate::formats::internal::{Instruction, InstructionId, InstructionPosition, Program};
+
+    fn at(page: u8, row: u8, column: u8) -> InstructionPosition {
+        InstructionPosition::new(page, row, column).unwrap()
+    }
+
+    fn simple(id: InstructionId) -> Instruction {
+        Instruction::new_simple(id).unwrap()
+    }
+
+    /// Returns the program with `MOVE_W` at the first and `END` at the last cell of each row.
+    fn bordered() -> Program {
+        let mut program = Program::default();
+        for index in (0..Program::INSTRUCTIONS_PER_PROGRAM).step_by(Program::INSTRUCTIONS_PER_ROW) {
+            program[index] = simple(InstructionId::MoveW);
+            program[index + Program::INSTRUCTIONS_PER_ROW - 1] = simple(InstructionId::End);
+        }
+        program
+    }
+
+    #[test]
+    fn insert_cell() {
+        let move_s = simple(InstructionId::MoveS);
+        let end = simple(InstructionId::End);
+
+        let mut program = bordered();
+        let removed = program.insert_cell(at(0, 0, 0), move_s, Scope::Row);
+        assert_eq!(vec![(at(0, 0, 15), end)], removed);
+        assert_eq!(move_s, program[0]);
+        assert_eq!(simple(InstructionId::MoveW), program[1]);
+        assert_eq!(Instruction::default(), program[15]);
+        assert_eq!(simple(InstructionId::MoveW), program[16]);
+
+        let mut program = bordered();
+        let removed = program.insert_cell(at(0, 11, 15), move_s, Scope::Page);
+        assert_eq!(vec![(at(0, 11, 15), end)], removed);
+        assert_eq!(move_s, program[at(0, 11, 15)]);
+
+        let mut program = bordered();
+        let removed = program.insert_cell(at(0, 0, 1), move_s, Scope::Program);
+        assert_eq!(vec![(at(15, 11, 15), end)], removed);
+        assert_eq!(end, program[at(0, 1, 0)]);
+        assert_eq!(simple(InstructionId::MoveW), program[at(0, 1, 1)]);
+    }
+
+    #[test]
+    fn delete_cell() {
+        let mut program = bordered();
+        assert_eq!(Removed::new(), program.delete_cell(at(3, 2, 1), Scope::Row));
+        assert_eq!(simple(InstructionId::End), program[at(3, 2, 14)]);
+        assert_eq!(Instruction::default(), program[at(3, 2, 15)]);
+
+        let removed = program.delete_cell(at(3, 2, 0), Scope::Page);
+        assert_eq!(vec![(at(3, 2, 0), simple(InstructionId::MoveW))], removed);
+        assert_eq!(simple(InstructionId::End), program[at(3, 2, 13)]);
+        assert_eq!(simple(InstructionId::MoveW), program[at(3, 2, 15)]);
+        assert_eq!(Instruction::default(), program[at(3, 11, 15)]);
+        assert_eq!(simple(InstructionId::MoveW), program[at(4, 0, 0)]);
+    }
+
+    #[test]
+    fn rows() {
+        let mut program = bordered();
+        let removed = program.insert_row(1, 0, Scope::Page);
+        assert_eq!(2, removed.len());
+        assert_eq!(at(1, 11, 0), removed[0].0);
+        assert_eq!(Instruction::default(), program[at(1, 0, 0)]);
+        assert_eq!(simple(InstructionId::MoveW), program[at(1, 1, 0)]);
+
+        let removed = program.delete_row(1, 0, Scope::Program);
+        assert_eq!(Removed::new(), removed);
+        assert_eq!(simple(InstructionId::MoveW), program[at(1, 0, 0)]);
+        // the first row of the next page is shifted to the end of the page
+        assert_eq!(simple(InstructionId::MoveW), program[at(1, 11, 0)]);
+        assert_eq!(simple(InstructionId::MoveW), program[at(2, 0, 0)]);
+        assert_eq!(Instruction::default(), program[at(15, 11, 0)]);
+
+        let removed = program.delete_row(2, 5, Scope::Row);
+        assert_eq!(2, removed.len());
+        assert_eq!(Instruction::default(), program[at(2, 5, 0)]);
+        assert_eq!(simple(InstructionId::MoveW), program[at(2, 6, 0)]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn row_out_of_range() {
+        Program::default().insert_row(0, 12, Scope::Page);
+    }
+
+    #[test]
+    fn pages() {
+        let mut program = Program::default();
+        program[at(0, 0, 0)] = simple(InstructionId::MoveW);
+        program[at(15, 3, 3)] = simple(InstructionId::End);
+
+        assert_eq!(
+            vec![(at(15, 3, 3), simple(InstructionId::End))],
+            program.insert_page(0)
+        );
+        assert_eq!(Instruction::default(), program[0]);
+        assert_eq!(simple(InstructionId::MoveW), program[at(1, 0, 0)]);
+
+        assert_eq!(Removed::new(), program.delete_page(0));
+        assert_eq!(simple(InstructionId::MoveW), program[0]);
+
+        program.swap_pages(7, 0);
+        assert_eq!(Instruction::default(), program[0]);
+        assert_eq!(simple(InstructionId::MoveW), program[at(7, 0, 0)]);
+        program.swap_pages(7, 7);
+        assert_eq!(simple(InstructionId::MoveW), program[at(7, 0, 0)]);
+
+        assert_eq!(
+            vec![(at(7, 0, 0), simple(InstructionId::MoveW))],
+            program.delete_page(7)
+        );
+        assert_eq!(Program::default(), program);
+    }
+
+    #[test]
+    fn regions() {
+        let program = bordered();
+        let region = program.copy_region(at(2, 10, 14), 2, 2).unwrap();
+        assert_eq!((2, 2), (region.rows(), region.columns()));
+        assert_eq!(Some(Instruction::default()), region.get(1, 0));
+        assert_eq!(Some(simple(InstructionId::End)), region.get(1, 1));
+        assert_eq!(None, region.get(2, 0));
+
+        let mut copy = bordered();
+        let removed = copy.paste_region(at(0, 0, 0), &region).unwrap();
+        assert_eq!(
+            vec![
+                (at(0, 0, 0), simple(InstructionId::MoveW)),
+                (at(0, 1, 0), simple(InstructionId::MoveW))
+            ],
+            removed
+        );
+        assert_eq!(simple(InstructionId::End), copy[1]);
+        assert_eq!(simple(InstructionId::End), copy[17]);
+        assert_eq!(Instruction::default(), copy[16]);
+
+        assert_eq!(
+            Err(RegionOutOfPageError {}),
+            program.copy_region(at(0, 0, 15), 1, 2)
+        );
+        assert_eq!(
+            Err(RegionOutOfPageError {}),
+            copy.paste_region(at(0, 11, 0), &region)
+        );
+        assert_eq!(simple(InstructionId::MoveW), copy[at(0, 11, 0)]);
+    }
+}
+
+// endregion: test
@@ -1,14 +1,17 @@
 //! Raw literals.
 
-use std::{error::Error, fmt, io, iter::Enumerate, str, str::Chars};
+use std::{
+    error::Error, fmt, io, iter::Enumerate, marker::PhantomData, str, str::Chars, str::FromStr,
+};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct IllegalCharError {
+    /// Index of the illegal char in chars, not in bytes.
     index: usize,
 }
 
 impl IllegalCharError {
-    /// Returns the contained illegal char.
+    /// Returns the index of the illegal char. It's a char index, not a byte one.
     pub fn index(&self) -> usize {
         self.index
     }
@@ -33,8 +36,98 @@ impl fmt::Display for IllegalVariableValueError {
 
 impl Error for IllegalVariableValueError {}
 
+/// An error returned when a literal has more chars than its type allows.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LiteralTooLongError {
+    index: usize,
+    len: usize,
+}
+
+impl LiteralTooLongError {
+    /// Returns index of the first char of the literal.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+    /// Returns length of the whole literal in chars.
+    pub fn length(&self) -> usize {
+        self.len
+    }
+}
+
+impl fmt::Display for LiteralTooLongError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "a literal of {} chars was found at index {}",
+            self.len, self.index
+        )
+    }
+}
+
+impl Error for LiteralTooLongError {}
+
+/// An error returned when parsing a literal from a `str`.
+///
+/// # Examples
+///
+/// ```
+/// use m3c::formats::internal::literals::{LabelIdentifierLiteral, ParseLiteralError};
+///
+/// match "ab-".parse::<LabelIdentifierLiteral>() {
+///     Err(ParseLiteralError::IllegalChar(e)) => assert_eq!(2, e.index()),
+///     _ => unreachable!(),
+/// }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ParseLiteralError {
+    /// See [`IllegalCharError`].
+    IllegalChar(IllegalCharError),
+    /// See [`LiteralTooLongError`].
+    TooLong(LiteralTooLongError),
+    /// The variable value has no digits or is out of the range. See [`IllegalVariableValueError`].
+    IllegalValue(IllegalVariableValueError),
+}
+
+impl fmt::Display for ParseLiteralError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::IllegalChar(e) => write!(f, "{}", e),
+            Self::TooLong(e) => write!(f, "{}", e),
+            Self::IllegalValue(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for ParseLiteralError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::IllegalChar(e) => Some(e),
+            Self::TooLong(e) => Some(e),
+            Self::IllegalValue(e) => Some(e),
+        }
+    }
+}
+
+impl From<IllegalCharError> for ParseLiteralError {
+    fn from(error: IllegalCharError) -> Self {
+        Self::IllegalChar(error)
+    }
+}
+
+impl From<LiteralTooLongError> for ParseLiteralError {
+    fn from(error: LiteralTooLongError) -> Self {
+        Self::TooLong(error)
+    }
+}
+
+impl From<IllegalVariableValueError> for ParseLiteralError {
+    fn from(error: IllegalVariableValueError) -> Self {
+        Self::IllegalValue(error)
+    }
+}
+
 /// Literal's type.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum LiteralType {
     /// See [`LabelIdentifierLiteral`].
     LabelIdentifierLiteral,
@@ -52,10 +145,20 @@ pub trait Literal {
     /// Greedy creates a new literal from a `&mut Enumerate<Chars>`.
     ///
     /// Consumes the given `enumerate` until the first illegal char is found (if any) and returns
-    /// it (if any). Only the first [`MAX_CHAR_LEN`] legal chars aren't ignored.
+    /// it (if any).
+    ///
+    /// # Errors
+    ///
+    /// If the literal has more than [`MAX_CHAR_LEN`] chars, the [`TooLong`] error will be
+    /// returned. The whole literal and the char after it are consumed anyway. If `\` escapes an
+    /// illegal char, the [`IllegalChar`] error with the index of the char will be returned.
     ///
     /// [`MAX_CHAR_LEN`]: Self::MAX_CHAR_LEN
-    fn new_from_enumerate(enumerate: &mut Enumerate<Chars>) -> (Self, Option<(usize, char)>)
+    /// [`TooLong`]: ParseLiteralError::TooLong
+    /// [`IllegalChar`]: ParseLiteralError::IllegalChar
+    fn new_from_enumerate(
+        enumerate: &mut Enumerate<Chars>,
+    ) -> Result<(Self, Option<(usize, char)>), ParseLiteralError>
     where
         Self: Sized;
     /// Dumps this literal to the given `String`.
@@ -74,273 +177,240 @@ pub trait Literal {
         W: io::Write;
 }
 
-/// Label identifier literal.
+/// Rules of a [`BoundedIdentifier`]: which chars it can contain and how many of them.
+pub trait IdentifierRules {
+    /// The maximum length in chars.
+    const MAX_CHAR_LEN: usize;
+    /// Checks if the given char is allowed. `'\0'` can't be allowed.
+    fn is_allowed(ch: char) -> bool;
+    /// Checks if the given allowed char has to be escaped with `\` in text formats (e.g. the
+    /// closing quote). No chars are escaped by default.
+    fn needs_escape(ch: char) -> bool {
+        let _ = ch;
+        false
+    }
+}
+
+/// An identifier-like literal stored as a NUL-terminated UTF-8 string in `[u8; N]`.
 ///
-/// Matches the regex `[0-9A-Za-z]{0,3}`.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub struct LabelIdentifierLiteral {
-    data: [u8; 4],
+/// The allowed chars and the maximum length are defined by the rules `R`, so literals of
+/// different kinds are distinct types. `N` must be big enough to hold [`MAX_CHAR_LEN`] allowed
+/// chars and the NUL. See [`LabelIdentifierLiteral`], [`StringLiteral`] and
+/// [`VariableIdentifierLiteral`].
+///
+/// [`MAX_CHAR_LEN`]: IdentifierRules::MAX_CHAR_LEN
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BoundedIdentifier<R, const N: usize = 4> {
+    data: [u8; N],
+    rules: PhantomData<R>,
 }
 
-impl LabelIdentifierLiteral {
-    /// Creates a new literal from `[u8; 4]` ignoring the last element.
+impl<R: IdentifierRules, const N: usize> BoundedIdentifier<R, N> {
+    /// Creates a new literal from a NUL-terminated UTF-8 `[u8; N]` ignoring the last element.
     ///
     /// # Errors
     ///
-    /// If the first three chars have an illegal char, the [`IllegalCharError`] will be returned.
-    pub fn new_from_array(data: [u8; 4]) -> Result<Self, IllegalCharError> {
-        let mut internal_data = [0; 4];
-        let mut index = 0;
-        while index < Self::MAX_CHAR_LEN {
-            let ch = data[index];
-            if ch.is_ascii_alphanumeric() {
-                internal_data[index] = ch;
-                index += 1;
-                continue;
-            } else if ch == 0 {
-                return Ok(Self {
-                    data: internal_data,
-                });
+    /// If the data before the NUL isn't valid UTF-8, has an illegal char or more than
+    /// [`MAX_CHAR_LEN`] chars, the [`IllegalCharError`] with the char index will be returned. The
+    /// first byte of an invalid UTF-8 sequence counts as a char.
+    ///
+    /// [`MAX_CHAR_LEN`]: IdentifierRules::MAX_CHAR_LEN
+    pub fn new_from_array(data: [u8; N]) -> Result<Self, IllegalCharError> {
+        let len = data[..N - 1].iter().position(|&b| b == 0).unwrap_or(N - 1);
+        let s = str::from_utf8(&data[..len]).map_err(|e| {
+            let valid = str::from_utf8(&data[..e.valid_up_to()]).expect("the prefix is valid");
+            IllegalCharError {
+                index: valid.chars().count(),
+            }
+        })?;
+        for (index, ch) in s.chars().enumerate() {
+            if index == R::MAX_CHAR_LEN || !R::is_allowed(ch) {
+                return Err(IllegalCharError { index });
             }
-            return Err(IllegalCharError { index });
         }
-        Ok(Self {
-            data: internal_data,
-        })
+        let mut internal_data = [0; N];
+        internal_data[..len].copy_from_slice(&data[..len]);
+        Ok(Self::new_unchecked(internal_data))
     }
-    /// Returns the length of this literal.
+    /// Creates a new literal from already checked data.
+    fn new_unchecked(data: [u8; N]) -> Self {
+        Self {
+            data,
+            rules: PhantomData,
+        }
+    }
+    /// Returns the length of this literal in bytes.
     pub fn len(&self) -> usize {
         self.data.iter().position(|&b| b == 0).unwrap()
     }
+    /// Returns the length of this literal in chars.
+    pub fn char_len(&self) -> usize {
+        self.as_str().chars().count()
+    }
     /// Checks if this literal is empty.
     #[must_use]
     pub fn is_empty(&self) -> bool {
         self.data[0] == 0
     }
+    /// Returns this literal as `&str`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use m3c::formats::internal::literals::LabelIdentifierLiteral;
+    ///
+    /// let literal: LabelIdentifierLiteral = "abc".parse().unwrap();
+    /// assert_eq!("abc", literal.as_str());
+    /// ```
+    pub fn as_str(&self) -> &str {
+        str::from_utf8(&self.data[..self.len()]).unwrap()
+    }
     /// Returns the underlying data.
     ///
     /// The last element of the array is always zero.
-    pub fn data(&self) -> [u8; 4] {
+    pub fn data(&self) -> [u8; N] {
         self.data
     }
 }
 
-impl Literal for LabelIdentifierLiteral {
-    const MAX_CHAR_LEN: usize = 3;
-    fn new_from_enumerate(enumerate: &mut Enumerate<Chars>) -> (Self, Option<(usize, char)>) {
-        let mut literal = Self { data: [0; 4] };
-        let mut index = 0;
-        loop {
-            let next = enumerate.next();
-            match next {
-                None => return (literal, None::<(usize, char)>),
-                Some(last_item) => {
-                    if last_item.1.is_ascii_alphanumeric() {
-                        if index < Self::MAX_CHAR_LEN {
-                            literal.data[index] = last_item.1 as u8;
-                        }
-                        index += 1;
-                        continue;
-                    }
-                    return (literal, Some(last_item));
-                }
+impl<R: IdentifierRules, const N: usize> Literal for BoundedIdentifier<R, N> {
+    const MAX_CHAR_LEN: usize = R::MAX_CHAR_LEN;
+    /// Chars escaped with `\` are unescaped.
+    fn new_from_enumerate(
+        enumerate: &mut Enumerate<Chars>,
+    ) -> Result<(Self, Option<(usize, char)>), ParseLiteralError> {
+        let mut s = String::new();
+        let mut len = 0;
+        let mut start = 0;
+        let next_char = loop {
+            let (index, ch) = match enumerate.next() {
+                Some((index, '\\')) if R::needs_escape('\\') => match enumerate.next() {
+                    Some((_, ch)) if R::is_allowed(ch) => (index, ch),
+                    Some((index, _)) => return Err(IllegalCharError { index }.into()),
+                    None => return Err(IllegalCharError { index: index + 1 }.into()),
+                },
+                Some((index, ch)) if R::is_allowed(ch) && !R::needs_escape(ch) => (index, ch),
+                next => break next,
+            };
+            if len == 0 {
+                start = index;
             }
+            if len < Self::MAX_CHAR_LEN {
+                s.push(ch);
+            }
+            len += 1;
+        };
+        if len > Self::MAX_CHAR_LEN {
+            return Err(LiteralTooLongError { index: start, len }.into());
         }
+        Ok((s.parse()?, next_char))
     }
+    /// Chars which [need escape](IdentifierRules::needs_escape) are escaped with `\`.
     fn dumps_to(&self, s: &mut String) {
-        if !self.is_empty() {
-            s.push_str(str::from_utf8(&self.data[0..self.len()]).unwrap());
+        for ch in self.as_str().chars() {
+            if R::needs_escape(ch) {
+                s.push('\\');
+            }
+            s.push(ch);
         }
     }
+    /// Chars which [need escape](IdentifierRules::needs_escape) are escaped with `\`.
     fn write_all<W>(self, writer: &mut W) -> io::Result<()>
     where
         W: io::Write,
     {
-        if !self.is_empty() {
-            return writer.write_all(&self.data[0..self.len()]);
-        }
-        Ok(())
+        let mut s = String::new();
+        self.dumps_to(&mut s);
+        writer.write_all(s.as_bytes())
     }
 }
 
-/// String literal.
-///
-/// Matches the regex `[0-9A-Za-z]{0,3}`.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub struct StringLiteral {
-    data: [u8; 4],
-}
-
-impl StringLiteral {
-    /// Creates a new literal from `[u8; 4]` ignoring the last element.
-    ///
-    /// # Errors
-    ///
-    /// If the first three chars have an illegal char, the [`IllegalCharError`] will be returned.
-    pub fn new_from_array(data: [u8; 4]) -> Result<Self, IllegalCharError> {
-        let mut internal_data = [0; 4];
-        let mut index = 0;
-        while index < Self::MAX_CHAR_LEN {
-            let ch = data[index];
-            if ch.is_ascii_alphanumeric() {
-                internal_data[index] = ch;
-                index += 1;
-                continue;
-            } else if ch == 0 {
-                return Ok(Self {
-                    data: internal_data,
-                });
+/// Parses a literal from its raw (not escaped) chars. The index of [`IllegalCharError`] is a char
+/// index.
+impl<R: IdentifierRules, const N: usize> FromStr for BoundedIdentifier<R, N> {
+    type Err = ParseLiteralError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut len = 0;
+        for (index, ch) in s.chars().enumerate() {
+            if !R::is_allowed(ch) {
+                return Err(IllegalCharError { index }.into());
             }
-            return Err(IllegalCharError { index });
+            len += 1;
         }
-        Ok(Self {
-            data: internal_data,
-        })
-    }
-    /// Returns the length of this literal.
-    pub fn len(&self) -> usize {
-        self.data.iter().position(|&b| b == 0).unwrap()
+        if len > R::MAX_CHAR_LEN || s.len() >= N {
+            return Err(LiteralTooLongError { index: 0, len }.into());
+        }
+        let mut data = [0; N];
+        data[..s.len()].copy_from_slice(s.as_bytes());
+        Ok(Self::new_unchecked(data))
     }
-    /// Checks if this literal is empty.
-    #[must_use]
-    pub fn is_empty(&self) -> bool {
-        self.data[0] == 0
+}
+
+impl<R: IdentifierRules, const N: usize> TryFrom<&str> for BoundedIdentifier<R, N> {
+    type Error = ParseLiteralError;
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
     }
-    /// Returns the underlying data.
-    ///
-    /// The last element of the array is always zero.
-    pub fn data(&self) -> [u8; 4] {
-        self.data
+}
+
+impl<R: IdentifierRules, const N: usize> fmt::Display for BoundedIdentifier<R, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
     }
 }
 
-impl Literal for StringLiteral {
+/// Rules of [`LabelIdentifierLiteral`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LabelRules {}
+
+impl IdentifierRules for LabelRules {
     const MAX_CHAR_LEN: usize = 3;
-    fn new_from_enumerate(enumerate: &mut Enumerate<Chars>) -> (Self, Option<(usize, char)>) {
-        let mut literal = Self { data: [0; 4] };
-        let mut index = 0;
-        loop {
-            let next = enumerate.next();
-            match next {
-                None => return (literal, None::<(usize, char)>),
-                Some(last_item) => {
-                    if last_item.1.is_ascii_alphanumeric() {
-                        if index < Self::MAX_CHAR_LEN {
-                            literal.data[index] = last_item.1 as u8;
-                        }
-                        index += 1;
-                        continue;
-                    }
-                    return (literal, Some(last_item));
-                }
-            }
-        }
-    }
-    fn dumps_to(&self, s: &mut String) {
-        if !self.is_empty() {
-            s.push_str(str::from_utf8(&self.data[0..self.len()]).unwrap());
-        }
-    }
-    fn write_all<W>(self, writer: &mut W) -> io::Result<()>
-    where
-        W: io::Write,
-    {
-        if !self.is_empty() {
-            return writer.write_all(&self.data[0..self.len()]);
-        }
-        Ok(())
+    fn is_allowed(ch: char) -> bool {
+        ch.is_ascii_alphanumeric()
     }
 }
 
-/// Variable identifier literal.
+/// Label identifier literal.
 ///
 /// Matches the regex `[0-9A-Za-z]{0,3}`.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub struct VariableIdentifierLiteral {
-    data: [u8; 4],
-}
+pub type LabelIdentifierLiteral = BoundedIdentifier<LabelRules>;
 
-impl VariableIdentifierLiteral {
-    /// Creates a new literal from `[u8; 4]` ignoring the last element.
-    ///
-    /// # Errors
-    ///
-    /// If the first three chars have an illegal char, the [`IllegalCharError`] will be returned.
-    pub fn new_from_array(data: [u8; 4]) -> Result<Self, IllegalCharError> {
-        let mut internal_data = [0; 4];
-        let mut index = 0;
-        while index < Self::MAX_CHAR_LEN {
-            let ch = data[index];
-            if ch.is_ascii_alphanumeric() {
-                internal_data[index] = ch;
-                index += 1;
-                continue;
-            } else if ch == 0 {
-                return Ok(Self {
-                    data: internal_data,
-                });
-            }
-            return Err(IllegalCharError { index });
-        }
-        Ok(Self {
-            data: internal_data,
-        })
-    }
-    /// Returns the length of this literal.
-    pub fn len(&self) -> usize {
-        self.data.iter().position(|&b| b == 0).unwrap()
+/// Rules of [`StringLiteral`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum StringRules {}
+
+impl IdentifierRules for StringRules {
+    const MAX_CHAR_LEN: usize = 3;
+    fn is_allowed(ch: char) -> bool {
+        !ch.is_control()
     }
-    /// Checks if this literal is empty.
-    #[must_use]
-    pub fn is_empty(&self) -> bool {
-        self.data[0] == 0
-    }
-    /// Returns the underlying data.
-    ///
-    /// The last element of the array is always zero.
-    pub fn data(&self) -> [u8; 4] {
-        self.data
+    fn needs_escape(ch: char) -> bool {
+        matches!(ch, '\\' | '\'' | '}')
     }
 }
 
-impl Literal for VariableIdentifierLiteral {
+/// String literal, the tag of debug instructions.
+///
+/// Contains up to 3 chars which aren't control ones, including non-ASCII (e.g. `hp`, `a b` or
+/// `фів`), so it takes up to 12 bytes. `\`, `'` and `}` are escaped with `\` in text formats.
+pub type StringLiteral = BoundedIdentifier<StringRules, 13>;
+
+/// Rules of [`VariableIdentifierLiteral`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum VariableRules {}
+
+impl IdentifierRules for VariableRules {
     const MAX_CHAR_LEN: usize = 3;
-    fn new_from_enumerate(enumerate: &mut Enumerate<Chars>) -> (Self, Option<(usize, char)>) {
-        let mut literal = Self { data: [0; 4] };
-        let mut index = 0;
-        loop {
-            let next = enumerate.next();
-            match next {
-                None => return (literal, None::<(usize, char)>),
-                Some(last_item) => {
-                    if last_item.1.is_ascii_alphanumeric() {
-                        if index < Self::MAX_CHAR_LEN {
-                            literal.data[index] = last_item.1 as u8;
-                        }
-                        index += 1;
-                        continue;
-                    }
-                    return (literal, Some(last_item));
-                }
-            }
-        }
-    }
-    fn dumps_to(&self, s: &mut String) {
-        if !self.is_empty() {
-            s.push_str(str::from_utf8(&self.data[0..self.len()]).unwrap());
-        }
-    }
-    fn write_all<W>(self, writer: &mut W) -> io::Result<()>
-    where
-        W: io::Write,
-    {
-        if !self.is_empty() {
-            return writer.write_all(&self.data[0..self.len()]);
-        }
-        Ok(())
+    fn is_allowed(ch: char) -> bool {
+        ch.is_ascii_alphanumeric()
     }
 }
 
+/// Variable identifier literal.
+///
+/// Matches the regex `[0-9A-Za-z]{0,3}`.
+pub type VariableIdentifierLiteral = BoundedIdentifier<VariableRules>;
+
 /// Variable value literal.
 ///
 /// The value can be in the range `[-9_999, 99_999]`. Default value is `0`.
@@ -368,46 +438,46 @@ impl VariableValueLiteral {
 }
 
 impl Literal for VariableValueLiteral {
+    /// Including the minus sign.
     const MAX_CHAR_LEN: usize = 5;
-    fn new_from_enumerate(enumerate: &mut Enumerate<Chars>) -> (Self, Option<(usize, char)>) {
-        let mut buf = [0; Self::MAX_CHAR_LEN];
+    fn new_from_enumerate(
+        enumerate: &mut Enumerate<Chars>,
+    ) -> Result<(Self, Option<(usize, char)>), ParseLiteralError> {
+        let mut value = 0;
         let mut is_positive = true;
-        let mut next_char = None;
         let mut index = 0;
-        let mut buf_index = 0;
-        // read to buf
-        loop {
-            let next = enumerate.next();
-            match next {
-                Some(last_item) => {
-                    if last_item.1.is_ascii_digit() {
-                        buf[buf_index] = last_item.1 as u8 - b'0';
-                        buf_index += 1;
-                    } else if last_item.1 == '-' && index == 0 {
+        let mut start = 0;
+        let next_char = loop {
+            match enumerate.next() {
+                Some(last_item)
+                    if last_item.1.is_ascii_digit() || last_item.1 == '-' && index == 0 =>
+                {
+                    if index == 0 {
+                        start = last_item.0;
+                    }
+                    if last_item.1 == '-' {
                         is_positive = false;
-                    } else {
-                        next_char = Some(last_item);
-                        break;
+                    } else if index < Self::MAX_CHAR_LEN {
+                        value = value * 10 + (last_item.1 as i32 - '0' as i32);
                     }
                     index += 1;
                 }
-                None => break,
+                next => break next,
             }
+        };
+        if index > Self::MAX_CHAR_LEN {
+            return Err(LiteralTooLongError {
+                index: start,
+                len: index,
+            }
+            .into());
         }
-        // parse buf
-        let mut value = 0;
-        let mut pow = 0;
-        while buf_index > 0 {
-            value += i32::from(buf[buf_index - 1]) * 10_i32.pow(pow);
-            pow += 1;
-            buf_index -= 1;
-        }
-        (
+        Ok((
             Self {
                 data: if is_positive { value } else { -value },
             },
             next_char,
-        )
+        ))
     }
     fn dumps_to(&self, s: &mut String) {
         s.push_str(self.data.to_string().as_str());
@@ -420,13 +490,48 @@ impl Literal for VariableValueLiteral {
     }
 }
 
+/// Parses a variable value literal (matches the regex `-?[0-9]+`).
+///
+/// Unlike [`Literal::new_from_enumerate`], leading zeros are allowed.
+impl FromStr for VariableValueLiteral {
+    type Err = ParseLiteralError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s.strip_prefix('-').unwrap_or(s);
+        if let Some(index) = digits.chars().position(|ch| !ch.is_ascii_digit()) {
+            return Err(IllegalCharError {
+                index: index + s.len() - digits.len(),
+            }
+            .into());
+        }
+        // `digits` can only be empty or too long for `i32` here
+        let value = s.parse().map_err(|_| IllegalVariableValueError {})?;
+        Ok(Self::new_from_value(value)?)
+    }
+}
+
+impl TryFrom<&str> for VariableValueLiteral {
+    type Error = ParseLiteralError;
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for VariableValueLiteral {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.data)
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     #[cfg(test)]
     mod label_identifier_literal {
 
-        use super::super::{LabelIdentifierLiteral, Literal};
+        use super::super::{
+            IllegalCharError, LabelIdentifierLiteral, Literal, LiteralTooLongError,
+            ParseLiteralError,
+        };
 
         #[test]
         fn new_from_string() {
@@ -438,7 +543,7 @@ mod tests {
             );
             let actual_literal =
                 LabelIdentifierLiteral::new_from_enumerate(&mut s.chars().enumerate());
-            assert_eq!(expected_literal, actual_literal);
+            assert_eq!(Ok(expected_literal), actual_literal);
         }
 
         #[test]
@@ -451,7 +556,7 @@ mod tests {
             );
             let actual_literal =
                 LabelIdentifierLiteral::new_from_enumerate(&mut s.chars().enumerate());
-            assert_eq!(expected_literal, actual_literal);
+            assert_eq!(Ok(expected_literal), actual_literal);
         }
 
         #[test]
@@ -464,14 +569,191 @@ mod tests {
             );
             let actual_literal =
                 LabelIdentifierLiteral::new_from_enumerate(&mut s.chars().enumerate());
-            assert_eq!(expected_literal, actual_literal);
+            assert_eq!(Ok(expected_literal), actual_literal);
+        }
+
+        #[test]
+        fn new_from_too_long() {
+            let s = ">abcde|)";
+            let mut enumerate = s.chars().enumerate();
+            enumerate.next();
+            let actual_literal = LabelIdentifierLiteral::new_from_enumerate(&mut enumerate);
+            assert_eq!(
+                Err(LiteralTooLongError { index: 1, len: 5 }.into()),
+                actual_literal
+            );
+            // the whole literal and the char after it are consumed
+            assert_eq!(Some((7, ')')), enumerate.next());
+        }
+
+        #[test]
+        fn from_str() {
+            for s in ["", "0", "a1Z"] {
+                let literal = s.parse::<LabelIdentifierLiteral>().unwrap();
+                assert_eq!(s, literal.as_str());
+                assert_eq!(s, literal.to_string());
+                assert_eq!(Ok(literal), LabelIdentifierLiteral::try_from(s));
+            }
+            assert_eq!(
+                Err(ParseLiteralError::IllegalChar(IllegalCharError {
+                    index: 1
+                })),
+                "a-b".parse::<LabelIdentifierLiteral>()
+            );
+            assert_eq!(
+                Err(ParseLiteralError::IllegalChar(IllegalCharError {
+                    index: 0
+                })),
+                "ф".parse::<LabelIdentifierLiteral>()
+            );
+            assert_eq!(
+                Err(ParseLiteralError::TooLong(LiteralTooLongError {
+                    index: 0,
+                    len: 4
+                })),
+                "abcd".parse::<LabelIdentifierLiteral>()
+            );
+        }
+    }
+
+    #[cfg(test)]
+    mod bounded_identifier {
+
+        use super::super::{
+            BoundedIdentifier, IdentifierRules, IllegalCharError, Literal, LiteralTooLongError,
+            ParseLiteralError,
+        };
+
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        enum LowercaseRules {}
+
+        impl IdentifierRules for LowercaseRules {
+            const MAX_CHAR_LEN: usize = 2;
+            fn is_allowed(ch: char) -> bool {
+                ch.is_ascii_lowercase()
+            }
+        }
+
+        type Lowercase = BoundedIdentifier<LowercaseRules>;
+
+        #[test]
+        fn custom_rules() {
+            assert_eq!("ab", "ab".parse::<Lowercase>().unwrap().as_str());
+            assert_eq!(
+                Err(ParseLiteralError::IllegalChar(IllegalCharError {
+                    index: 1
+                })),
+                "aB".parse::<Lowercase>()
+            );
+            assert_eq!(
+                Err(ParseLiteralError::TooLong(LiteralTooLongError {
+                    index: 0,
+                    len: 3
+                })),
+                "abc".parse::<Lowercase>()
+            );
+            assert_eq!(
+                Err(IllegalCharError { index: 0 }),
+                Lowercase::new_from_array(*b"1\0\0\0")
+            );
+            assert_eq!(
+                Err(IllegalCharError { index: 2 }),
+                Lowercase::new_from_array(*b"abc\0")
+            );
+            // the last element is ignored
+            assert_eq!(
+                Ok("ab".parse().unwrap()),
+                Lowercase::new_from_array(*b"ab\0c")
+            );
+
+            let s = "xy1";
+            let (literal, next_char) =
+                Lowercase::new_from_enumerate(&mut s.chars().enumerate()).unwrap();
+            assert_eq!(("xy", Some((2, '1'))), (literal.as_str(), next_char));
+        }
+    }
+
+    #[cfg(test)]
+    mod string_literal {
+
+        use super::super::{
+            IllegalCharError, Literal, LiteralTooLongError, ParseLiteralError, StringLiteral,
+        };
+
+        #[test]
+        fn unicode() {
+            let literal = "фів".parse::<StringLiteral>().unwrap();
+            assert_eq!((6, 3), (literal.len(), literal.char_len()));
+            assert_eq!(Ok(literal), StringLiteral::new_from_array(literal.data()));
+            assert_eq!(
+                Err(ParseLiteralError::TooLong(LiteralTooLongError {
+                    index: 0,
+                    len: 4
+                })),
+                "фіва".parse::<StringLiteral>()
+            );
+            assert_eq!(
+                Err(ParseLiteralError::IllegalChar(IllegalCharError {
+                    index: 1
+                })),
+                "a\nb".parse::<StringLiteral>()
+            );
+            // a broken UTF-8 sequence
+            assert_eq!(
+                Err(IllegalCharError { index: 1 }),
+                StringLiteral::new_from_array(*b"a\xd1\0\0\0\0\0\0\0\0\0\0\0")
+            );
+            assert_eq!(
+                Err(IllegalCharError { index: 1 }),
+                StringLiteral::new_from_array(*b"\xd1\x84\xd1\0\0\0\0\0\0\0\0\0\0")
+            );
+            // indexes are char ones, whatever the literal is created from
+            let mut data = [0; 13];
+            data[..5].copy_from_slice("фі\n".as_bytes());
+            assert_eq!(
+                Err(IllegalCharError { index: 2 }),
+                StringLiteral::new_from_array(data)
+            );
+            assert_eq!(
+                Err(ParseLiteralError::IllegalChar(IllegalCharError {
+                    index: 2
+                })),
+                "фі\n".parse::<StringLiteral>()
+            );
+        }
+
+        #[test]
+        fn escapes() {
+            let literal = "}'\\".parse::<StringLiteral>().unwrap();
+            let mut s = String::new();
+            literal.dumps_to(&mut s);
+            assert_eq!(r"\}\'\\", s);
+
+            let s = r"\}\'\\}";
+            let actual_literal = StringLiteral::new_from_enumerate(&mut s.chars().enumerate());
+            assert_eq!(Ok((literal, Some((6, '}')))), actual_literal);
+
+            let s = "ф і}";
+            let actual_literal = StringLiteral::new_from_enumerate(&mut s.chars().enumerate());
+            assert_eq!(Ok(("ф і".parse().unwrap(), Some((3, '}')))), actual_literal);
+
+            // `\` can't escape an illegal char or the end
+            let s = "a\\\n}";
+            let actual_literal = StringLiteral::new_from_enumerate(&mut s.chars().enumerate());
+            assert_eq!(Err(IllegalCharError { index: 2 }.into()), actual_literal);
+            let s = "a\\";
+            let actual_literal = StringLiteral::new_from_enumerate(&mut s.chars().enumerate());
+            assert_eq!(Err(IllegalCharError { index: 2 }.into()), actual_literal);
         }
     }
 
     #[cfg(test)]
     mod variable_value_literal {
 
-        use super::super::{Literal, VariableValueLiteral};
+        use super::super::{
+            IllegalCharError, IllegalVariableValueError, Literal, LiteralTooLongError,
+            ParseLiteralError, VariableValueLiteral,
+        };
 
         #[test]
         fn new_from_enumerate_empty_string() {
@@ -480,7 +762,7 @@ mod tests {
             let expected_literal = (VariableValueLiteral::new_from_value(0).unwrap(), last_char);
             let actual_literal =
                 VariableValueLiteral::new_from_enumerate(&mut s.chars().enumerate());
-            assert_eq!(expected_literal, actual_literal);
+            assert_eq!(Ok(expected_literal), actual_literal);
         }
 
         #[test]
@@ -490,7 +772,7 @@ mod tests {
             let expected_literal = (VariableValueLiteral::new_from_value(0).unwrap(), last_char);
             let actual_literal =
                 VariableValueLiteral::new_from_enumerate(&mut s.chars().enumerate());
-            assert_eq!(expected_literal, actual_literal);
+            assert_eq!(Ok(expected_literal), actual_literal);
         }
 
         #[test]
@@ -500,7 +782,7 @@ mod tests {
             let expected_literal = (VariableValueLiteral::new_from_value(-1).unwrap(), last_char);
             let actual_literal =
                 VariableValueLiteral::new_from_enumerate(&mut s.chars().enumerate());
-            assert_eq!(expected_literal, actual_literal);
+            assert_eq!(Ok(expected_literal), actual_literal);
         }
 
         #[test]
@@ -513,7 +795,7 @@ mod tests {
             );
             let actual_literal =
                 VariableValueLiteral::new_from_enumerate(&mut s.chars().enumerate());
-            assert_eq!(expected_literal, actual_literal);
+            assert_eq!(Ok(expected_literal), actual_literal);
         }
 
         #[test]
@@ -526,7 +808,7 @@ mod tests {
             );
             let actual_literal =
                 VariableValueLiteral::new_from_enumerate(&mut s.chars().enumerate());
-            assert_eq!(expected_literal, actual_literal);
+            assert_eq!(Ok(expected_literal), actual_literal);
         }
 
         #[test]
@@ -539,7 +821,47 @@ mod tests {
             );
             let actual_literal =
                 VariableValueLiteral::new_from_enumerate(&mut s.chars().enumerate());
-            assert_eq!(expected_literal, actual_literal);
+            assert_eq!(Ok(expected_literal), actual_literal);
+        }
+
+        #[test]
+        fn new_from_enumerate_too_long() {
+            for (s, len) in [("000007)", 6), ("-00001)", 6), ("123456", 6)] {
+                let actual_literal =
+                    VariableValueLiteral::new_from_enumerate(&mut s.chars().enumerate());
+                assert_eq!(
+                    Err(LiteralTooLongError { index: 0, len }.into()),
+                    actual_literal
+                );
+            }
+        }
+
+        #[test]
+        fn from_str() {
+            for (s, value) in [("0", 0), ("-9999", -9_999), ("99999", 99_999), ("-007", -7)] {
+                let literal = s.parse::<VariableValueLiteral>().unwrap();
+                assert_eq!(value, literal.data());
+                assert_eq!(value.to_string(), literal.to_string());
+                assert_eq!(Ok(literal), VariableValueLiteral::try_from(s));
+            }
+            for (s, index) in [("+1", 0), ("1-", 1), ("-1a", 2), ("--1", 1)] {
+                assert_eq!(
+                    Err(ParseLiteralError::IllegalChar(IllegalCharError { index })),
+                    s.parse::<VariableValueLiteral>(),
+                    "{}",
+                    s
+                );
+            }
+            for s in ["", "-", "100000", "-10000", "99999999999"] {
+                assert_eq!(
+                    Err(ParseLiteralError::IllegalValue(
+                        IllegalVariableValueError {}
+                    )),
+                    s.parse::<VariableValueLiteral>(),
+                    "{}",
+                    s
+                );
+            }
         }
     }
 }
@@ -1,10 +1,15 @@
 //! An internal raw program representation.
 
+pub mod editing;
 pub mod literals;
+pub mod registry;
+#[cfg(feature = "serde")]
+mod serde;
 
 use literals::{
     LabelIdentifierLiteral, StringLiteral, VariableIdentifierLiteral, VariableValueLiteral,
 };
+use registry::InstructionInfo;
 use std::error::Error;
 use std::fmt;
 use std::ops::{Index, IndexMut};
@@ -57,176 +62,278 @@ impl fmt::Display for InstructionPositionOverflowError {
 
 impl Error for InstructionPositionOverflowError {}
 
+/// An error returned when converting a numeric value without an [`InstructionId`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct UnknownInstructionIdError {
+    value: u8,
+}
+
+impl UnknownInstructionIdError {
+    /// Returns the value.
+    pub fn value(&self) -> u8 {
+        self.value
+    }
+}
+
+impl fmt::Display for UnknownInstructionIdError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown instruction id {}", self.value)
+    }
+}
+
+impl Error for UnknownInstructionIdError {}
+
 // endregion: errors
 
 // region: instruction_id
 
 /// Instruction's ids.
 ///
-/// This enumeration doesn't contain ids for `LAST` and `RESTART` instructions.
+/// All ids are taken from official client, their numeric values are in range `[0-182]` and `200`
+/// (see [`value`](Self::value)). There is no ids for values `34, 41-42, 55-56, 61-73, 75, 78-118,
+/// 121-122, 124-130, 150-155, 183-199`, [`ALL`](Self::ALL) lists the known ids. The rest of the
+/// values are kept as [`Unknown`](Self::Unknown) ids.
+///
+/// See the [registry] for the facts about every id.
 ///
-/// All values are taken from official client. Values are in range `[0-182]`. There is no ids for
-/// values `13, 34, 41-42, 55-56, 61-73, 75, 78-118, 121-122, 124-130, 150-155`.
+/// Since [`Unknown`](Self::Unknown) ids hold a value, ids can't be cast with `as`, use
+/// [`value`](Self::value) or `u8::from` instead.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum InstructionId {
-    Empty = 0,
-    Back = 1,
-
-    Start = 2,
-    End = 3,
-
-    MoveW = 4,
-    MoveA = 5,
-    MoveS = 6,
-    MoveD = 7,
-
-    Digg = 8,
-
-    LookW = 9,
-    LookA = 10,
-    LookS = 11,
-    LookD = 12,
-
-    MoveF = 14,
-
-    RotateCcw = 15,
-    RotateCw = 16,
-
-    ActionBuild = 17,
-    ActionGeo = 18,
-    ActionRoad = 19,
-    ActionHeal = 20,
-    ActionQuadro = 21,
-    ActionRandom = 22,
-    ActionBibika = 23,
-
-    GoTo = 24,
-    GoSub = 25,
-    GoSub1 = 26,
-    Return = 27,
-    Return1 = 28,
-
-    CellWa = 29,
-    CellSd = 30,
-    CellW = 31,
-    CellDw = 32,
-    CellA = 33,
-    CellD = 35,
-    CellAs = 36,
-    CellS = 37,
-
-    BoolModeOr = 38,
-    BoolModeAnd = 39,
-
-    Label = 40,
-
-    CcNotEmpty = 43,
-    CcEmpty = 44,
-    CcGravity = 45,
-    CcCrystall = 46,
-    CcAlive = 47,
-    CcBolder = 48,
-    CcSand = 49,
-    CcRock = 50,
-    CcDead = 51,
-    CccRedRock = 52,
-    CccBlackRock = 53,
-    CcAcid = 54,
-    CccQuadro = 57,
-    CccRoad = 58,
-    CccRedBlock = 59,
-    CccYellowBlock = 60,
-    CccBox = 74,
-    CccOpor = 76,
-    CccGreenBlock = 77,
-
-    VarMore = 119,
-    VarLess = 120,
-    VarEqual = 123,
-
-    CellWw = 131,
-    CellAa = 132,
-    CellSs = 133,
-    CellDd = 134,
-    CellF = 135,
-    CellFf = 136,
-
-    GoSubF = 137,
-    ReturnF = 138,
-
-    IfNotGoTo = 139,
-    IfGoTo = 140,
-
-    StdDigg = 141,
-    StdBuild = 142,
-    StdHeal = 143,
-    ProgFlip = 144,
-    StdMine = 145,
-
-    CcGun = 146,
-    FillGun = 147,
-
-    CbHp = 148,
-    CbHp50 = 149,
-
-    CellRightHand = 156,
-    CellLeftHand = 157,
-
-    ModeAutodiggOn = 158,
-    ModeAutodiggOff = 159,
-    ModeAgrOn = 160,
-    ModeAgrOff = 161,
-
-    ActionB1 = 162,
-    ActionB3 = 163,
-    ActionB2 = 164,
-    ActionWb = 165,
-
-    OnResp = 166,
-
-    ActionGeopack = 167,
-    ActionZm = 168,
-    ActionC190 = 169,
-    ActionPoly = 170,
-    ActionUp = 171,
-    ActionCraft = 172,
-    ActionNano = 173,
-    ActionRembot = 174,
-
-    InvDirW = 175,
-    InvDirA = 176,
-    InvDirS = 177,
-    InvDirD = 178,
-
-    HandModeOn = 179,
-    HandModeOff = 180,
-
-    DebugBreak = 181,
-    DebugSet = 182,
+    Empty,
+    Back,
+
+    Start,
+    End,
+
+    MoveW,
+    MoveA,
+    MoveS,
+    MoveD,
+
+    Digg,
+
+    LookW,
+    LookA,
+    LookS,
+    LookD,
+
+    Last,
+
+    MoveF,
+
+    RotateCcw,
+    RotateCw,
+
+    ActionBuild,
+    ActionGeo,
+    ActionRoad,
+    ActionHeal,
+    ActionQuadro,
+    ActionRandom,
+    ActionBibika,
+
+    GoTo,
+    GoSub,
+    GoSub1,
+    Return,
+    Return1,
+
+    CellWa,
+    CellSd,
+    CellW,
+    CellDw,
+    CellA,
+    CellD,
+    CellAs,
+    CellS,
+
+    BoolModeOr,
+    BoolModeAnd,
+
+    Label,
+
+    CcNotEmpty,
+    CcEmpty,
+    CcGravity,
+    CcCrystall,
+    CcAlive,
+    CcBolder,
+    CcSand,
+    CcRock,
+    CcDead,
+    CccRedRock,
+    CccBlackRock,
+    CcAcid,
+    CccQuadro,
+    CccRoad,
+    CccRedBlock,
+    CccYellowBlock,
+    CccBox,
+    CccOpor,
+    CccGreenBlock,
+
+    VarMore,
+    VarLess,
+    VarEqual,
+
+    CellWw,
+    CellAa,
+    CellSs,
+    CellDd,
+    CellF,
+    CellFf,
+
+    GoSubF,
+    ReturnF,
+
+    IfNotGoTo,
+    IfGoTo,
+
+    StdDigg,
+    StdBuild,
+    StdHeal,
+    ProgFlip,
+    StdMine,
+
+    CcGun,
+    FillGun,
+
+    CbHp,
+    CbHp50,
+
+    CellRightHand,
+    CellLeftHand,
+
+    ModeAutodiggOn,
+    ModeAutodiggOff,
+    ModeAgrOn,
+    ModeAgrOff,
+
+    ActionB1,
+    ActionB3,
+    ActionB2,
+    ActionWb,
+
+    OnResp,
+
+    ActionGeopack,
+    ActionZm,
+    ActionC190,
+    ActionPoly,
+    ActionUp,
+    ActionCraft,
+    ActionNano,
+    ActionRembot,
+
+    InvDirW,
+    InvDirA,
+    InvDirS,
+    InvDirD,
+
+    HandModeOn,
+    HandModeOff,
+
+    DebugBreak,
+    DebugSet,
+
+    Restart,
+
+    /// An id unknown to this crate, e.g. from a newer client. Contains the raw value, which isn't
+    /// the [value](Self::value) of a known id.
+    ///
+    /// The variant can't enforce this, so ids like `Unknown(4)` can be built but aren't equal to
+    /// the known ones. [`from_value`](Self::from_value) never returns them and
+    /// [`Instruction`]s [normalize](Self::normalize) their ids, so they don't get into a
+    /// [`Program`].
+    ///
+    /// Formats storing raw values keep such ids through round-trips. Formats without a spelling
+    /// of them (e.g. [New Text format](crate::serialization::native::new)) report an error instead
+    /// of dropping them.
+    Unknown(u8),
 }
 
 impl InstructionId {
-    /// Returns the appropriate [`InstructionKind`].
+    /// Returns the appropriate [`InstructionKind`]. [`Unknown`](Self::Unknown) ids are
+    /// [`Simple`](InstructionKind::Simple).
     pub fn kind(self) -> InstructionKind {
+        self.info()
+            .map_or(InstructionKind::Simple, InstructionInfo::kind)
+    }
+    /// Returns the numeric value of the id in the native client.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use m3c::formats::internal::InstructionId;
+    ///
+    /// assert_eq!(4, InstructionId::MoveW.value());
+    /// assert_eq!(250, InstructionId::Unknown(250).value());
+    /// ```
+    pub fn value(self) -> u8 {
         match self {
-            Self::DebugBreak | Self::DebugSet => InstructionKind::String,
-
-            Self::VarMore | Self::VarLess | Self::VarEqual => InstructionKind::VarCmp,
+            Self::Unknown(value) => value,
+            _ => self.info().expect("known ids are registered").value(),
+        }
+    }
+    /// Returns the id with the given numeric value, or an [`Unknown`](Self::Unknown) id if there
+    /// is no such id. See also the [`TryFrom<u8>`](#impl-TryFrom<u8>-for-InstructionId)
+    /// implementation, which rejects unknown values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use m3c::formats::internal::InstructionId;
+    ///
+    /// assert_eq!(InstructionId::MoveW, InstructionId::from_value(4));
+    /// assert_eq!(InstructionId::Unknown(34), InstructionId::from_value(34));
+    /// ```
+    pub fn from_value(value: u8) -> Self {
+        registry::find_by_value(value).map_or(Self::Unknown(value), InstructionInfo::id)
+    }
+    /// Returns the known id if this one is [`Unknown`](Self::Unknown) with the value of a known
+    /// id, otherwise returns this id.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use m3c::formats::internal::InstructionId;
+    ///
+    /// assert_eq!(InstructionId::MoveW, InstructionId::Unknown(4).normalize());
+    /// assert_eq!(InstructionId::Unknown(34), InstructionId::Unknown(34).normalize());
+    /// ```
+    pub fn normalize(self) -> Self {
+        Self::from_value(self.value())
+    }
+    /// Checks if the id isn't [`Unknown`](Self::Unknown).
+    pub fn is_known(self) -> bool {
+        !matches!(self, Self::Unknown(_))
+    }
+}
 
-            Self::GoTo
-            | Self::GoSub
-            | Self::GoSub1
-            | Self::Label
-            | Self::GoSubF
-            | Self::IfNotGoTo
-            | Self::IfGoTo
-            | Self::OnResp => InstructionKind::Label,
+impl TryFrom<u8> for InstructionId {
+    type Error = UnknownInstructionIdError;
 
-            _ => InstructionKind::Simple,
+    /// Returns the id with the given numeric value.
+    ///
+    /// # Errors
+    ///
+    /// If there is no such id, an [`UnknownInstructionIdError`] will be returned.
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match Self::from_value(value) {
+            Self::Unknown(value) => Err(UnknownInstructionIdError { value }),
+            id => Ok(id),
         }
     }
 }
 
+impl From<InstructionId> for u8 {
+    /// Returns the [value](InstructionId::value).
+    fn from(id: InstructionId) -> Self {
+        id.value()
+    }
+}
+
 impl Default for InstructionId {
     /// Returns [Empty](Self::Empty).
     fn default() -> Self {
@@ -240,6 +347,7 @@ impl Default for InstructionId {
 ///
 /// To get a kind of [`Instruction`] see the [`Instruction::kind`] method. To get a kind of
 /// [`InstructionId`] see the [`InstructionId::kind`] method.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum InstructionKind {
     /// Instructions of this kind don't contain any additional info.
     Simple,
@@ -291,6 +399,7 @@ impl Instruction {
     /// If the given `instruction_id` is not appropriate to the [`Simple`](InstructionKind::Simple)
     /// kind an [`UnsupportedInstructionId`] will be returned.
     pub fn new_simple(instruction_id: InstructionId) -> Result<Self, UnsupportedInstructionId> {
+        let instruction_id = instruction_id.normalize();
         match instruction_id.kind() {
             InstructionKind::Simple => Ok(Instruction {
                 id: instruction_id,
@@ -309,6 +418,7 @@ impl Instruction {
         instruction_id: InstructionId,
         label: LabelIdentifierLiteral,
     ) -> Result<Self, UnsupportedInstructionId> {
+        let instruction_id = instruction_id.normalize();
         match instruction_id.kind() {
             InstructionKind::Label => Ok(Instruction {
                 id: instruction_id,
@@ -328,6 +438,7 @@ impl Instruction {
         identifier: VariableIdentifierLiteral,
         value: VariableValueLiteral,
     ) -> Result<Self, UnsupportedInstructionId> {
+        let instruction_id = instruction_id.normalize();
         match instruction_id.kind() {
             InstructionKind::VarCmp => Ok(Instruction {
                 id: instruction_id,
@@ -346,6 +457,7 @@ impl Instruction {
         instruction_id: InstructionId,
         string_literal: StringLiteral,
     ) -> Result<Self, UnsupportedInstructionId> {
+        let instruction_id = instruction_id.normalize();
         match instruction_id.kind() {
             InstructionKind::String => Ok(Instruction {
                 id: instruction_id,
@@ -383,7 +495,9 @@ impl Default for Instruction {
 // region: instruction_position
 
 /// Describe an instruction position at the program.
-#[derive(Copy, Clone, Debug)]
+///
+/// Positions are ordered the same way as instructions in the program.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct InstructionPosition {
     page: u8,
     row: u8,
@@ -428,6 +542,10 @@ impl InstructionPosition {
     pub fn column(&self) -> u8 {
         self.column
     }
+    /// Formats this position as `page:row:column`.
+    pub fn custom_format(&self) -> String {
+        format_args!("{}:{}:{}", self.page, self.row, self.column).to_string()
+    }
     /// Moves this [`InstructionPosition`] to the next position.
     ///
     /// # Errors
@@ -503,6 +621,20 @@ impl From<InstructionPosition> for usize {
     }
 }
 
+impl TryFrom<usize> for InstructionPosition {
+    type Error = InstructionPositionConstructionError;
+    /// Constructs the position from the "flat index".
+    fn try_from(index: usize) -> Result<Self, Self::Error> {
+        if index >= Program::INSTRUCTIONS_PER_PROGRAM {
+            return Err(InstructionPositionConstructionError {});
+        }
+        let column = index % Program::INSTRUCTIONS_PER_ROW;
+        let row = index / Program::INSTRUCTIONS_PER_ROW % Program::ROWS_PER_PAGE;
+        let page = index / (Program::INSTRUCTIONS_PER_ROW * Program::ROWS_PER_PAGE);
+        Self::new(page as u8, row as u8, column as u8)
+    }
+}
+
 // endregion: instruction_position
 
 /// An iterator over the [`Instruction`]s and their [position](InstructionPosition)s in
@@ -538,6 +670,17 @@ impl<'a> Iterator for InstructionPositions<'a> {
 /// * each program contains [16](Self::PAGES_PER_PROGRAM) pages
 /// * each page contains [12](Self::ROWS_PER_PAGE) rows
 /// * each row contains [16](Self::INSTRUCTIONS_PER_ROW) instructions
+///
+/// With the `serde` feature, a program is (de)serialized as a sparse list of its non-empty
+/// instructions:
+///
+/// ```json
+/// [
+///   {"page": 0, "row": 0, "column": 0, "instruction": "MOVE_W"},
+///   {"page": 0, "row": 0, "column": 1, "instruction": "GOTO", "label": "a"},
+///   {"page": 0, "row": 1, "column": 0, "instruction": "VAR_MORE", "variable": "x", "value": 5}
+/// ]
+/// ```
 #[derive(Debug, PartialEq, Eq)]
 pub struct Program {
     instructions: Box<[Instruction; Self::INSTRUCTIONS_PER_PROGRAM]>,
@@ -563,7 +706,7 @@ impl Program {
     /// The iterator yields tuples. The [`InstructionPosition`] is first, the [`Instruction`] is
     /// second.
     #[must_use]
-    pub fn instruction_positions(&self) -> InstructionPositions {
+    pub fn instruction_positions(&self) -> InstructionPositions<'_> {
         // see https://doc.rust-lang.org/std/primitive.str.html#method.char_indices
         InstructionPositions {
             position: InstructionPosition::default(),
@@ -625,6 +768,70 @@ impl IndexMut<InstructionPosition> for Program {
 #[cfg(test)]
 mod tests {
 
+    #[cfg(test)]
+    mod instruction_id {
+
+        use super::super::{
+            Instruction, InstructionId, InstructionKind, UnknownInstructionIdError,
+        };
+
+        #[test]
+        fn values() {
+            for value in 0..=u8::MAX {
+                let id = InstructionId::from_value(value);
+                assert_eq!(value, id.value());
+                assert_eq!(value, u8::from(id));
+                match InstructionId::try_from(value) {
+                    Ok(known) => assert_eq!(id, known),
+                    Err(e) => {
+                        assert_eq!(UnknownInstructionIdError { value }, e);
+                        assert_eq!(InstructionId::Unknown(value), id);
+                    }
+                }
+            }
+            assert_eq!(Ok(InstructionId::Last), InstructionId::try_from(13));
+            assert_eq!(Ok(InstructionId::Restart), InstructionId::try_from(200));
+            assert!(InstructionId::try_from(34).is_err());
+            assert!(InstructionId::try_from(199).is_err());
+        }
+
+        #[test]
+        fn all() {
+            assert_eq!(109, InstructionId::ALL.len());
+            assert!(InstructionId::ALL
+                .windows(2)
+                .all(|w| w[0].value() < w[1].value()));
+            assert!(InstructionId::ALL.iter().all(|id| id.is_known()));
+            let known = (0..=u8::MAX)
+                .filter(|&value| InstructionId::try_from(value).is_ok())
+                .count();
+            assert_eq!(InstructionId::ALL.len(), known);
+        }
+
+        #[test]
+        fn unknown() {
+            let id = InstructionId::Unknown(250);
+            assert!(!id.is_known());
+            assert_eq!(None, id.info());
+            assert_eq!(InstructionKind::Simple, id.kind());
+        }
+
+        #[test]
+        fn normalize() {
+            assert_eq!(InstructionId::MoveW, InstructionId::Unknown(4).normalize());
+            assert_eq!(InstructionId::GoTo, InstructionId::Unknown(24).normalize());
+            assert_eq!(InstructionId::MoveW, InstructionId::MoveW.normalize());
+            assert_eq!(
+                InstructionId::Unknown(250),
+                InstructionId::Unknown(250).normalize()
+            );
+
+            let instruction = Instruction::new_simple(InstructionId::Unknown(4)).unwrap();
+            assert_eq!(InstructionId::MoveW, instruction.id());
+            assert!(Instruction::new_simple(InstructionId::Unknown(24)).is_err());
+        }
+    }
+
     #[cfg(test)]
     mod instruction_position {
 
@@ -0,0 +1,1197 @@
+//! The registry of the instructions: the facts about every [`InstructionId`].
+//!
+//! The registry is the only place instructions are described. [`InstructionId::kind`], the
+//! mnemonics of the [assembly-like format](crate::serialization::custom::assembly) and the
+//! tables of the [New Text format](crate::serialization::native::new) are derived from it.
+//!
+//! # Examples
+//!
+//! ```
+//! use m3c::formats::internal::registry::{self, Category};
+//! use m3c::formats::internal::InstructionId;
+//!
+//! let info = InstructionId::GoTo.info().unwrap();
+//! assert_eq!(24, info.value());
+//! assert_eq!("GOTO", info.mnemonic());
+//! assert_eq!(&[">", "|"], info.ntf());
+//! assert_eq!(Category::Control, info.category());
+//!
+//! assert_eq!(Some(info), registry::find_by_mnemonic("GOTO"));
+//! assert_eq!(Some(info), registry::find_by_value(24));
+//! ```
+
+use super::literals::LiteralType;
+use super::{InstructionId, InstructionKind};
+
+/// What an instruction is used for.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Category {
+    /// Changes the control flow: jumps, calls, labels, the start and the end.
+    Control,
+    /// Moves or turns the robot.
+    Move,
+    /// Makes the robot act on the world: dig, build, heal and so on.
+    Action,
+    /// Switches a mode of the robot or of the conditions.
+    Mode,
+    /// Selects the cell to check.
+    Cell,
+    /// Checks the selected cell or the robot.
+    Check,
+    /// Compares a variable with a value.
+    Variable,
+    /// Helps debugging.
+    Debug,
+}
+
+/// The facts about an [`InstructionId`].
+///
+/// See the [module](self) docs for an example.
+#[derive(Debug, PartialEq, Eq)]
+pub struct InstructionInfo {
+    id: InstructionId,
+    value: u8,
+    kind: InstructionKind,
+    mnemonic: &'static str,
+    ntf: &'static [&'static str],
+    category: Category,
+    description: &'static str,
+}
+
+impl InstructionInfo {
+    /// Returns the id.
+    pub fn id(&self) -> InstructionId {
+        self.id
+    }
+    /// Returns the numeric value of the id in the native client.
+    pub fn value(&self) -> u8 {
+        self.value
+    }
+    /// Returns the kind.
+    pub fn kind(&self) -> InstructionKind {
+        self.kind
+    }
+    /// Returns the identifier in the native client (e.g. `MOVE_W`).
+    pub fn mnemonic(&self) -> &'static str {
+        self.mnemonic
+    }
+    /// Returns the spelling in New Text format: the chars before, between and after the
+    /// [literals](InstructionKind::literals). E.g. `["(", ">", ")"]` for `(x>1)`.
+    ///
+    /// Returns an empty slice if New Text format has no spelling of the instruction.
+    pub fn ntf(&self) -> &'static [&'static str] {
+        self.ntf
+    }
+    /// Returns the category.
+    pub fn category(&self) -> Category {
+        self.category
+    }
+    /// Returns the human description.
+    pub fn description(&self) -> &'static str {
+        self.description
+    }
+}
+
+const fn info(
+    id: InstructionId,
+    value: u8,
+    kind: InstructionKind,
+    mnemonic: &'static str,
+    ntf: &'static [&'static str],
+    category: Category,
+    description: &'static str,
+) -> InstructionInfo {
+    InstructionInfo {
+        id,
+        value,
+        kind,
+        mnemonic,
+        ntf,
+        category,
+        description,
+    }
+}
+
+/// All the instructions sorted by id (and by value).
+pub static INSTRUCTIONS: [InstructionInfo; COUNT] = TABLE;
+
+/// The number of the known ids.
+const COUNT: usize = 109;
+
+const TABLE: [InstructionInfo; COUNT] = [
+    info(
+        InstructionId::Empty,
+        0,
+        InstructionKind::Simple,
+        "EMPTY",
+        &[" "],
+        Category::Control,
+        "An empty cell, does nothing.",
+    ),
+    info(
+        InstructionId::Back,
+        1,
+        InstructionKind::Simple,
+        "BACK",
+        &[","],
+        Category::Control,
+        "Starts the program over, forgetting the return positions.",
+    ),
+    info(
+        InstructionId::Start,
+        2,
+        InstructionKind::Simple,
+        "START",
+        &["#S"],
+        Category::Control,
+        "Marks the start of the program.",
+    ),
+    info(
+        InstructionId::End,
+        3,
+        InstructionKind::Simple,
+        "END",
+        &["#E"],
+        Category::Control,
+        "Finishes the program.",
+    ),
+    info(
+        InstructionId::MoveW,
+        4,
+        InstructionKind::Simple,
+        "MOVE_W",
+        &["^W"],
+        Category::Move,
+        "Moves the robot up.",
+    ),
+    info(
+        InstructionId::MoveA,
+        5,
+        InstructionKind::Simple,
+        "MOVE_A",
+        &["^A"],
+        Category::Move,
+        "Moves the robot left.",
+    ),
+    info(
+        InstructionId::MoveS,
+        6,
+        InstructionKind::Simple,
+        "MOVE_S",
+        &["^S"],
+        Category::Move,
+        "Moves the robot down.",
+    ),
+    info(
+        InstructionId::MoveD,
+        7,
+        InstructionKind::Simple,
+        "MOVE_D",
+        &["^D"],
+        Category::Move,
+        "Moves the robot right.",
+    ),
+    info(
+        InstructionId::Digg,
+        8,
+        InstructionKind::Simple,
+        "DIGG",
+        &["z"],
+        Category::Action,
+        "Digs the cell in front of the robot.",
+    ),
+    info(
+        InstructionId::LookW,
+        9,
+        InstructionKind::Simple,
+        "LOOK_W",
+        &["w"],
+        Category::Move,
+        "Turns the robot up.",
+    ),
+    info(
+        InstructionId::LookA,
+        10,
+        InstructionKind::Simple,
+        "LOOK_A",
+        &["a"],
+        Category::Move,
+        "Turns the robot left.",
+    ),
+    info(
+        InstructionId::LookS,
+        11,
+        InstructionKind::Simple,
+        "LOOK_S",
+        &["s"],
+        Category::Move,
+        "Turns the robot down.",
+    ),
+    info(
+        InstructionId::LookD,
+        12,
+        InstructionKind::Simple,
+        "LOOK_D",
+        &["d"],
+        Category::Move,
+        "Turns the robot right.",
+    ),
+    info(
+        InstructionId::Last,
+        13,
+        InstructionKind::Simple,
+        "LAST",
+        &[],
+        Category::Control,
+        "Marks the end of the basic instructions in the client's list of ids. Does nothing.",
+    ),
+    info(
+        InstructionId::MoveF,
+        14,
+        InstructionKind::Simple,
+        "MOVE_F",
+        &["^F"],
+        Category::Move,
+        "Moves the robot forward.",
+    ),
+    info(
+        InstructionId::RotateCcw,
+        15,
+        InstructionKind::Simple,
+        "ROTATE_CCW",
+        &["CCW;"],
+        Category::Move,
+        "Turns the robot counterclockwise.",
+    ),
+    info(
+        InstructionId::RotateCw,
+        16,
+        InstructionKind::Simple,
+        "ROTATE_CW",
+        &["CW;"],
+        Category::Move,
+        "Turns the robot clockwise.",
+    ),
+    info(
+        InstructionId::ActionBuild,
+        17,
+        InstructionKind::Simple,
+        "ACTION_BUILD",
+        &["b"],
+        Category::Action,
+        "Builds a support in front of the robot.",
+    ),
+    info(
+        InstructionId::ActionGeo,
+        18,
+        InstructionKind::Simple,
+        "ACTION_GEO",
+        &["g"],
+        Category::Action,
+        "Picks up or drops a geological block.",
+    ),
+    info(
+        InstructionId::ActionRoad,
+        19,
+        InstructionKind::Simple,
+        "ACTION_ROAD",
+        &["r"],
+        Category::Action,
+        "Builds a road in front of the robot.",
+    ),
+    info(
+        InstructionId::ActionHeal,
+        20,
+        InstructionKind::Simple,
+        "ACTION_HEAL",
+        &["h"],
+        Category::Action,
+        "Heals the robot.",
+    ),
+    info(
+        InstructionId::ActionQuadro,
+        21,
+        InstructionKind::Simple,
+        "ACTION_QUADRO",
+        &["q"],
+        Category::Action,
+        "Builds a quadro block in front of the robot.",
+    ),
+    info(
+        InstructionId::ActionRandom,
+        22,
+        InstructionKind::Simple,
+        "ACTION_RANDOM",
+        &["RAND;"],
+        Category::Action,
+        "Moves the robot in a random direction.",
+    ),
+    info(
+        InstructionId::ActionBibika,
+        23,
+        InstructionKind::Simple,
+        "ACTION_BIBIKA",
+        &["BEEP;"],
+        Category::Action,
+        "Beeps.",
+    ),
+    info(
+        InstructionId::GoTo,
+        24,
+        InstructionKind::Label,
+        "GOTO",
+        &[">", "|"],
+        Category::Control,
+        "Jumps to the label.",
+    ),
+    info(
+        InstructionId::GoSub,
+        25,
+        InstructionKind::Label,
+        "GOSUB",
+        &[":>", ">"],
+        Category::Control,
+        "Calls the label as a subroutine.",
+    ),
+    info(
+        InstructionId::GoSub1,
+        26,
+        InstructionKind::Label,
+        "GOSUB1",
+        &["->", ">"],
+        Category::Control,
+        "Calls the label as a subroutine, remembering only one return position.",
+    ),
+    info(
+        InstructionId::Return,
+        27,
+        InstructionKind::Simple,
+        "RETURN",
+        &["<|"],
+        Category::Control,
+        "Returns from a `GOSUB` subroutine.",
+    ),
+    info(
+        InstructionId::Return1,
+        28,
+        InstructionKind::Simple,
+        "RETURN1",
+        &["<-|"],
+        Category::Control,
+        "Returns from a `GOSUB1` subroutine.",
+    ),
+    info(
+        InstructionId::CellWa,
+        29,
+        InstructionKind::Simple,
+        "CELL_WA",
+        &["[WA]"],
+        Category::Cell,
+        "Selects the cell up-left of the robot.",
+    ),
+    info(
+        InstructionId::CellSd,
+        30,
+        InstructionKind::Simple,
+        "CELL_SD",
+        &["[SD]"],
+        Category::Cell,
+        "Selects the cell down-right of the robot.",
+    ),
+    info(
+        InstructionId::CellW,
+        31,
+        InstructionKind::Simple,
+        "CELL_W",
+        &["[W]"],
+        Category::Cell,
+        "Selects the cell above the robot.",
+    ),
+    info(
+        InstructionId::CellDw,
+        32,
+        InstructionKind::Simple,
+        "CELL_DW",
+        &["[DW]"],
+        Category::Cell,
+        "Selects the cell up-right of the robot.",
+    ),
+    info(
+        InstructionId::CellA,
+        33,
+        InstructionKind::Simple,
+        "CELL_A",
+        &["[A]"],
+        Category::Cell,
+        "Selects the cell left of the robot.",
+    ),
+    info(
+        InstructionId::CellD,
+        35,
+        InstructionKind::Simple,
+        "CELL_D",
+        &["[D]"],
+        Category::Cell,
+        "Selects the cell right of the robot.",
+    ),
+    info(
+        InstructionId::CellAs,
+        36,
+        InstructionKind::Simple,
+        "CELL_AS",
+        &["[AS]"],
+        Category::Cell,
+        "Selects the cell down-left of the robot.",
+    ),
+    info(
+        InstructionId::CellS,
+        37,
+        InstructionKind::Simple,
+        "CELL_S",
+        &["[S]"],
+        Category::Cell,
+        "Selects the cell below the robot.",
+    ),
+    info(
+        InstructionId::BoolModeOr,
+        38,
+        InstructionKind::Simple,
+        "BOOLMODE_OR",
+        &["OR"],
+        Category::Mode,
+        "Combines the following checks with `OR`.",
+    ),
+    info(
+        InstructionId::BoolModeAnd,
+        39,
+        InstructionKind::Simple,
+        "BOOLMODE_AND",
+        &["AND"],
+        Category::Mode,
+        "Combines the following checks with `AND`.",
+    ),
+    info(
+        InstructionId::Label,
+        40,
+        InstructionKind::Label,
+        "LABEL",
+        &["|", ":"],
+        Category::Control,
+        "Defines the label.",
+    ),
+    info(
+        InstructionId::CcNotEmpty,
+        43,
+        InstructionKind::Simple,
+        "CC_NOTEMPTY",
+        &["=n"],
+        Category::Check,
+        "Checks the selected cell is not passable.",
+    ),
+    info(
+        InstructionId::CcEmpty,
+        44,
+        InstructionKind::Simple,
+        "CC_EMPTY",
+        &["=e"],
+        Category::Check,
+        "Checks the selected cell is passable.",
+    ),
+    info(
+        InstructionId::CcGravity,
+        45,
+        InstructionKind::Simple,
+        "CC_GRAVITY",
+        &["=f"],
+        Category::Check,
+        "Checks the selected cell falls.",
+    ),
+    info(
+        InstructionId::CcCrystall,
+        46,
+        InstructionKind::Simple,
+        "CC_CRYSTALL",
+        &["=c"],
+        Category::Check,
+        "Checks the selected cell is a crystal.",
+    ),
+    info(
+        InstructionId::CcAlive,
+        47,
+        InstructionKind::Simple,
+        "CC_ALIVE",
+        &["=a"],
+        Category::Check,
+        "Checks the selected cell is an alive crystal.",
+    ),
+    info(
+        InstructionId::CcBolder,
+        48,
+        InstructionKind::Simple,
+        "CC_BOLDER",
+        &["=b"],
+        Category::Check,
+        "Checks the selected cell is a boulder.",
+    ),
+    info(
+        InstructionId::CcSand,
+        49,
+        InstructionKind::Simple,
+        "CC_SAND",
+        &["=s"],
+        Category::Check,
+        "Checks the selected cell is sand.",
+    ),
+    info(
+        InstructionId::CcRock,
+        50,
+        InstructionKind::Simple,
+        "CC_ROCK",
+        &["=k"],
+        Category::Check,
+        "Checks the selected cell is a rock.",
+    ),
+    info(
+        InstructionId::CcDead,
+        51,
+        InstructionKind::Simple,
+        "CC_DEAD",
+        &["=d"],
+        Category::Check,
+        "Checks the selected cell is a dead rock.",
+    ),
+    info(
+        InstructionId::CccRedRock,
+        52,
+        InstructionKind::Simple,
+        "CCC_REDROCK",
+        &["=K"],
+        Category::Check,
+        "Checks the selected cell is a red rock.",
+    ),
+    info(
+        InstructionId::CccBlackRock,
+        53,
+        InstructionKind::Simple,
+        "CCC_BLACKROCK",
+        &["=B"],
+        Category::Check,
+        "Checks the selected cell is a black rock.",
+    ),
+    info(
+        InstructionId::CcAcid,
+        54,
+        InstructionKind::Simple,
+        "CC_ACID",
+        &["=A"],
+        Category::Check,
+        "Checks the selected cell is acid.",
+    ),
+    info(
+        InstructionId::CccQuadro,
+        57,
+        InstructionKind::Simple,
+        "CCC_QUADRO",
+        &["=q"],
+        Category::Check,
+        "Checks the selected cell is a quadro block.",
+    ),
+    info(
+        InstructionId::CccRoad,
+        58,
+        InstructionKind::Simple,
+        "CCC_ROAD",
+        &["=R"],
+        Category::Check,
+        "Checks the selected cell is a road.",
+    ),
+    info(
+        InstructionId::CccRedBlock,
+        59,
+        InstructionKind::Simple,
+        "CCC_REDBLOCK",
+        &["=r"],
+        Category::Check,
+        "Checks the selected cell is a red block.",
+    ),
+    info(
+        InstructionId::CccYellowBlock,
+        60,
+        InstructionKind::Simple,
+        "CCC_YELLOWBLOCK",
+        &["=y"],
+        Category::Check,
+        "Checks the selected cell is a yellow block.",
+    ),
+    info(
+        InstructionId::CccBox,
+        74,
+        InstructionKind::Simple,
+        "CCC_BOX",
+        &["=x"],
+        Category::Check,
+        "Checks the selected cell is a box.",
+    ),
+    info(
+        InstructionId::CccOpor,
+        76,
+        InstructionKind::Simple,
+        "CCC_OPOR",
+        &["=o"],
+        Category::Check,
+        "Checks the selected cell is a support.",
+    ),
+    info(
+        InstructionId::CccGreenBlock,
+        77,
+        InstructionKind::Simple,
+        "CCC_GREENBLOCK",
+        &["=g"],
+        Category::Check,
+        "Checks the selected cell is a green block.",
+    ),
+    info(
+        InstructionId::VarMore,
+        119,
+        InstructionKind::VarCmp,
+        "VAR_MORE",
+        &["(", ">", ")"],
+        Category::Variable,
+        "Checks the variable is greater than the value.",
+    ),
+    info(
+        InstructionId::VarLess,
+        120,
+        InstructionKind::VarCmp,
+        "VAR_LESS",
+        &["(", "<", ")"],
+        Category::Variable,
+        "Checks the variable is less than the value.",
+    ),
+    info(
+        InstructionId::VarEqual,
+        123,
+        InstructionKind::VarCmp,
+        "VAR_EQUAL",
+        &["(", "=", ")"],
+        Category::Variable,
+        "Checks the variable equals the value.",
+    ),
+    info(
+        InstructionId::CellWw,
+        131,
+        InstructionKind::Simple,
+        "CELL_WW",
+        &["[w]"],
+        Category::Cell,
+        "Selects the cell two cells above the robot.",
+    ),
+    info(
+        InstructionId::CellAa,
+        132,
+        InstructionKind::Simple,
+        "CELL_AA",
+        &["[a]"],
+        Category::Cell,
+        "Selects the cell two cells left of the robot.",
+    ),
+    info(
+        InstructionId::CellSs,
+        133,
+        InstructionKind::Simple,
+        "CELL_SS",
+        &["[s]"],
+        Category::Cell,
+        "Selects the cell two cells below the robot.",
+    ),
+    info(
+        InstructionId::CellDd,
+        134,
+        InstructionKind::Simple,
+        "CELL_DD",
+        &["[d]"],
+        Category::Cell,
+        "Selects the cell two cells right of the robot.",
+    ),
+    info(
+        InstructionId::CellF,
+        135,
+        InstructionKind::Simple,
+        "CELL_F",
+        &["[F]"],
+        Category::Cell,
+        "Selects the cell in front of the robot.",
+    ),
+    info(
+        InstructionId::CellFf,
+        136,
+        InstructionKind::Simple,
+        "CELL_FF",
+        &["[f]"],
+        Category::Cell,
+        "Selects the cell two cells in front of the robot.",
+    ),
+    info(
+        InstructionId::GoSubF,
+        137,
+        InstructionKind::Label,
+        "GOSUBF",
+        &["=>", ">"],
+        Category::Control,
+        "Calls the label as a subroutine with its own return stack.",
+    ),
+    info(
+        InstructionId::ReturnF,
+        138,
+        InstructionKind::Simple,
+        "RETURNF",
+        &["<=|"],
+        Category::Control,
+        "Returns from a `GOSUBF` subroutine.",
+    ),
+    info(
+        InstructionId::IfNotGoTo,
+        139,
+        InstructionKind::Label,
+        "IF_NOT_GOTO",
+        &["?", "<"],
+        Category::Control,
+        "Jumps to the label if the checks failed.",
+    ),
+    info(
+        InstructionId::IfGoTo,
+        140,
+        InstructionKind::Label,
+        "IF_GOTO",
+        &["!?", "<"],
+        Category::Control,
+        "Jumps to the label if the checks passed.",
+    ),
+    info(
+        InstructionId::StdDigg,
+        141,
+        InstructionKind::Simple,
+        "STD_DIGG",
+        &["DIGG;"],
+        Category::Action,
+        "Runs the standard digging routine of the client.",
+    ),
+    info(
+        InstructionId::StdBuild,
+        142,
+        InstructionKind::Simple,
+        "STD_BUILD",
+        &["BUILD;"],
+        Category::Action,
+        "Runs the standard building routine of the client.",
+    ),
+    info(
+        InstructionId::StdHeal,
+        143,
+        InstructionKind::Simple,
+        "STD_HEAL",
+        &["HEAL;"],
+        Category::Action,
+        "Runs the standard healing routine of the client.",
+    ),
+    info(
+        InstructionId::ProgFlip,
+        144,
+        InstructionKind::Simple,
+        "PROG_FLIP",
+        &["FLIP;"],
+        Category::Action,
+        "Flips the program of the robot.",
+    ),
+    info(
+        InstructionId::StdMine,
+        145,
+        InstructionKind::Simple,
+        "STD_MINE",
+        &["MINE;"],
+        Category::Action,
+        "Runs the standard mining routine of the client.",
+    ),
+    info(
+        InstructionId::CcGun,
+        146,
+        InstructionKind::Simple,
+        "CC_GUN",
+        &["=G"],
+        Category::Check,
+        "Checks the selected cell is a gun.",
+    ),
+    info(
+        InstructionId::FillGun,
+        147,
+        InstructionKind::Simple,
+        "FILL_GUN",
+        &["FILL;"],
+        Category::Action,
+        "Fills the gun.",
+    ),
+    info(
+        InstructionId::CbHp,
+        148,
+        InstructionKind::Simple,
+        "CB_HP",
+        &["=hp-"],
+        Category::Check,
+        "Checks the robot is hurt.",
+    ),
+    info(
+        InstructionId::CbHp50,
+        149,
+        InstructionKind::Simple,
+        "CB_HP50",
+        &["=hp50"],
+        Category::Check,
+        "Checks the robot has less than half of its health.",
+    ),
+    info(
+        InstructionId::CellRightHand,
+        156,
+        InstructionKind::Simple,
+        "CELL_RIGHT_HAND",
+        &["[r]"],
+        Category::Cell,
+        "Selects the cell at the right hand of the robot.",
+    ),
+    info(
+        InstructionId::CellLeftHand,
+        157,
+        InstructionKind::Simple,
+        "CELL_LEFT_HAND",
+        &["[l]"],
+        Category::Cell,
+        "Selects the cell at the left hand of the robot.",
+    ),
+    info(
+        InstructionId::ModeAutodiggOn,
+        158,
+        InstructionKind::Simple,
+        "MODE_AUTODIGG_ON",
+        &["AUT+"],
+        Category::Mode,
+        "Turns on digging on blocked moves.",
+    ),
+    info(
+        InstructionId::ModeAutodiggOff,
+        159,
+        InstructionKind::Simple,
+        "MODE_AUTODIGG_OFF",
+        &["AUT-"],
+        Category::Mode,
+        "Turns off digging on blocked moves.",
+    ),
+    info(
+        InstructionId::ModeAgrOn,
+        160,
+        InstructionKind::Simple,
+        "MODE_AGR_ON",
+        &["AGR+"],
+        Category::Mode,
+        "Turns on the aggression.",
+    ),
+    info(
+        InstructionId::ModeAgrOff,
+        161,
+        InstructionKind::Simple,
+        "MODE_AGR_OFF",
+        &["AGR-"],
+        Category::Mode,
+        "Turns off the aggression.",
+    ),
+    info(
+        InstructionId::ActionB1,
+        162,
+        InstructionKind::Simple,
+        "ACTION_B1",
+        &["B1;"],
+        Category::Action,
+        "Builds a green block in front of the robot.",
+    ),
+    info(
+        InstructionId::ActionB3,
+        163,
+        InstructionKind::Simple,
+        "ACTION_B3",
+        &["B2;"],
+        Category::Action,
+        "Builds a red block in front of the robot.",
+    ),
+    info(
+        InstructionId::ActionB2,
+        164,
+        InstructionKind::Simple,
+        "ACTION_B2",
+        &["B3;"],
+        Category::Action,
+        "Builds a yellow block in front of the robot.",
+    ),
+    info(
+        InstructionId::ActionWb,
+        165,
+        InstructionKind::Simple,
+        "ACTION_WB",
+        &["VB;"],
+        Category::Action,
+        "Builds a war block in front of the robot.",
+    ),
+    info(
+        InstructionId::OnResp,
+        166,
+        InstructionKind::Label,
+        "ON_RESP",
+        &["#R", "<"],
+        Category::Control,
+        "Sets the label as the response handler.",
+    ),
+    info(
+        InstructionId::ActionGeopack,
+        167,
+        InstructionKind::Simple,
+        "ACTION_GEOPACK",
+        &["GEO;"],
+        Category::Action,
+        "Uses the geopack.",
+    ),
+    info(
+        InstructionId::ActionZm,
+        168,
+        InstructionKind::Simple,
+        "ACTION_ZM",
+        &["ZZ;"],
+        Category::Action,
+        "Uses the zoom.",
+    ),
+    info(
+        InstructionId::ActionC190,
+        169,
+        InstructionKind::Simple,
+        "ACTION_C190",
+        &["C190;"],
+        Category::Action,
+        "Uses the C190 gun.",
+    ),
+    info(
+        InstructionId::ActionPoly,
+        170,
+        InstructionKind::Simple,
+        "ACTION_POLY",
+        &["POLY;"],
+        Category::Action,
+        "Uses the polymer.",
+    ),
+    info(
+        InstructionId::ActionUp,
+        171,
+        InstructionKind::Simple,
+        "ACTION_UP",
+        &["UP;"],
+        Category::Action,
+        "Upgrades the block in front of the robot.",
+    ),
+    info(
+        InstructionId::ActionCraft,
+        172,
+        InstructionKind::Simple,
+        "ACTION_CRAFT",
+        &["CRAFT;"],
+        Category::Action,
+        "Crafts.",
+    ),
+    info(
+        InstructionId::ActionNano,
+        173,
+        InstructionKind::Simple,
+        "ACTION_NANO",
+        &["NANO;"],
+        Category::Action,
+        "Uses nanobots.",
+    ),
+    info(
+        InstructionId::ActionRembot,
+        174,
+        InstructionKind::Simple,
+        "ACTION_REMBOT",
+        &["REM;"],
+        Category::Action,
+        "Repairs the robot.",
+    ),
+    info(
+        InstructionId::InvDirW,
+        175,
+        InstructionKind::Simple,
+        "INVDIR_W",
+        &["iw"],
+        Category::Mode,
+        "Inverts moves and turns up.",
+    ),
+    info(
+        InstructionId::InvDirA,
+        176,
+        InstructionKind::Simple,
+        "INVDIR_A",
+        &["ia"],
+        Category::Mode,
+        "Inverts moves and turns left.",
+    ),
+    info(
+        InstructionId::InvDirS,
+        177,
+        InstructionKind::Simple,
+        "INVDIR_S",
+        &["is"],
+        Category::Mode,
+        "Inverts moves and turns down.",
+    ),
+    info(
+        InstructionId::InvDirD,
+        178,
+        InstructionKind::Simple,
+        "INVDIR_D",
+        &["id"],
+        Category::Mode,
+        "Inverts moves and turns right.",
+    ),
+    info(
+        InstructionId::HandModeOn,
+        179,
+        InstructionKind::Simple,
+        "HANDMODE_ON",
+        &["Hand+"],
+        Category::Mode,
+        "Turns on the hand mode.",
+    ),
+    info(
+        InstructionId::HandModeOff,
+        180,
+        InstructionKind::Simple,
+        "HANDMODE_OFF",
+        &["Hand-"],
+        Category::Mode,
+        "Turns off the hand mode.",
+    ),
+    info(
+        InstructionId::DebugBreak,
+        181,
+        InstructionKind::String,
+        "DEBUG_BREAK",
+        &["!{", "}"],
+        Category::Debug,
+        "Stops debuggers watching the tag, an empty tag stops all of them.",
+    ),
+    info(
+        InstructionId::DebugSet,
+        182,
+        InstructionKind::String,
+        "DEBUG_SET",
+        &["{", "}"],
+        Category::Debug,
+        "Sets the debug tag.",
+    ),
+    info(
+        InstructionId::Restart,
+        200,
+        InstructionKind::Simple,
+        "RESTART",
+        &[],
+        Category::Control,
+        "Restarts the program from the first cell, the same as `BACK`.",
+    ),
+];
+
+/// Returns the instruction with the given numeric value.
+///
+/// Returns `None` if there is no such instruction.
+pub fn find_by_value(value: u8) -> Option<&'static InstructionInfo> {
+    INSTRUCTIONS
+        .binary_search_by_key(&value, InstructionInfo::value)
+        .ok()
+        .map(|i| &INSTRUCTIONS[i])
+}
+
+/// Returns the instruction with the given identifier from the native client.
+///
+/// Returns `None` if there is no such instruction.
+pub fn find_by_mnemonic(mnemonic: &str) -> Option<&'static InstructionInfo> {
+    INSTRUCTIONS.iter().find(|info| info.mnemonic == mnemonic)
+}
+
+impl InstructionId {
+    /// All the known ids sorted by value.
+    pub const ALL: [Self; COUNT] = {
+        let mut ids = [Self::Empty; COUNT];
+        let mut i = 0;
+        while i < COUNT {
+            ids[i] = TABLE[i].id;
+            i += 1;
+        }
+        ids
+    };
+
+    /// Returns the facts about the id from the [registry](self).
+    ///
+    /// Returns `None` if the id is [`Unknown`](Self::Unknown).
+    pub fn info(self) -> Option<&'static InstructionInfo> {
+        INSTRUCTIONS
+            .binary_search_by_key(&self, InstructionInfo::id)
+            .ok()
+            .map(|i| &INSTRUCTIONS[i])
+    }
+}
+
+impl InstructionKind {
+    /// Returns the types of the literals an instruction of this kind contains, in order.
+    pub fn literals(self) -> &'static [LiteralType] {
+        match self {
+            Self::Simple => &[],
+            Self::Label => &[LiteralType::LabelIdentifierLiteral],
+            Self::VarCmp => &[
+                LiteralType::VariableIdentifierLiteral,
+                LiteralType::VariableValueLiteral,
+            ],
+            Self::String => &[LiteralType::StringLiteral],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{find_by_mnemonic, find_by_value, INSTRUCTIONS};
+    use crate::formats::internal::InstructionId;
+
+    #[test]
+    fn consistent() {
+        assert!(INSTRUCTIONS
+            .windows(2)
+            .all(|w| w[0].id() < w[1].id() && w[0].value() < w[1].value()));
+        let mut mnemonics = HashSet::new();
+        let mut spellings = HashSet::new();
+        for info in &INSTRUCTIONS {
+            assert!(mnemonics.insert(info.mnemonic()), "{}", info.mnemonic());
+            if !info.ntf().is_empty() {
+                assert!(spellings.insert(info.ntf()), "{}", info.mnemonic());
+                assert_eq!(
+                    info.kind().literals().len() + 1,
+                    info.ntf().len(),
+                    "{}",
+                    info.mnemonic()
+                );
+            }
+            assert!(!info.description().is_empty());
+            assert_eq!(info.value(), info.id().value(), "{}", info.mnemonic());
+            assert_eq!(Some(info), find_by_value(info.value()));
+            assert_eq!(Some(info), find_by_mnemonic(info.mnemonic()));
+            assert_eq!(Some(info), info.id().info());
+        }
+        assert_eq!(None, InstructionId::Unknown(34).info());
+        assert_eq!(None, find_by_value(34));
+        assert_eq!(None, find_by_value(199));
+        assert_eq!(None, find_by_value(201));
+        assert_eq!(None, find_by_mnemonic("move_w"));
+    }
+}
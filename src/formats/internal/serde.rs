@@ -0,0 +1,287 @@
+//! [Serde](https://serde.rs) support, enabled by the `serde` feature.
+//!
+//! A [`Program`] is a sparse sequence of its non-empty instructions, every one is a map of the
+//! position (`page`, `row`, `column`), the `instruction` mnemonic (`MOVE_W`, `GOTO`, ...) and
+//! the data (`label`; `variable` and `value`; or `string`). Literals are strings, variable values
+//! are integers. Positions and literals are validated on deserialization.
+
+use std::fmt;
+
+use serde::de::{self, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::literals::{
+    LabelIdentifierLiteral, StringLiteral, VariableIdentifierLiteral, VariableValueLiteral,
+};
+use super::{
+    Instruction, InstructionData, InstructionId, InstructionKind, InstructionPosition, Program,
+};
+
+// region: literals
+
+/// Implements serialization of a literal as a string.
+macro_rules! string_literal {
+    ($literal:ty, $what:literal) => {
+        impl Serialize for $literal {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $literal {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let s = String::deserialize(deserializer)?;
+                s.parse()
+                    .map_err(|_| de::Error::custom(format!("illegal {} `{}`", $what, s)))
+            }
+        }
+    };
+}
+
+string_literal!(LabelIdentifierLiteral, "label");
+string_literal!(StringLiteral, "string");
+string_literal!(VariableIdentifierLiteral, "variable");
+
+impl Serialize for VariableValueLiteral {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i32(self.data())
+    }
+}
+
+impl<'de> Deserialize<'de> for VariableValueLiteral {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = i32::deserialize(deserializer)?;
+        Self::new_from_value(value)
+            .map_err(|_| de::Error::custom(format!("illegal value `{}`", value)))
+    }
+}
+
+// endregion: literals
+
+// region: instruction_position
+
+#[derive(Serialize, Deserialize)]
+struct PositionRepr {
+    page: u8,
+    row: u8,
+    column: u8,
+}
+
+impl Serialize for InstructionPosition {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PositionRepr {
+            page: self.page,
+            row: self.row,
+            column: self.column,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for InstructionPosition {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let PositionRepr { page, row, column } = PositionRepr::deserialize(deserializer)?;
+        Self::new(page, row, column)
+            .map_err(|_| de::Error::custom(format!("illegal position {}:{}:{}", page, row, column)))
+    }
+}
+
+// endregion: instruction_position
+
+// region: instruction
+
+impl Serialize for InstructionId {
+    /// Serializes the id as its mnemonic, or as its numeric value if it's
+    /// [unknown](InstructionId::Unknown).
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.client_identifier() {
+            Some(identifier) => serializer.serialize_str(identifier),
+            None => serializer.serialize_u8(self.value()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for InstructionId {
+    /// Deserializes the id from its mnemonic or from its numeric value.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(InstructionIdVisitor)
+    }
+}
+
+struct InstructionIdVisitor;
+
+impl<'de> Visitor<'de> for InstructionIdVisitor {
+    type Value = InstructionId;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "an instruction mnemonic or id value")
+    }
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<Self::Value, E> {
+        InstructionId::from_client_identifier(s)
+            .ok_or_else(|| E::custom(format!("unknown instruction `{}`", s)))
+    }
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+        u8::try_from(value)
+            .map(InstructionId::from_value)
+            .map_err(|_| E::custom(format!("illegal instruction id {}", value)))
+    }
+    /// Some formats (e.g. TOML) have only signed integers.
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+        u8::try_from(value)
+            .map(InstructionId::from_value)
+            .map_err(|_| E::custom(format!("illegal instruction id {}", value)))
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DataRepr {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<LabelIdentifierLiteral>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    variable: Option<VariableIdentifierLiteral>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    value: Option<VariableValueLiteral>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    string: Option<StringLiteral>,
+}
+
+impl Serialize for InstructionData {
+    /// Serializes the data as a map of its fields: none, `label`, `variable` and `value`, or
+    /// `string`.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut repr = DataRepr::default();
+        match *self {
+            Self::Simple => {}
+            Self::Label(label) => repr.label = Some(label),
+            Self::VarCmp((variable, value)) => {
+                repr.variable = Some(variable);
+                repr.value = Some(value);
+            }
+            Self::String(string) => repr.string = Some(string),
+        }
+        repr.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for InstructionData {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = DataRepr::deserialize(deserializer)?;
+        match (repr.label, repr.variable, repr.value, repr.string) {
+            (None, None, None, None) => Ok(Self::Simple),
+            (Some(label), None, None, None) => Ok(Self::Label(label)),
+            (None, Some(variable), Some(value), None) => Ok(Self::VarCmp((variable, value))),
+            (None, None, None, Some(string)) => Ok(Self::String(string)),
+            _ => Err(de::Error::custom(
+                "expected no data, `label`, `variable` and `value`, or `string`",
+            )),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct InstructionRepr {
+    instruction: InstructionId,
+    #[serde(flatten)]
+    data: InstructionData,
+}
+
+impl Serialize for Instruction {
+    /// Serializes the instruction as a map of the `instruction` mnemonic and the
+    /// [data](InstructionData) fields.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        InstructionRepr {
+            instruction: self.id,
+            data: self.data,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Instruction {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let InstructionRepr { instruction, data } = InstructionRepr::deserialize(deserializer)?;
+        let result = match data {
+            InstructionData::Simple => Self::new_simple(instruction),
+            InstructionData::Label(label) => Self::new_label(instruction, label),
+            InstructionData::VarCmp((variable, value)) => {
+                Self::new_var_cmp(instruction, variable, value)
+            }
+            InstructionData::String(string) => Self::new_string(instruction, string),
+        };
+        result.map_err(|_| {
+            let expected = match instruction.kind() {
+                InstructionKind::Simple => "no data",
+                InstructionKind::Label => "`label`",
+                InstructionKind::VarCmp => "`variable` and `value`",
+                InstructionKind::String => "`string`",
+            };
+            de::Error::custom(format!("`{}` takes {}", instruction.mnemonic(), expected))
+        })
+    }
+}
+
+// endregion: instruction
+
+// region: program
+
+#[derive(Serialize, Deserialize)]
+struct EntryRepr {
+    #[serde(flatten)]
+    position: InstructionPosition,
+    #[serde(flatten)]
+    instruction: Instruction,
+}
+
+impl Serialize for Program {
+    /// Serializes the program as a sequence of its non-empty instructions with their positions.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(
+            self.instruction_positions()
+                .filter(|(_, instruction)| instruction.id() != InstructionId::Empty)
+                .map(|(position, instruction)| EntryRepr {
+                    position,
+                    instruction,
+                }),
+        )
+    }
+}
+
+impl<'de> Deserialize<'de> for Program {
+    /// Deserializes the program from a sequence of instructions with their positions. Cells
+    /// missing in the sequence are empty, a position can't repeat.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(ProgramVisitor)
+    }
+}
+
+struct ProgramVisitor;
+
+impl<'de> Visitor<'de> for ProgramVisitor {
+    type Value = Program;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a sequence of instructions with their positions")
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut program = Program::default();
+        let mut set = vec![false; Program::INSTRUCTIONS_PER_PROGRAM];
+        while let Some(EntryRepr {
+            position,
+            instruction,
+        }) = seq.next_element()?
+        {
+            if std::mem::replace(&mut set[position.index()], true) {
+                return Err(de::Error::custom(format!(
+                    "position {} is set twice",
+                    position.custom_format()
+                )));
+            }
+            program[position] = instruction;
+        }
+        Ok(program)
+    }
+}
+
+// endregion: program
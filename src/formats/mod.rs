@@ -3,6 +3,8 @@
 //! Available representations:
 //! * [internal] - an internal raw program representation. Contains no additional info
 //! * [native] - native formats
+//! * [custom] - custom formats
 
+pub mod custom;
 pub mod internal;
 pub mod native;
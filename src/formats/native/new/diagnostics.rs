@@ -1,5 +1,12 @@
 //! Module for New Text format's diagnostics.
+//!
+//! Besides the diagnostics themselves, provides a [`Renderer`] to print them rustc-like, with the
+//! offending source line and markers under the offending chars.
 
+use crate::formats::internal::literals::{
+    Literal, VariableIdentifierLiteral, VariableValueLiteral,
+};
+use crate::formats::internal::Program;
 use crate::utils::CharPosition;
 
 // region: general
@@ -14,12 +21,90 @@ pub trait Diagnostic {
         let id: u8 = self.id().into();
         format_args!("N{:0>2}", id).to_string()
     }
+    /// Returns this diagnostic's message without position and id.
+    fn message(&self) -> String;
     /// Returns this diagnostic's message.
-    fn what(&self) -> String;
+    fn what(&self) -> String {
+        let (start, end) = self.span();
+        format_args!(
+            "{}: [{}] {}\n",
+            format_span(start, end),
+            self.prefixed_id(),
+            self.message()
+        )
+        .to_string()
+    }
     /// Returns this diagnostic's position in source code.
     ///
-    /// Returns the start position for [`UnknownToken`].
+    /// Returns the start position for diagnostics with a span.
     fn position(&self) -> CharPosition;
+    /// Returns positions of the first and the last chars this diagnostic points to.
+    fn span(&self) -> (CharPosition, CharPosition) {
+        (self.position(), self.position())
+    }
+    /// Returns this diagnostic's [severity](Severity).
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+    /// Returns a note on how to fix the problem (if any).
+    fn help(&self) -> Option<String> {
+        None
+    }
+    /// Returns a fix-it which can be applied to the source automatically (if any).
+    fn suggestion(&self) -> Option<Suggestion> {
+        None
+    }
+}
+
+/// How serious a [Diagnostic] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The source is deserialized, but probably not the way it's meant to (e.g. a literal is
+    /// truncated).
+    Warning,
+    /// A part of the source is ignored.
+    Error,
+}
+
+impl Severity {
+    /// Returns the lowercase name of the severity (e.g. `"error"`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// A fix-it: replace chars from [`start`](Self::start) to [`end`](Self::end) (both inclusive)
+/// with the [`replacement`](Self::replacement).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Suggestion {
+    start: CharPosition,
+    end: CharPosition,
+    replacement: &'static str,
+}
+
+impl Suggestion {
+    pub fn new(start: CharPosition, end: CharPosition, replacement: &'static str) -> Self {
+        Self {
+            start,
+            end,
+            replacement,
+        }
+    }
+    /// Returns position of the first char to replace.
+    pub fn start(&self) -> CharPosition {
+        self.start
+    }
+    /// Returns position of the last char to replace.
+    pub fn end(&self) -> CharPosition {
+        self.end
+    }
+    /// Returns the text to replace with.
+    pub fn replacement(&self) -> &'static str {
+        self.replacement
+    }
 }
 
 /// Represents all id of [Diagnostic].
@@ -28,6 +113,12 @@ pub trait Diagnostic {
 pub enum DiagnosticId {
     NoMagicFound = 1,
     UnknownToken = 2,
+    ProgramOverflow = 3,
+    RowOverflow = 4,
+    LiteralTooLong = 5,
+    VariableValueOutOfRange = 6,
+    UnterminatedLiteral = 7,
+    MalformedRowSkip = 8,
 }
 
 impl From<DiagnosticId> for u8 {
@@ -41,14 +132,26 @@ impl From<DiagnosticId> for u8 {
 pub enum Diagnostics {
     NoMagicFound(NoMagicFound),
     UnknownToken(UnknownToken),
+    ProgramOverflow(ProgramOverflow),
+    RowOverflow(RowOverflow),
+    LiteralTooLong(LiteralTooLong),
+    VariableValueOutOfRange(VariableValueOutOfRange),
+    UnterminatedLiteral(UnterminatedLiteral),
+    MalformedRowSkip(MalformedRowSkip),
 }
 
 macro_rules! impl_trait_for_diagnostics {
-    ($method:ident, $rtype:ident) => {
+    ($method:ident, $rtype:ty) => {
         fn $method(&self) -> $rtype {
             match self {
                 Self::NoMagicFound(x) => x.$method(),
                 Self::UnknownToken(x) => x.$method(),
+                Self::ProgramOverflow(x) => x.$method(),
+                Self::RowOverflow(x) => x.$method(),
+                Self::LiteralTooLong(x) => x.$method(),
+                Self::VariableValueOutOfRange(x) => x.$method(),
+                Self::UnterminatedLiteral(x) => x.$method(),
+                Self::MalformedRowSkip(x) => x.$method(),
             }
         }
     };
@@ -57,12 +160,288 @@ macro_rules! impl_trait_for_diagnostics {
 impl Diagnostic for Diagnostics {
     impl_trait_for_diagnostics!(id, DiagnosticId);
     impl_trait_for_diagnostics!(prefixed_id, String);
+    impl_trait_for_diagnostics!(message, String);
     impl_trait_for_diagnostics!(what, String);
     impl_trait_for_diagnostics!(position, CharPosition);
+    impl_trait_for_diagnostics!(span, (CharPosition, CharPosition));
+    impl_trait_for_diagnostics!(severity, Severity);
+    impl_trait_for_diagnostics!(help, Option<String>);
+    impl_trait_for_diagnostics!(suggestion, Option<Suggestion>);
+}
+
+/// Formats the given span as `line:col` or `line:col-line:col`.
+fn format_span(start: CharPosition, end: CharPosition) -> String {
+    if start == end {
+        start.custom_format()
+    } else {
+        format_args!("{}-{}", start.custom_format(), end.custom_format()).to_string()
+    }
 }
 
 // endregion: general
 
+// region: rendering
+
+/// Renders [Diagnostics] rustc-like.
+///
+/// Every diagnostic is rendered as a header with the severity, id and message, followed by the
+/// offending source line with markers under the offending chars and an optional help note:
+///
+/// ```text
+/// error[N02]: unknown token found
+///  --> 0:3-0:6
+///   |
+/// 0 | $^W]]]]^S
+///   |    ^^^^
+/// ```
+///
+/// Lines and columns are zero-based, the same as in [`Diagnostic::what`]. A span crossing the end
+/// of the line is marked up to the end of its first line.
+///
+/// # Examples
+///
+/// ```
+/// use m3c::formats::internal::Program;
+/// use m3c::formats::native::new::diagnostics::Renderer;
+/// use m3c::serialization::native::new::TextFormatDeserializerV2;
+///
+/// let source = "$^W]]]]^S";
+/// let mut program = Program::default();
+/// let diagnostics = TextFormatDeserializerV2::new(source).deserialize(&mut program);
+///
+/// let rendered = Renderer::new(source).render(&diagnostics);
+/// assert!(rendered.contains("0 | $^W]]]]^S\n  |    ^^^^\n"));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Renderer<'s> {
+    source: &'s str,
+    color: bool,
+}
+
+impl<'s> Renderer<'s> {
+    const RESET: &'static str = "\x1b[0m";
+    const BOLD: &'static str = "\x1b[1m";
+    const RED: &'static str = "\x1b[1;31m";
+    const YELLOW: &'static str = "\x1b[1;33m";
+    const BLUE: &'static str = "\x1b[1;34m";
+
+    /// Creates a new renderer for diagnostics of the given `source`. Color is off.
+    pub fn new(source: &'s str) -> Self {
+        Self {
+            source,
+            color: false,
+        }
+    }
+    /// Turns ANSI color codes on or off.
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+    /// Renders all the `diagnostics` separated by an empty line.
+    pub fn render(&self, diagnostics: &[Diagnostics]) -> String {
+        diagnostics
+            .iter()
+            .map(|diagnostic| self.render_one(diagnostic))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+    /// Renders one diagnostic.
+    pub fn render_one(&self, diagnostic: &Diagnostics) -> String {
+        let (start, end) = diagnostic.span();
+        let line = self
+            .source
+            .split('\n')
+            .nth(start.line)
+            .unwrap_or_default()
+            .trim_end_matches('\r');
+        let line_number = start.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+
+        // markers are aligned with the source line char by char, so keep tabs as they are
+        let mut markers: String = line
+            .chars()
+            .take(start.column)
+            .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+            .collect();
+        let len = if end.line == start.line {
+            end.column.saturating_sub(start.column) + 1
+        } else {
+            line.chars().count().saturating_sub(start.column).max(1)
+        };
+        markers.push_str(&"^".repeat(len));
+
+        let severity = diagnostic.severity();
+        let style = match severity {
+            Severity::Warning => Self::YELLOW,
+            Severity::Error => Self::RED,
+        };
+
+        let mut out = format_args!(
+            "{}: {}\n",
+            self.paint(
+                style,
+                &format_args!("{}[{}]", severity.as_str(), diagnostic.prefixed_id()).to_string()
+            ),
+            self.paint(Self::BOLD, &diagnostic.message()),
+        )
+        .to_string();
+        out += &format_args!(
+            "{}{} {}\n",
+            gutter,
+            self.paint(Self::BLUE, "-->"),
+            format_span(start, end)
+        )
+        .to_string();
+        out += &format_args!("{} {}\n", gutter, self.paint(Self::BLUE, "|")).to_string();
+        out += &format_args!(
+            "{} {}\n",
+            self.paint(Self::BLUE, &format_args!("{} |", line_number).to_string()),
+            line
+        )
+        .to_string();
+        out += &format_args!(
+            "{} {} {}\n",
+            gutter,
+            self.paint(Self::BLUE, "|"),
+            self.paint(style, &markers)
+        )
+        .to_string();
+        if let Some(help) = diagnostic.help() {
+            out += &format_args!(
+                "{} {} {}\n",
+                gutter,
+                self.paint(Self::BLUE, "="),
+                self.paint(Self::BOLD, &format_args!("help: {}", help).to_string())
+            )
+            .to_string();
+        }
+        out
+    }
+    /// Wraps `text` into the given ANSI `style` if color is on.
+    fn paint(&self, style: &str, text: &str) -> String {
+        if self.color && !text.is_empty() {
+            format_args!("{}{}{}", style, text, Self::RESET).to_string()
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+// endregion: rendering
+
+// region: json
+
+/// Serializes `diagnostics` of the given `source` to a JSON array.
+///
+/// Every diagnostic is an object:
+///
+/// ```text
+/// {
+///   "id": "N02",
+///   "severity": "error",
+///   "message": "unknown token found",
+///   "help": "did you mean `^W`?",
+///   "start": {"byte": 1, "char": 1, "line": 0, "column": 1},
+///   "end": {"byte": 3, "char": 3, "line": 0, "column": 3},
+///   "suggestion": {"start": {...}, "end": {...}, "replacement": "^W"}
+/// }
+/// ```
+///
+/// Unlike the rest of this module, `end` positions are exclusive (point just past the last char),
+/// so a `[start, end)` range can be handed to an editor as is. Lines and columns are zero-based
+/// and counted in chars. `help` and `suggestion` are `null` if there are none.
+///
+/// # Examples
+///
+/// ```
+/// use m3c::formats::native::new::diagnostics::{to_json, UnknownToken};
+/// use m3c::utils::CharPosition;
+///
+/// let pos = CharPosition { index: 1, line: 0, column: 1 };
+/// let json = to_json("$]^W", &[UnknownToken::new(pos, pos).into()]);
+/// assert!(json.contains(r#""start":{"byte":1,"char":1,"line":0,"column":1}"#));
+/// assert!(json.contains(r#""end":{"byte":2,"char":2,"line":0,"column":2}"#));
+/// ```
+pub fn to_json(source: &str, diagnostics: &[Diagnostics]) -> String {
+    // byte offset of every char and of the end of the source
+    let offsets: Vec<usize> = source
+        .char_indices()
+        .map(|(byte, _)| byte)
+        .chain(std::iter::once(source.len()))
+        .collect();
+    let position = |pos: CharPosition, exclusive: bool| {
+        let (index, column) = if exclusive {
+            (pos.index + 1, pos.column + 1)
+        } else {
+            (pos.index, pos.column)
+        };
+        format_args!(
+            r#"{{"byte":{},"char":{},"line":{},"column":{}}}"#,
+            offsets.get(index).copied().unwrap_or(source.len()),
+            index,
+            pos.line,
+            column
+        )
+        .to_string()
+    };
+
+    let objects: Vec<String> = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let (start, end) = diagnostic.span();
+            let help = diagnostic
+                .help()
+                .map_or("null".to_string(), |help| json_string(&help));
+            let suggestion = diagnostic
+                .suggestion()
+                .map_or("null".to_string(), |suggestion| {
+                    format_args!(
+                        r#"{{"start":{},"end":{},"replacement":{}}}"#,
+                        position(suggestion.start(), false),
+                        position(suggestion.end(), true),
+                        json_string(suggestion.replacement())
+                    )
+                    .to_string()
+                });
+            format_args!(
+                r#"{{"id":"{}","severity":"{}","message":{},"help":{},"start":{},"end":{},"suggestion":{}}}"#,
+                diagnostic.prefixed_id(),
+                diagnostic.severity().as_str(),
+                json_string(&diagnostic.message()),
+                help,
+                position(start, false),
+                position(end, true),
+                suggestion
+            )
+            .to_string()
+        })
+        .collect();
+    format_args!("[{}]", objects.join(",")).to_string()
+}
+
+/// Returns `s` as a quoted and escaped JSON string.
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            ch if (ch as u32) < 0x20 => {
+                out.push_str(&format_args!("\\u{:04x}", ch as u32).to_string())
+            }
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// endregion: json
+
 /// No magic (`$`) found at the start of the line.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct NoMagicFound;
@@ -74,22 +453,29 @@ impl NoMagicFound {
     }
 }
 
+impl Default for NoMagicFound {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Diagnostic for NoMagicFound {
     fn id(&self) -> DiagnosticId {
         Self::ID
     }
-    fn what(&self) -> String {
-        format_args!(
-            "{}: [{}] no magic ('$') found at the start of the line\n",
-            self.position().custom_format(),
-            self.prefixed_id()
-        )
-        .to_string()
+    fn message(&self) -> String {
+        "no magic ('$') found at the start of the line".to_string()
+    }
+    fn severity(&self) -> Severity {
+        Severity::Warning
     }
     fn position(&self) -> CharPosition {
         // NOTE: this diagnostic is always at the beginning of string
         CharPosition::default()
     }
+    fn help(&self) -> Option<String> {
+        Some("add '$' at the start of the line".to_string())
+    }
 }
 
 impl From<NoMagicFound> for Diagnostics {
@@ -99,22 +485,44 @@ impl From<NoMagicFound> for Diagnostics {
 }
 
 /// Illegal char(s) at the beginning of the token.
+///
+/// If the chars together with the next one look like a known token with a typo in letter case
+/// (e.g. `^w` instead of `^W`), the known one is suggested.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct UnknownToken {
     start: CharPosition,
     end: CharPosition,
+    suggestion: Option<Suggestion>,
 }
 
 impl UnknownToken {
     pub const ID: DiagnosticId = DiagnosticId::UnknownToken;
     pub fn new(start: CharPosition, end: CharPosition) -> Self {
-        Self { start, end }
+        Self {
+            start,
+            end,
+            suggestion: None,
+        }
+    }
+    /// Creates a new [`UnknownToken`] with a fix-it.
+    ///
+    /// Note that the `suggestion` may cover more chars than the illegal ones.
+    pub fn new_with_suggestion(
+        start: CharPosition,
+        end: CharPosition,
+        suggestion: Suggestion,
+    ) -> Self {
+        Self {
+            start,
+            end,
+            suggestion: Some(suggestion),
+        }
     }
-    /// Returns position of the first illegal char.
+    /// Returns position of the first char of the illegal char sequence.
     pub fn start(&self) -> CharPosition {
         self.start
     }
-    /// Returns position of the last illegal char.
+    /// Returns position of the last char of the illegal char sequence.
     pub fn end(&self) -> CharPosition {
         self.end
     }
@@ -124,28 +532,24 @@ impl Diagnostic for UnknownToken {
     fn id(&self) -> DiagnosticId {
         Self::ID
     }
-    fn what(&self) -> String {
-        let pos_str = if self.start == self.end {
-            self.position().custom_format()
-        } else {
-            format_args!(
-                "{}-{}",
-                self.start.custom_format(),
-                self.end.custom_format()
-            )
-            .to_string()
-        };
-        format_args!(
-            "{}: [{}] unknown token found\n",
-            pos_str,
-            self.prefixed_id()
-        )
-        .to_string()
+    fn message(&self) -> String {
+        "unknown token found".to_string()
     }
-    /// Returns position of the first illegal char.
+    /// Returns position of the first char of the illegal char sequence.
     fn position(&self) -> CharPosition {
         self.start()
     }
+    fn span(&self) -> (CharPosition, CharPosition) {
+        (self.start, self.end)
+    }
+    fn help(&self) -> Option<String> {
+        self.suggestion.map(|suggestion| {
+            format_args!("did you mean `{}`?", suggestion.replacement()).to_string()
+        })
+    }
+    fn suggestion(&self) -> Option<Suggestion> {
+        self.suggestion
+    }
 }
 
 impl From<UnknownToken> for Diagnostics {
@@ -153,3 +557,420 @@ impl From<UnknownToken> for Diagnostics {
         Diagnostics::UnknownToken(x)
     }
 }
+
+/// There are more instructions than the program can contain.
+///
+/// Reported once, for the first instruction that doesn't fit into the program.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProgramOverflow {
+    start: CharPosition,
+    end: CharPosition,
+}
+
+impl ProgramOverflow {
+    pub const ID: DiagnosticId = DiagnosticId::ProgramOverflow;
+    pub fn new(start: CharPosition, end: CharPosition) -> Self {
+        Self { start, end }
+    }
+    /// Returns position of the first char of the instruction.
+    pub fn start(&self) -> CharPosition {
+        self.start
+    }
+    /// Returns position of the last char of the instruction.
+    pub fn end(&self) -> CharPosition {
+        self.end
+    }
+}
+
+impl Diagnostic for ProgramOverflow {
+    fn id(&self) -> DiagnosticId {
+        Self::ID
+    }
+    fn message(&self) -> String {
+        "the program is full, the rest of instructions is ignored".to_string()
+    }
+    /// Returns position of the first char of the instruction.
+    fn position(&self) -> CharPosition {
+        self.start()
+    }
+    fn span(&self) -> (CharPosition, CharPosition) {
+        (self.start, self.end)
+    }
+    fn help(&self) -> Option<String> {
+        Some(
+            format_args!(
+                "a program can contain at most {} instructions",
+                Program::INSTRUCTIONS_PER_PROGRAM
+            )
+            .to_string(),
+        )
+    }
+}
+
+impl From<ProgramOverflow> for Diagnostics {
+    fn from(x: ProgramOverflow) -> Self {
+        Diagnostics::ProgramOverflow(x)
+    }
+}
+
+/// Command `_` (three steps forward) crosses the end of the row.
+///
+/// The cursor moves to the start of the next row instead.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RowOverflow {
+    start: CharPosition,
+    end: CharPosition,
+}
+
+impl RowOverflow {
+    pub const ID: DiagnosticId = DiagnosticId::RowOverflow;
+    pub fn new(start: CharPosition, end: CharPosition) -> Self {
+        Self { start, end }
+    }
+    /// Returns position of the first char of the command.
+    pub fn start(&self) -> CharPosition {
+        self.start
+    }
+    /// Returns position of the last char of the command.
+    pub fn end(&self) -> CharPosition {
+        self.end
+    }
+}
+
+impl Diagnostic for RowOverflow {
+    fn id(&self) -> DiagnosticId {
+        Self::ID
+    }
+    fn message(&self) -> String {
+        "three steps forward cross the end of the row".to_string()
+    }
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+    /// Returns position of the first char of the command.
+    fn position(&self) -> CharPosition {
+        self.start()
+    }
+    fn span(&self) -> (CharPosition, CharPosition) {
+        (self.start, self.end)
+    }
+    fn help(&self) -> Option<String> {
+        Some("use '\\n' to move to the next row".to_string())
+    }
+}
+
+impl From<RowOverflow> for Diagnostics {
+    fn from(x: RowOverflow) -> Self {
+        Diagnostics::RowOverflow(x)
+    }
+}
+
+/// A literal is longer than its type allows.
+///
+/// Only the leading chars are kept, the rest are ignored.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LiteralTooLong {
+    start: CharPosition,
+    end: CharPosition,
+}
+
+impl LiteralTooLong {
+    pub const ID: DiagnosticId = DiagnosticId::LiteralTooLong;
+    pub fn new(start: CharPosition, end: CharPosition) -> Self {
+        Self { start, end }
+    }
+    /// Returns position of the first char of the literal.
+    pub fn start(&self) -> CharPosition {
+        self.start
+    }
+    /// Returns position of the last char of the literal.
+    pub fn end(&self) -> CharPosition {
+        self.end
+    }
+}
+
+impl Diagnostic for LiteralTooLong {
+    fn id(&self) -> DiagnosticId {
+        Self::ID
+    }
+    fn message(&self) -> String {
+        "literal is too long, it's truncated".to_string()
+    }
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+    /// Returns position of the first char of the literal.
+    fn position(&self) -> CharPosition {
+        self.start()
+    }
+    fn span(&self) -> (CharPosition, CharPosition) {
+        (self.start, self.end)
+    }
+    fn help(&self) -> Option<String> {
+        Some(
+            format_args!(
+                "names can contain at most {} chars, variable values at most {} chars",
+                VariableIdentifierLiteral::MAX_CHAR_LEN,
+                VariableValueLiteral::MAX_CHAR_LEN
+            )
+            .to_string(),
+        )
+    }
+}
+
+impl From<LiteralTooLong> for Diagnostics {
+    fn from(x: LiteralTooLong) -> Self {
+        Diagnostics::LiteralTooLong(x)
+    }
+}
+
+/// A variable value literal is out of the allowed range.
+///
+/// Only the leading chars are kept, the rest are ignored.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VariableValueOutOfRange {
+    start: CharPosition,
+    end: CharPosition,
+}
+
+impl VariableValueOutOfRange {
+    pub const ID: DiagnosticId = DiagnosticId::VariableValueOutOfRange;
+    pub fn new(start: CharPosition, end: CharPosition) -> Self {
+        Self { start, end }
+    }
+    /// Returns position of the first char of the literal.
+    pub fn start(&self) -> CharPosition {
+        self.start
+    }
+    /// Returns position of the last char of the literal.
+    pub fn end(&self) -> CharPosition {
+        self.end
+    }
+}
+
+impl Diagnostic for VariableValueOutOfRange {
+    fn id(&self) -> DiagnosticId {
+        Self::ID
+    }
+    fn message(&self) -> String {
+        "variable value is out of range, it's truncated".to_string()
+    }
+    /// Returns position of the first char of the literal.
+    fn position(&self) -> CharPosition {
+        self.start()
+    }
+    fn span(&self) -> (CharPosition, CharPosition) {
+        (self.start, self.end)
+    }
+    fn help(&self) -> Option<String> {
+        Some("variable values must be in range [-9999, 99999]".to_string())
+    }
+}
+
+impl From<VariableValueOutOfRange> for Diagnostics {
+    fn from(x: VariableValueOutOfRange) -> Self {
+        Diagnostics::VariableValueOutOfRange(x)
+    }
+}
+
+/// A token with literal(s) isn't finished (e.g. the closing `)` is missing).
+///
+/// The whole token is ignored.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnterminatedLiteral {
+    start: CharPosition,
+    end: CharPosition,
+}
+
+impl UnterminatedLiteral {
+    pub const ID: DiagnosticId = DiagnosticId::UnterminatedLiteral;
+    pub fn new(start: CharPosition, end: CharPosition) -> Self {
+        Self { start, end }
+    }
+    /// Returns position of the first char of the token.
+    pub fn start(&self) -> CharPosition {
+        self.start
+    }
+    /// Returns position of the last char of the token.
+    pub fn end(&self) -> CharPosition {
+        self.end
+    }
+}
+
+impl Diagnostic for UnterminatedLiteral {
+    fn id(&self) -> DiagnosticId {
+        Self::ID
+    }
+    fn message(&self) -> String {
+        "unterminated literal found".to_string()
+    }
+    /// Returns position of the first char of the token.
+    fn position(&self) -> CharPosition {
+        self.start()
+    }
+    fn span(&self) -> (CharPosition, CharPosition) {
+        (self.start, self.end)
+    }
+}
+
+impl From<UnterminatedLiteral> for Diagnostics {
+    fn from(x: UnterminatedLiteral) -> Self {
+        Diagnostics::UnterminatedLiteral(x)
+    }
+}
+
+/// A row skip with a count (`.N.`) isn't closed with `.` (e.g. `.12.` or `.5^W`).
+///
+/// The whole row skip is ignored.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MalformedRowSkip {
+    start: CharPosition,
+    end: CharPosition,
+}
+
+impl MalformedRowSkip {
+    pub const ID: DiagnosticId = DiagnosticId::MalformedRowSkip;
+    pub fn new(start: CharPosition, end: CharPosition) -> Self {
+        Self { start, end }
+    }
+    /// Returns position of the opening `.`.
+    pub fn start(&self) -> CharPosition {
+        self.start
+    }
+    /// Returns position of the count digit.
+    pub fn end(&self) -> CharPosition {
+        self.end
+    }
+}
+
+impl Diagnostic for MalformedRowSkip {
+    fn id(&self) -> DiagnosticId {
+        Self::ID
+    }
+    fn message(&self) -> String {
+        "malformed row skip, expected `.` after the count".to_string()
+    }
+    /// Returns position of the opening `.`.
+    fn position(&self) -> CharPosition {
+        self.start()
+    }
+    fn span(&self) -> (CharPosition, CharPosition) {
+        (self.start, self.end)
+    }
+    fn help(&self) -> Option<String> {
+        Some("a row skip is either `.` or `.N.` with a single digit `N`".to_string())
+    }
+}
+
+impl From<MalformedRowSkip> for Diagnostics {
+    fn from(x: MalformedRowSkip) -> Self {
+        Diagnostics::MalformedRowSkip(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(line: usize, column: usize, index: usize) -> CharPosition {
+        CharPosition {
+            index,
+            line,
+            column,
+        }
+    }
+
+    #[test]
+    fn render_unknown_token() {
+        let source = "$^W]]]]^S";
+        let diagnostics: Vec<Diagnostics> =
+            vec![UnknownToken::new(at(0, 3, 3), at(0, 6, 6)).into()];
+
+        let expected = concat!(
+            "error[N02]: unknown token found\n",
+            " --> 0:3-0:6\n",
+            "  |\n",
+            "0 | $^W]]]]^S\n",
+            "  |    ^^^^\n",
+        );
+        assert_eq!(expected, Renderer::new(source).render(&diagnostics));
+    }
+
+    #[test]
+    fn render_with_help_on_the_second_line() {
+        let source = "$^W\r\n\t^A^W^W^W^W^W^W^W^W^W^W^W^W^W_";
+        let diagnostics: Vec<Diagnostics> = vec![
+            NoMagicFound::new().into(),
+            RowOverflow::new(at(1, 29, 34), at(1, 29, 34)).into(),
+        ];
+
+        let expected = concat!(
+            "warning[N01]: no magic ('$') found at the start of the line\n",
+            " --> 0:0\n",
+            "  |\n",
+            "0 | $^W\n",
+            "  | ^\n",
+            "  = help: add '$' at the start of the line\n",
+            "\n",
+            "warning[N04]: three steps forward cross the end of the row\n",
+            " --> 1:29\n",
+            "  |\n",
+            "1 | \t^A^W^W^W^W^W^W^W^W^W^W^W^W^W_\n",
+            "  | \t                            ^\n",
+            "  = help: use '\\n' to move to the next row\n",
+        );
+        assert_eq!(expected, Renderer::new(source).render(&diagnostics));
+    }
+
+    #[test]
+    fn render_with_color() {
+        let source = "$>ab";
+        let diagnostics: Vec<Diagnostics> =
+            vec![UnterminatedLiteral::new(at(0, 1, 1), at(0, 3, 3)).into()];
+
+        let expected = concat!(
+            "\x1b[1;31merror[N07]\x1b[0m: \x1b[1munterminated literal found\x1b[0m\n",
+            " \x1b[1;34m-->\x1b[0m 0:1-0:3\n",
+            "  \x1b[1;34m|\x1b[0m\n",
+            "\x1b[1;34m0 |\x1b[0m $>ab\n",
+            "  \x1b[1;34m|\x1b[0m \x1b[1;31m ^^^\x1b[0m\n",
+        );
+        assert_eq!(
+            expected,
+            Renderer::new(source).with_color(true).render(&diagnostics)
+        );
+    }
+
+    #[test]
+    fn json() {
+        let source = "$ї^w\n(a=\"";
+        let diagnostics: Vec<Diagnostics> = vec![
+            UnknownToken::new_with_suggestion(
+                at(0, 2, 2),
+                at(0, 2, 2),
+                Suggestion::new(at(0, 2, 2), at(0, 3, 3), "^W"),
+            )
+            .into(),
+            UnterminatedLiteral::new(at(1, 0, 5), at(1, 2, 7)).into(),
+        ];
+
+        let expected = concat!(
+            r#"[{"id":"N02","severity":"error","message":"unknown token found","#,
+            r#""help":"did you mean `^W`?","#,
+            r#""start":{"byte":3,"char":2,"line":0,"column":2},"#,
+            r#""end":{"byte":4,"char":3,"line":0,"column":3},"#,
+            r#""suggestion":{"start":{"byte":3,"char":2,"line":0,"column":2},"#,
+            r#""end":{"byte":5,"char":4,"line":0,"column":4},"replacement":"^W"}},"#,
+            r#"{"id":"N07","severity":"error","message":"unterminated literal found","#,
+            r#""help":null,"#,
+            r#""start":{"byte":6,"char":5,"line":1,"column":0},"#,
+            r#""end":{"byte":9,"char":8,"line":1,"column":3},"suggestion":null}]"#,
+        );
+        assert_eq!(expected, to_json(source, &diagnostics));
+    }
+
+    #[test]
+    fn json_string_escapes() {
+        assert_eq!(r#""a\"b\\c\n\u0001""#, json_string("a\"b\\c\n\u{1}"));
+    }
+}
@@ -0,0 +1,396 @@
+//! A control-flow interpreter of [internal](crate::formats::internal) programs.
+//!
+//! The [`Interpreter`] walks the program and executes control instructions itself: jumps,
+//! subroutines, conditions and responses. Checks and actions go through an [`Environment`]
+//! implemented by the user, e.g. the [simulator's sandbox](crate::simulation::sandbox) or a
+//! scripted mock.
+
+use std::collections::HashMap;
+
+use crate::analysis::labels::LabelAnalysis;
+use crate::formats::internal::literals::{StringLiteral, VariableIdentifierLiteral};
+use crate::formats::internal::registry::{Category, InstructionInfo};
+use crate::formats::internal::{InstructionData, InstructionId, InstructionPosition, Program};
+
+/// The world a program is executed in.
+pub trait Environment {
+    /// Performs the action `id` (a move, a look, a dig, a mode switch, ...).
+    ///
+    /// Returns whether the action takes time. The interpreter [ticks](Interpreter::tick) up to
+    /// the first such action.
+    fn act(&mut self, id: InstructionId) -> bool;
+    /// Returns the result of the check `id` (one of `Cc*`, `Ccc*` and `Cb*` instructions).
+    ///
+    /// `selector` is the last executed `Cell*` instruction ([`CellF`](InstructionId::CellF) if
+    /// none), it tells which cell relative to the robot to check.
+    fn check(&mut self, id: InstructionId, selector: InstructionId) -> bool;
+    /// Whether the [`OnResp`](InstructionId::OnResp) handler has to respond to something.
+    ///
+    /// Called before every instruction. Never responds by default.
+    fn poll_response(&mut self) -> bool {
+        false
+    }
+}
+
+/// How results of checks are combined.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum BoolMode {
+    #[default]
+    Or,
+    And,
+}
+
+/// The result of executing one instruction.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Step {
+    /// A control instruction, a check or an action which doesn't take time was executed.
+    Control,
+    /// An action which takes time was performed.
+    Action,
+    /// The program is finished with [`End`](InstructionId::End).
+    Finished,
+}
+
+/// Executes a [`Program`] instruction by instruction.
+///
+/// # Control flow
+///
+/// Instructions are executed in order of the program, starting with the first one. After the
+/// last one the execution starts over. Besides:
+/// * [`End`] finishes the program
+/// * [`Back`] and [`Restart`] start over at once, forgetting the return positions and the condition
+/// * [`GoTo`] jumps to its label
+/// * [`GoSub`], [`GoSubF`] and [`GoSub1`] jump to their label, remembering the position after
+///   them. [`Return`], [`ReturnF`] and [`Return1`] go back to the position the matching call
+///   remembered. `GoSub` and `GoSubF` have stacks of [`MAX_CALL_DEPTH`] positions each (the
+///   oldest positions are forgotten), `GoSub1` remembers only the last position. A return
+///   without a call does nothing
+/// * [`OnResp`] sets its label as the response handler. Every time the [`Environment`] asks to
+///   respond, the handler is called the same way as `GoSub` calls
+/// * jumps to undefined labels do nothing
+///
+/// [`DebugSet`] sets the [debug tag](Self::debug_tag). [`Empty`], [`Label`], [`Start`], [`Last`]
+/// and [`DebugBreak`] do nothing, debuggers stop at the last one (see
+/// [`Debugger`](crate::simulation::debugger::Debugger)).
+///
+/// # Conditions
+///
+/// `Cell*` instructions select the cell to check, `Cc*`, `Ccc*` and `Cb*` ones are checked by the
+/// [`Environment`]. [`VarMore`], [`VarLess`] and [`VarEqual`] compare a [variable](Self::variable)
+/// with a value. Results are combined with `OR` or `AND` (see [`BoolModeOr`] and [`BoolModeAnd`])
+/// until [`IfGoTo`] or [`IfNotGoTo`] consumes the combined result. Without checks the result is
+/// `false`.
+///
+/// The rest of the instructions are [actions](Environment::act).
+///
+/// [`End`]: InstructionId::End
+/// [`Back`]: InstructionId::Back
+/// [`Restart`]: InstructionId::Restart
+/// [`GoTo`]: InstructionId::GoTo
+/// [`GoSub`]: InstructionId::GoSub
+/// [`GoSubF`]: InstructionId::GoSubF
+/// [`GoSub1`]: InstructionId::GoSub1
+/// [`Return`]: InstructionId::Return
+/// [`ReturnF`]: InstructionId::ReturnF
+/// [`Return1`]: InstructionId::Return1
+/// [`MAX_CALL_DEPTH`]: Self::MAX_CALL_DEPTH
+/// [`OnResp`]: InstructionId::OnResp
+/// [`Empty`]: InstructionId::Empty
+/// [`Label`]: InstructionId::Label
+/// [`Start`]: InstructionId::Start
+/// [`Last`]: InstructionId::Last
+/// [`DebugBreak`]: InstructionId::DebugBreak
+/// [`DebugSet`]: InstructionId::DebugSet
+/// [`VarMore`]: InstructionId::VarMore
+/// [`VarLess`]: InstructionId::VarLess
+/// [`VarEqual`]: InstructionId::VarEqual
+/// [`BoolModeOr`]: InstructionId::BoolModeOr
+/// [`BoolModeAnd`]: InstructionId::BoolModeAnd
+/// [`IfGoTo`]: InstructionId::IfGoTo
+/// [`IfNotGoTo`]: InstructionId::IfNotGoTo
+///
+/// # Examples
+///
+/// ```
+/// use m3c::formats::internal::literals::LabelIdentifierLiteral;
+/// use m3c::formats::internal::{Instruction, InstructionId, Program};
+/// use m3c::interpreter::{Environment, Interpreter, Step};
+///
+/// /// Records actions, every cell is empty.
+/// struct Recorder(Vec<InstructionId>);
+///
+/// impl Environment for Recorder {
+///     fn act(&mut self, id: InstructionId) -> bool {
+///         self.0.push(id);
+///         true
+///     }
+///     fn check(&mut self, id: InstructionId, _selector: InstructionId) -> bool {
+///         id == InstructionId::CcEmpty
+///     }
+/// }
+///
+/// let label = LabelIdentifierLiteral::new_from_array(*b"a\0\0\0").unwrap();
+/// let mut program = Program::default();
+/// program[0] = Instruction::new_simple(InstructionId::CcEmpty).unwrap();
+/// program[1] = Instruction::new_label(InstructionId::IfGoTo, label).unwrap();
+/// program[2] = Instruction::new_simple(InstructionId::MoveW).unwrap();
+/// program[3] = Instruction::new_label(InstructionId::Label, label).unwrap();
+/// program[4] = Instruction::new_simple(InstructionId::MoveS).unwrap();
+/// program[5] = Instruction::new_simple(InstructionId::End).unwrap();
+///
+/// let mut interpreter = Interpreter::new(&program);
+/// let mut recorder = Recorder(vec![]);
+///
+/// assert_eq!(Step::Action, interpreter.tick(&mut recorder));
+/// assert_eq!(Step::Finished, interpreter.tick(&mut recorder));
+/// assert_eq!(vec![InstructionId::MoveS], recorder.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Interpreter<'p> {
+    program: &'p Program,
+    labels: LabelAnalysis,
+    // the next instruction to execute
+    position: InstructionPosition,
+    finished: bool,
+    // return positions, the last one is on top
+    calls: Vec<InstructionPosition>,
+    calls_f: Vec<InstructionPosition>,
+    call1: Option<InstructionPosition>,
+    handler: Option<InstructionPosition>,
+    // conditions
+    selector: InstructionId,
+    bool_mode: BoolMode,
+    condition: Option<bool>,
+    variables: HashMap<VariableIdentifierLiteral, i32>,
+    debug_tag: Option<StringLiteral>,
+}
+
+impl<'p> Interpreter<'p> {
+    /// The maximum number of return positions `GoSub` and `GoSubF` remember.
+    pub const MAX_CALL_DEPTH: usize = 256;
+    /// The maximum number of instructions executed per [tick](Self::tick). Reached only if the
+    /// program loops without actions.
+    pub const MAX_STEPS_PER_TICK: usize = Program::INSTRUCTIONS_PER_PROGRAM;
+
+    /// Creates an interpreter executing the `program` from its first instruction.
+    pub fn new(program: &'p Program) -> Self {
+        Self {
+            program,
+            labels: LabelAnalysis::new(program),
+            position: InstructionPosition::default(),
+            finished: false,
+            calls: vec![],
+            calls_f: vec![],
+            call1: None,
+            handler: None,
+            selector: InstructionId::CellF,
+            bool_mode: BoolMode::default(),
+            condition: None,
+            variables: HashMap::new(),
+            debug_tag: None,
+        }
+    }
+    /// Returns the program.
+    pub fn program(&self) -> &'p Program {
+        self.program
+    }
+    /// Returns position of the next instruction to execute.
+    pub fn position(&self) -> InstructionPosition {
+        self.position
+    }
+    /// Whether the program is finished with [`End`](InstructionId::End).
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+    /// Returns the return positions of [`GoSub`](InstructionId::GoSub) calls, the last one is on
+    /// top.
+    pub fn calls(&self) -> &[InstructionPosition] {
+        &self.calls
+    }
+    /// Returns the return positions of [`GoSubF`](InstructionId::GoSubF) calls, the last one is
+    /// on top.
+    pub fn calls_f(&self) -> &[InstructionPosition] {
+        &self.calls_f
+    }
+    /// Returns the return position of the last [`GoSub1`](InstructionId::GoSub1) call.
+    pub fn call1(&self) -> Option<InstructionPosition> {
+        self.call1
+    }
+    /// Returns position of the [`OnResp`](InstructionId::OnResp) handler.
+    pub fn handler(&self) -> Option<InstructionPosition> {
+        self.handler
+    }
+    /// Returns the selected `Cell*` instruction.
+    pub fn selector(&self) -> InstructionId {
+        self.selector
+    }
+    /// Returns how results of checks are combined.
+    pub fn bool_mode(&self) -> BoolMode {
+        self.bool_mode
+    }
+    /// Returns the combined result of checks so far. Value `None` stands for no checks since the
+    /// last `If*` instruction.
+    pub fn condition(&self) -> Option<bool> {
+        self.condition
+    }
+    /// Returns the value of the variable. Variables are `0` until [set](Self::set_variable).
+    pub fn variable(&self, name: VariableIdentifierLiteral) -> i32 {
+        self.variables.get(&name).copied().unwrap_or(0)
+    }
+    /// Returns all the set variables.
+    pub fn variables(&self) -> impl Iterator<Item = (VariableIdentifierLiteral, i32)> + '_ {
+        self.variables.iter().map(|(&name, &value)| (name, value))
+    }
+    /// Sets the value of the variable.
+    pub fn set_variable(&mut self, name: VariableIdentifierLiteral, value: i32) {
+        self.variables.insert(name, value);
+    }
+    /// Returns the tag of the last executed [`DebugSet`](InstructionId::DebugSet).
+    pub fn debug_tag(&self) -> Option<StringLiteral> {
+        self.debug_tag
+    }
+    /// Executes instructions up to the first action which takes time, the end of the program or
+    /// [`MAX_STEPS_PER_TICK`](Self::MAX_STEPS_PER_TICK) instructions.
+    pub fn tick<E>(&mut self, env: &mut E) -> Step
+    where
+        E: Environment + ?Sized,
+    {
+        for _ in 0..Self::MAX_STEPS_PER_TICK {
+            match self.step(env) {
+                Step::Control => continue,
+                step => return step,
+            }
+        }
+        Step::Control
+    }
+    /// Executes the next instruction.
+    ///
+    /// If the `env` asks to respond and there is a handler, the handler is called instead.
+    pub fn step<E>(&mut self, env: &mut E) -> Step
+    where
+        E: Environment + ?Sized,
+    {
+        if self.finished {
+            return Step::Finished;
+        }
+        if env.poll_response() {
+            if let Some(handler) = self.handler {
+                Self::push(&mut self.calls, self.position);
+                self.position = handler;
+                return Step::Control;
+            }
+        }
+
+        let current = self.position;
+        if self.position.move_forward().is_err() {
+            self.position = InstructionPosition::default();
+        }
+        let instruction = self.program[current];
+        let id = instruction.id();
+        match id {
+            InstructionId::End => {
+                self.finished = true;
+                return Step::Finished;
+            }
+            InstructionId::Back | InstructionId::Restart => {
+                self.position = InstructionPosition::default();
+                self.calls.clear();
+                self.calls_f.clear();
+                self.call1 = None;
+                self.condition = None;
+            }
+            InstructionId::GoTo => {
+                if let Some(target) = self.labels.target(current) {
+                    self.position = target;
+                }
+            }
+            InstructionId::GoSub | InstructionId::GoSubF | InstructionId::GoSub1 => {
+                if let Some(target) = self.labels.target(current) {
+                    match id {
+                        InstructionId::GoSub => Self::push(&mut self.calls, self.position),
+                        InstructionId::GoSubF => Self::push(&mut self.calls_f, self.position),
+                        _ => self.call1 = Some(self.position),
+                    }
+                    self.position = target;
+                }
+            }
+            InstructionId::Return | InstructionId::ReturnF | InstructionId::Return1 => {
+                let back = match id {
+                    InstructionId::Return => self.calls.pop(),
+                    InstructionId::ReturnF => self.calls_f.pop(),
+                    _ => self.call1.take(),
+                };
+                if let Some(back) = back {
+                    self.position = back;
+                }
+            }
+            InstructionId::OnResp => self.handler = self.labels.target(current),
+            InstructionId::IfGoTo | InstructionId::IfNotGoTo => {
+                let condition = self.condition.take().unwrap_or(false);
+                if condition == (id == InstructionId::IfGoTo) {
+                    if let Some(target) = self.labels.target(current) {
+                        self.position = target;
+                    }
+                }
+            }
+            InstructionId::BoolModeOr => self.bool_mode = BoolMode::Or,
+            InstructionId::BoolModeAnd => self.bool_mode = BoolMode::And,
+            InstructionId::VarMore | InstructionId::VarLess | InstructionId::VarEqual => {
+                let InstructionData::VarCmp((name, value)) = instruction.data() else {
+                    unreachable!("`Var*` instructions always have a variable and a value")
+                };
+                let (variable, value) = (self.variable(name), value.data());
+                self.combine(match id {
+                    InstructionId::VarMore => variable > value,
+                    InstructionId::VarLess => variable < value,
+                    _ => variable == value,
+                });
+            }
+            InstructionId::DebugSet => {
+                let InstructionData::String(tag) = instruction.data() else {
+                    unreachable!("`DebugSet` always has a tag")
+                };
+                self.debug_tag = Some(tag);
+            }
+            InstructionId::Empty
+            | InstructionId::Label
+            | InstructionId::Start
+            | InstructionId::Last
+            | InstructionId::DebugBreak => {}
+            _ if Self::is_selector(id) => self.selector = id,
+            _ if Self::is_check(id) => {
+                let result = env.check(id, self.selector);
+                self.combine(result);
+            }
+            _ => {
+                if env.act(id) {
+                    return Step::Action;
+                }
+            }
+        }
+        Step::Control
+    }
+    /// Pushes the return position, forgetting the oldest one if the stack is full.
+    fn push(stack: &mut Vec<InstructionPosition>, position: InstructionPosition) {
+        if stack.len() == Self::MAX_CALL_DEPTH {
+            stack.remove(0);
+        }
+        stack.push(position);
+    }
+    /// Combines the result of a check with the condition.
+    fn combine(&mut self, result: bool) {
+        self.condition = Some(match (self.condition, self.bool_mode) {
+            (None, _) => result,
+            (Some(condition), BoolMode::Or) => condition || result,
+            (Some(condition), BoolMode::And) => condition && result,
+        });
+    }
+    /// Checks if the instruction with the given `id` selects a cell to check.
+    pub fn is_selector(id: InstructionId) -> bool {
+        id.info().map(InstructionInfo::category) == Some(Category::Cell)
+    }
+    /// Checks if the instruction with the given `id` is checked by the [`Environment`].
+    pub fn is_check(id: InstructionId) -> bool {
+        id.info().map(InstructionInfo::category) == Some(Category::Check)
+    }
+}
@@ -1,3 +1,6 @@
+pub mod analysis;
 pub mod formats;
+pub mod interpreter;
 pub mod serialization;
+pub mod simulation;
 pub mod utils;
@@ -3,205 +3,80 @@
 //! It's only available to serialize from [Internal format](crate::formats::internal)
 //! and deserialize into [Internal format](crate::formats::internal).
 
-use std::io;
+use std::{error::Error, fmt, io, str::FromStr};
 
+use crate::formats::custom::assembly::diagnostics::{
+    Diagnostic, Diagnostics, IllegalLiteral, IllegalOperand, ProgramOverflow, UnknownMnemonic,
+};
 use crate::formats::internal::{
-    literals::Literal, Instruction, InstructionData, InstructionId, InstructionPosition, Program,
+    literals::{IdentifierRules, LabelRules, Literal, VariableRules},
+    registry::{self, InstructionInfo},
+    Instruction, InstructionData, InstructionId, InstructionKind, InstructionPosition, Program,
 };
+use crate::utils::{CharPosition, EnumerateWithPosition};
+
+// region: errors
+
+/// An error returned when parsing an [`Instruction`] from its assembly syntax.
+///
+/// Every index is a char index in the parsed string.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ParseInstructionError {
+    /// There is no instruction, the string is empty or contains only a comment.
+    Empty,
+    /// The mnemonic is unknown. Contains index of its first char.
+    UnknownMnemonic(usize),
+    /// The operands don't match the instruction. Contains index of their first char.
+    IllegalOperand(usize),
+    /// A literal is illegal. Contains index of its first char.
+    IllegalLiteral(usize),
+}
+
+impl fmt::Display for ParseInstructionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "no instruction was found"),
+            Self::UnknownMnemonic(index) => {
+                write!(f, "an unknown mnemonic was found at index {}", index)
+            }
+            Self::IllegalOperand(index) => {
+                write!(f, "illegal operands were found at index {}", index)
+            }
+            Self::IllegalLiteral(index) => {
+                write!(f, "an illegal literal was found at index {}", index)
+            }
+        }
+    }
+}
+
+impl Error for ParseInstructionError {}
 
-static FULLY_EMPTY_STRING: &str = "";
-
-static INSTRUCTIONS_NAMES: [&str; 183] = [
-    "EMPTY",
-    "BACK",
-    "START",
-    "END",
-    "MOVE_W",
-    "MOVE_A",
-    "MOVE_S",
-    "MOVE_D",
-    "DIGG",
-    "LOOK_W",
-    "LOOK_A",
-    "LOOK_S",
-    "LOOK_D",
-    FULLY_EMPTY_STRING, // 13, `LAST`
-    "MOVE_F",
-    "ROTATE_CCW",
-    "ROTATE_CW",
-    "ACTION_BUILD",
-    "ACTION_GEO",
-    "ACTION_ROAD",
-    "ACTION_HEAL",
-    "ACTION_QUADRO",
-    "ACTION_RANDOM",
-    "ACTION_BIBIKA",
-    "GOTO",
-    "GOSUB",
-    "GOSUB1",
-    "RETURN",
-    "RETURN1",
-    "CELL_WA",
-    "CELL_SD",
-    "CELL_W",
-    "CELL_DW",
-    "CELL_A",
-    FULLY_EMPTY_STRING, // 34
-    "CELL_D",
-    "CELL_AS",
-    "CELL_S",
-    "BOOLMODE_OR",
-    "BOOLMODE_AND",
-    "LABEL",
-    FULLY_EMPTY_STRING, // 41
-    FULLY_EMPTY_STRING, // 42
-    "CC_NOTEMPTY",
-    "CC_EMPTY",
-    "CC_GRAVITY",
-    "CC_CRYSTALL",
-    "CC_ALIVE",
-    "CC_BOLDER",
-    "CC_SAND",
-    "CC_ROCK",
-    "CC_DEAD",
-    "CCC_REDROCK",
-    "CCC_BLACKROCK",
-    "CC_ACID",
-    FULLY_EMPTY_STRING, // 55
-    FULLY_EMPTY_STRING, // 56
-    "CCC_QUADRO",
-    "CCC_ROAD",
-    "CCC_REDBLOCK",
-    "CCC_YELLOWBLOCK",
-    FULLY_EMPTY_STRING, // 61
-    FULLY_EMPTY_STRING, // 62
-    FULLY_EMPTY_STRING, // 63
-    FULLY_EMPTY_STRING, // 64
-    FULLY_EMPTY_STRING, // 65
-    FULLY_EMPTY_STRING, // 66
-    FULLY_EMPTY_STRING, // 67
-    FULLY_EMPTY_STRING, // 68
-    FULLY_EMPTY_STRING, // 69
-    FULLY_EMPTY_STRING, // 70
-    FULLY_EMPTY_STRING, // 71
-    FULLY_EMPTY_STRING, // 72
-    FULLY_EMPTY_STRING, // 73
-    "CCC_BOX",
-    FULLY_EMPTY_STRING, // 75
-    "CCC_OPOR",
-    "CCC_GREENBLOCK",
-    FULLY_EMPTY_STRING, // 78
-    FULLY_EMPTY_STRING, // 79
-    FULLY_EMPTY_STRING, // 80
-    FULLY_EMPTY_STRING, // 81
-    FULLY_EMPTY_STRING, // 82
-    FULLY_EMPTY_STRING, // 83
-    FULLY_EMPTY_STRING, // 84
-    FULLY_EMPTY_STRING, // 85
-    FULLY_EMPTY_STRING, // 86
-    FULLY_EMPTY_STRING, // 87
-    FULLY_EMPTY_STRING, // 88
-    FULLY_EMPTY_STRING, // 89
-    FULLY_EMPTY_STRING, // 90
-    FULLY_EMPTY_STRING, // 91
-    FULLY_EMPTY_STRING, // 92
-    FULLY_EMPTY_STRING, // 93
-    FULLY_EMPTY_STRING, // 94
-    FULLY_EMPTY_STRING, // 95
-    FULLY_EMPTY_STRING, // 96
-    FULLY_EMPTY_STRING, // 97
-    FULLY_EMPTY_STRING, // 98
-    FULLY_EMPTY_STRING, // 99
-    FULLY_EMPTY_STRING, // 100
-    FULLY_EMPTY_STRING, // 101
-    FULLY_EMPTY_STRING, // 102
-    FULLY_EMPTY_STRING, // 103
-    FULLY_EMPTY_STRING, // 104
-    FULLY_EMPTY_STRING, // 105
-    FULLY_EMPTY_STRING, // 106
-    FULLY_EMPTY_STRING, // 107
-    FULLY_EMPTY_STRING, // 108
-    FULLY_EMPTY_STRING, // 109
-    FULLY_EMPTY_STRING, // 110
-    FULLY_EMPTY_STRING, // 111
-    FULLY_EMPTY_STRING, // 112
-    FULLY_EMPTY_STRING, // 113
-    FULLY_EMPTY_STRING, // 114
-    FULLY_EMPTY_STRING, // 115
-    FULLY_EMPTY_STRING, // 116
-    FULLY_EMPTY_STRING, // 117
-    FULLY_EMPTY_STRING, // 118
-    "VAR_MORE",
-    "VAR_LESS",
-    FULLY_EMPTY_STRING, // 121
-    FULLY_EMPTY_STRING, // 122
-    "VAR_EQUAL",
-    FULLY_EMPTY_STRING, // 124
-    FULLY_EMPTY_STRING, // 125
-    FULLY_EMPTY_STRING, // 126
-    FULLY_EMPTY_STRING, // 127
-    FULLY_EMPTY_STRING, // 128
-    FULLY_EMPTY_STRING, // 129
-    FULLY_EMPTY_STRING, // 130
-    "CELL_WW",
-    "CELL_AA",
-    "CELL_SS",
-    "CELL_DD",
-    "CELL_F",
-    "CELL_FF",
-    "GOSUBF",
-    "RETURNF",
-    "IF_NOT_GOTO",
-    "IF_GOTO",
-    "STD_DIGG",
-    "STD_BUILD",
-    "STD_HEAL",
-    "PROG_FLIP",
-    "STD_MINE",
-    "CC_GUN",
-    "FILL_GUN",
-    "CB_HP",
-    "CB_HP50",
-    FULLY_EMPTY_STRING, // 150
-    FULLY_EMPTY_STRING, // 151
-    FULLY_EMPTY_STRING, // 152
-    FULLY_EMPTY_STRING, // 153
-    FULLY_EMPTY_STRING, // 154
-    FULLY_EMPTY_STRING, // 155
-    "CELL_RIGHT_HAND",
-    "CELL_LEFT_HAND",
-    "MODE_AUTODIGG_ON",
-    "MODE_AUTODIGG_OFF",
-    "MODE_AGR_ON",
-    "MODE_AGR_OFF",
-    "ACTION_B1",
-    "ACTION_B3",
-    "ACTION_B2",
-    "ACTION_WB",
-    "ON_RESP",
-    "ACTION_GEOPACK",
-    "ACTION_ZM",
-    "ACTION_C190",
-    "ACTION_POLY",
-    "ACTION_UP",
-    "ACTION_CRAFT",
-    "ACTION_NANO",
-    "ACTION_REMBOT",
-    "INVDIR_W",
-    "INVDIR_A",
-    "INVDIR_S",
-    "INVDIR_D",
-    "HANDMODE_ON",
-    "HANDMODE_OFF",
-    "DEBUG_BREAK",
-    "DEBUG_SET",
-    // 200, `RESTART`
-];
+// endregion: errors
 
 impl InstructionId {
     /// Returns the identifier from the native client for this [`InstructionId`].
-    fn client_identifier(self) -> &'static str {
-        INSTRUCTIONS_NAMES[self as usize]
+    ///
+    /// Returns `None` if the id is [`Unknown`](InstructionId::Unknown).
+    pub(crate) fn client_identifier(self) -> Option<&'static str> {
+        self.info().map(InstructionInfo::mnemonic)
+    }
+    /// Returns the [`InstructionId`] for the given identifier from the native client or for the
+    /// given numeric value (e.g. `250`).
+    ///
+    /// Returns `None` if there is no such identifier.
+    pub(crate) fn from_client_identifier(identifier: &str) -> Option<Self> {
+        if identifier.starts_with(|ch: char| ch.is_ascii_digit()) {
+            return identifier.parse().ok().map(Self::from_value);
+        }
+        registry::find_by_mnemonic(identifier).map(InstructionInfo::id)
+    }
+    /// Returns the identifier from the native client, or the numeric value if the id is
+    /// [`Unknown`](InstructionId::Unknown).
+    pub(crate) fn mnemonic(self) -> String {
+        match self.client_identifier() {
+            Some(identifier) => identifier.to_string(),
+            None => self.value().to_string(),
+        }
     }
     /// Writes the identifier from the native client for this [`InstructionId`] to the given
     /// `writer`.
@@ -217,7 +92,7 @@ impl InstructionId {
     where
         W: io::Write,
     {
-        writer.write_all(INSTRUCTIONS_NAMES[self as usize].as_bytes())
+        writer.write_all(self.mnemonic().as_bytes())
     }
 }
 
@@ -226,7 +101,7 @@ impl Instruction {
     ///
     /// The returned string will be prefixed by the given `indent` if this instruction
     /// [id](InstructionId) is not equal to [`Label`](InstructionId::Label).
-    fn dumps_to(&self, s: &mut String, indent: &str) {
+    pub(crate) fn dumps_to(&self, s: &mut String, indent: &str) {
         let id = self.id();
         let data = self.data();
         if id == InstructionId::Label {
@@ -239,7 +114,7 @@ impl Instruction {
             }
         } else {
             s.push_str(indent);
-            s.push_str(id.client_identifier());
+            s.push_str(&id.mnemonic());
             match data {
                 InstructionData::Simple => {}
                 InstructionData::Label(label) => {
@@ -279,7 +154,7 @@ impl Instruction {
             match data {
                 InstructionData::Label(label) => {
                     label.write_all(writer)?;
-                    writer.write_all(&[b';'])?;
+                    writer.write_all(b":")?;
                 }
                 _ => unreachable!(),
             }
@@ -289,18 +164,18 @@ impl Instruction {
             match data {
                 InstructionData::Simple => {}
                 InstructionData::Label(label) => {
-                    writer.write_all(&[b' '])?;
+                    writer.write_all(b" ")?;
                     label.write_all(writer)?;
                 }
                 InstructionData::String(string_literal) => {
-                    writer.write_all(&[b' ', b'\''])?;
+                    writer.write_all(b" '")?;
                     string_literal.write_all(writer)?;
-                    writer.write_all(&[b'\''])?;
+                    writer.write_all(b"'")?;
                 }
                 InstructionData::VarCmp((identifier, value)) => {
-                    writer.write_all(&[b' '])?;
+                    writer.write_all(b" ")?;
                     identifier.write_all(writer)?;
-                    writer.write_all(&[b',', b' '])?;
+                    writer.write_all(b", ")?;
                     value.write_all(writer)?;
                 }
             }
@@ -309,6 +184,62 @@ impl Instruction {
     }
 }
 
+/// Parses one line of the [assembly format](Deserializer), e.g. `GOTO abc` or `abc:`.
+///
+/// # Examples
+///
+/// ```
+/// use m3c::formats::internal::{Instruction, InstructionId};
+/// use m3c::serialization::custom::assembly::ParseInstructionError;
+///
+/// let instruction: Instruction = "VAR_MORE x, -5".parse().unwrap();
+/// assert_eq!(InstructionId::VarMore, instruction.id());
+/// assert_eq!("VAR_MORE x, -5", instruction.to_string());
+///
+/// assert_eq!(
+///     Err(ParseInstructionError::IllegalLiteral(5)),
+///     "GOTO abcd".parse::<Instruction>()
+/// );
+/// ```
+impl FromStr for Instruction {
+    type Err = ParseInstructionError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chars: Vec<_> = EnumerateWithPosition::new(s).collect();
+        let mut line = Line::new(&chars);
+        if line.is_at_end() {
+            return Err(ParseInstructionError::Empty);
+        }
+        let mut deserializer = Deserializer::new(s);
+        deserializer.parse_instruction(&mut line).ok_or_else(|| {
+            // an illegal instruction is always reported
+            let diagnostic = deserializer.diagnostics.pop().unwrap();
+            let index = diagnostic.position().index;
+            match diagnostic {
+                Diagnostics::UnknownMnemonic(_) => ParseInstructionError::UnknownMnemonic(index),
+                Diagnostics::IllegalOperand(_) => ParseInstructionError::IllegalOperand(index),
+                Diagnostics::IllegalLiteral(_) => ParseInstructionError::IllegalLiteral(index),
+                Diagnostics::ProgramOverflow(_) => unreachable!(),
+            }
+        })
+    }
+}
+
+impl TryFrom<&str> for Instruction {
+    type Error = ParseInstructionError;
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// Formats the instruction in the [assembly format](Serializer) without an indent.
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut s = String::new();
+        self.dumps_to(&mut s, "");
+        f.write_str(&s)
+    }
+}
+
 impl InstructionPosition {
     /// Dumps this position to the given `String`.
     fn dumps_to(self, s: &mut String, hide_column: bool) {
@@ -511,9 +442,9 @@ impl<'p> Serializer<'p> {
         for (position, instruction) in instruction_positions {
             if position.column() == 0 {
                 writer.write_all(Self::LINE_SEPARATOR.as_bytes())?;
-                writer.write_all(&[b';', b' ', b'('])?;
+                writer.write_all(b"; (")?;
                 position.write_all(writer, true)?;
-                writer.write_all(&[b')', b' '])?;
+                writer.write_all(b") ")?;
                 if position.row() == 0 {
                     writer.write_all(Self::NEW_PAGE_WARN.as_bytes())?;
                 } else {
@@ -528,6 +459,330 @@ impl<'p> Serializer<'p> {
     }
 }
 
+/// A cursor over the chars (and their positions) of one line of the source.
+#[derive(Debug)]
+struct Line<'l> {
+    chars: &'l [(CharPosition, char)],
+    index: usize,
+}
+
+impl<'l> Line<'l> {
+    fn new(chars: &'l [(CharPosition, char)]) -> Self {
+        Self { chars, index: 0 }
+    }
+    /// Returns the next char without consuming it.
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.index).map(|&(_, ch)| ch)
+    }
+    /// Consumes the next char if it's equal to `expected`.
+    fn consume(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+    /// Consumes chars while `predicate` returns `true`. Returns the range of consumed chars.
+    fn consume_while<P>(&mut self, predicate: P) -> (usize, usize)
+    where
+        P: Fn(char) -> bool,
+    {
+        let start = self.index;
+        while let Some(ch) = self.peek() {
+            if !predicate(ch) {
+                break;
+            }
+            self.index += 1;
+        }
+        (start, self.index)
+    }
+    /// Consumes chars up to the first not escaped (with `\`) `end`. Returns the range of
+    /// consumed chars.
+    fn consume_escaped_until(&mut self, end: char) -> (usize, usize) {
+        let start = self.index;
+        while let Some(ch) = self.peek() {
+            if ch == end {
+                break;
+            }
+            self.index += 1;
+            if ch == '\\' && self.peek().is_some() {
+                self.index += 1;
+            }
+        }
+        (start, self.index)
+    }
+    /// Skips whitespaces.
+    fn skip_whitespaces(&mut self) {
+        self.consume_while(|ch| ch == ' ' || ch == '\t' || ch == '\r');
+    }
+    /// Checks if there is nothing but whitespaces and a comment (if any) left.
+    fn is_at_end(&mut self) -> bool {
+        self.skip_whitespaces();
+        matches!(self.peek(), None | Some(';'))
+    }
+    /// Returns the range from the current char up to the last not whitespace char before the
+    /// comment (if any).
+    fn rest(&self) -> (usize, usize) {
+        let mut end = self.index;
+        let mut last_not_whitespace = self.index;
+        while let Some(&(_, ch)) = self.chars.get(end) {
+            if ch == ';' {
+                break;
+            }
+            end += 1;
+            if !ch.is_whitespace() {
+                last_not_whitespace = end;
+            }
+        }
+        (self.index, last_not_whitespace)
+    }
+    /// Returns chars in the given range as `String`.
+    fn string(&self, (start, end): (usize, usize)) -> String {
+        self.chars[start..end].iter().map(|&(_, ch)| ch).collect()
+    }
+    /// Returns positions of the first and the last chars of the given range.
+    ///
+    /// For an empty range both positions point to the char at `start` (or to the last char of the
+    /// line, if `start` is the end of the line).
+    fn span(&self, (start, end): (usize, usize)) -> (CharPosition, CharPosition) {
+        let last = self.chars.len() - 1;
+        let start_pos = self.chars[start.min(last)].0;
+        let end_pos = self.chars[end.max(start + 1).min(last + 1) - 1].0;
+        (start_pos, end_pos)
+    }
+}
+
+/// A structure for deserializing human-readable assembly-like format into [`Program`].
+///
+/// Each not empty line (except comments) is parsed as one instruction and placed into the next
+/// cell of the program, so the lines are placed the same way [`Serializer`] writes them:
+///
+/// * `MNEMONIC` - an instruction of the [`Simple`](InstructionKind::Simple) kind
+/// * `label:` - a [`Label`](InstructionId::Label) instruction
+/// * `MNEMONIC label` - an instruction of the [`Label`](InstructionKind::Label) kind
+/// * `MNEMONIC 'str'` - an instruction of the [`String`](InstructionKind::String) kind, the
+///   chars of the [`StringLiteral`](crate::formats::internal::literals::StringLiteral) which
+///   need escape are escaped with `\`
+/// * `MNEMONIC var, value` - an instruction of the [`VarCmp`](InstructionKind::VarCmp) kind
+/// * `; comment` - a comment (can also follow an instruction)
+///
+/// Mnemonics are the identifiers from the native client (`MOVE_W`, `GOTO`, ...). A line with an
+/// error is reported and leaves its cell [Empty](InstructionId::Empty).
+#[derive(Debug)]
+pub struct Deserializer<'s> {
+    // original source
+    source: &'s str,
+    // instruction position at program. Value `None` stands for the program is already full
+    ins_pos: Option<InstructionPosition>,
+    // diagnostics
+    diagnostics: Vec<Diagnostics>,
+}
+
+impl<'s> Deserializer<'s> {
+    /// Creates a new [`Deserializer`] from a `&str`.
+    pub fn new(source: &'s str) -> Self {
+        Self {
+            source,
+            ins_pos: Some(InstructionPosition::default()),
+            diagnostics: vec![],
+        }
+    }
+    /// Deserializes the source into the given `program` and returns found diagnostics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use m3c::formats::internal::{Instruction, InstructionId, Program};
+    /// use m3c::serialization::custom::assembly::Deserializer;
+    ///
+    /// let mut program = Program::default();
+    ///
+    /// let mut de = Deserializer::new("    MOVE_W\n; a comment\nlab:\n    GOTO lab\n");
+    /// let diagnostics = de.deserialize(&mut program);
+    ///
+    /// assert!(diagnostics.is_empty());
+    /// assert_eq!(InstructionId::MoveW, program[0].id());
+    /// assert_eq!(InstructionId::Label, program[1].id());
+    /// assert_eq!(InstructionId::GoTo, program[2].id());
+    /// ```
+    pub fn deserialize(&mut self, program: &mut Program) -> Vec<Diagnostics> {
+        // resets
+        program.reset();
+        self.ins_pos = Some(InstructionPosition::default());
+
+        let mut line = Vec::new();
+        for (pos, ch) in EnumerateWithPosition::new(self.source) {
+            if ch == '\n' {
+                self.parse_line(&line, program);
+                line.clear();
+            } else {
+                line.push((pos, ch));
+            }
+        }
+        self.parse_line(&line, program);
+
+        std::mem::take(&mut self.diagnostics)
+    }
+    /// Parses one line and places the instruction (if any) into the `program`.
+    fn parse_line(&mut self, chars: &[(CharPosition, char)], program: &mut Program) {
+        let mut line = Line::new(chars);
+        if line.is_at_end() {
+            return;
+        }
+
+        let pos = match self.ins_pos {
+            Some(pos) => pos,
+            None => {
+                // report only the first instruction which doesn't fit
+                if !matches!(
+                    self.diagnostics.last(),
+                    Some(Diagnostics::ProgramOverflow(_))
+                ) {
+                    self.diagnostics
+                        .push(ProgramOverflow::new(chars[line.index].0).into());
+                }
+                return;
+            }
+        };
+
+        if let Some(instruction) = self.parse_instruction(&mut line) {
+            program[pos] = instruction;
+        }
+
+        let mut next = pos;
+        self.ins_pos = match next.move_forward() {
+            Ok(_) => Some(next),
+            Err(_) => None,
+        };
+    }
+    /// Parses one instruction. Returns `None` (and reports it) if the instruction is illegal.
+    fn parse_instruction(&mut self, line: &mut Line) -> Option<Instruction> {
+        let word = line.consume_while(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+        line.skip_whitespaces();
+
+        // label definition
+        if line.consume(':') {
+            let operands_start = line.index;
+            self.expect_end(line, operands_start)?;
+            let label = self.parse_literal(line, word)?;
+            return Instruction::new_label(InstructionId::Label, label).ok();
+        }
+
+        let id = if word.0 == word.1 {
+            None
+        } else {
+            InstructionId::from_client_identifier(&line.string(word))
+        };
+        let id = match id {
+            Some(id) => id,
+            None => {
+                let word = if word.0 == word.1 {
+                    line.consume_while(|ch| !ch.is_whitespace() && ch != ';')
+                } else {
+                    word
+                };
+                let (start, end) = line.span(word);
+                self.diagnostics
+                    .push(UnknownMnemonic::new(start, end).into());
+                return None;
+            }
+        };
+
+        let operands_start = line.index;
+        match id.kind() {
+            InstructionKind::Simple => {
+                self.expect_end(line, operands_start)?;
+                Instruction::new_simple(id).ok()
+            }
+            InstructionKind::Label => {
+                let label = line.consume_while(LabelRules::is_allowed);
+                self.expect_end(line, operands_start)?;
+                let label = self.parse_literal(line, label)?;
+                Instruction::new_label(id, label).ok()
+            }
+            InstructionKind::String => {
+                if !line.consume('\'') {
+                    return self.illegal_operand(line, operands_start);
+                }
+                let string = line.consume_escaped_until('\'');
+                if !line.consume('\'') {
+                    return self.illegal_operand(line, operands_start);
+                }
+                self.expect_end(line, operands_start)?;
+                let string = self.parse_escaped_literal(line, string)?;
+                Instruction::new_string(id, string).ok()
+            }
+            InstructionKind::VarCmp => {
+                let name = line.consume_while(VariableRules::is_allowed);
+                line.skip_whitespaces();
+                if !line.consume(',') {
+                    return self.illegal_operand(line, operands_start);
+                }
+                line.skip_whitespaces();
+                let sign = line.consume_while(|ch| ch == '-');
+                let digits = line.consume_while(|ch| ch.is_ascii_digit());
+                if sign.1 - sign.0 > 1 || digits.0 == digits.1 {
+                    return self.illegal_operand(line, operands_start);
+                }
+                self.expect_end(line, operands_start)?;
+                let name = self.parse_literal(line, name)?;
+                let value = self.parse_literal(line, (sign.0, digits.1))?;
+                Instruction::new_var_cmp(id, name, value).ok()
+            }
+        }
+    }
+    /// Parses chars in the given range as a literal. Reports [`IllegalLiteral`] if it's
+    /// impossible.
+    fn parse_literal<L>(&mut self, line: &Line, range: (usize, usize)) -> Option<L>
+    where
+        L: FromStr,
+    {
+        let literal = line.string(range).parse().ok();
+        if literal.is_none() {
+            let (start, end) = line.span(range);
+            self.diagnostics
+                .push(IllegalLiteral::new(start, end).into());
+        }
+        literal
+    }
+    /// Parses chars in the given range as a literal with escaped chars. Reports
+    /// [`IllegalLiteral`] if it's impossible.
+    fn parse_escaped_literal<L>(&mut self, line: &Line, range: (usize, usize)) -> Option<L>
+    where
+        L: Literal,
+    {
+        let s = line.string(range);
+        match L::new_from_enumerate(&mut s.chars().enumerate()) {
+            Ok((literal, None)) => Some(literal),
+            _ => {
+                let (start, end) = line.span(range);
+                self.diagnostics
+                    .push(IllegalLiteral::new(start, end).into());
+                None
+            }
+        }
+    }
+    /// Checks that there is nothing left on the line (except a comment). Reports
+    /// [`IllegalOperand`] starting from `operands_start` if it isn't true.
+    fn expect_end(&mut self, line: &mut Line, operands_start: usize) -> Option<()> {
+        if line.is_at_end() {
+            Some(())
+        } else {
+            self.illegal_operand(line, operands_start)
+        }
+    }
+    /// Reports [`IllegalOperand`] from `operands_start` up to the end of the line (or the comment).
+    fn illegal_operand<T>(&mut self, line: &mut Line, operands_start: usize) -> Option<T> {
+        line.index = operands_start;
+        line.skip_whitespaces();
+        let (start, end) = line.span(line.rest());
+        self.diagnostics
+            .push(IllegalOperand::new(start, end).into());
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::io::Write;
@@ -539,35 +794,82 @@ mod tests {
 
     #[test]
     fn instruction_id_client_identifier() {
-        assert_eq!("EMPTY", InstructionId::Empty.client_identifier());
-        assert_eq!("LOOK_D", InstructionId::LookD.client_identifier());
-        assert_eq!("MOVE_F", InstructionId::MoveF.client_identifier());
-        assert_eq!("CELL_A", InstructionId::CellA.client_identifier());
-        assert_eq!("CELL_D", InstructionId::CellD.client_identifier());
-        assert_eq!("LABEL", InstructionId::Label.client_identifier());
-        assert_eq!("CC_NOTEMPTY", InstructionId::CcNotEmpty.client_identifier());
-        assert_eq!("CC_ACID", InstructionId::CcAcid.client_identifier());
-        assert_eq!("CCC_QUADRO", InstructionId::CccQuadro.client_identifier());
+        assert_eq!(Some("EMPTY"), InstructionId::Empty.client_identifier());
+        assert_eq!(Some("LOOK_D"), InstructionId::LookD.client_identifier());
+        assert_eq!(Some("LAST"), InstructionId::Last.client_identifier());
+        assert_eq!(Some("MOVE_F"), InstructionId::MoveF.client_identifier());
+        assert_eq!(Some("CELL_A"), InstructionId::CellA.client_identifier());
+        assert_eq!(Some("CELL_D"), InstructionId::CellD.client_identifier());
+        assert_eq!(Some("LABEL"), InstructionId::Label.client_identifier());
+        assert_eq!(
+            Some("CC_NOTEMPTY"),
+            InstructionId::CcNotEmpty.client_identifier()
+        );
+        assert_eq!(Some("CC_ACID"), InstructionId::CcAcid.client_identifier());
         assert_eq!(
-            "CCC_YELLOWBLOCK",
+            Some("CCC_QUADRO"),
+            InstructionId::CccQuadro.client_identifier()
+        );
+        assert_eq!(
+            Some("CCC_YELLOWBLOCK"),
             InstructionId::CccYellowBlock.client_identifier()
         );
-        assert_eq!("CCC_BOX", InstructionId::CccBox.client_identifier());
-        assert_eq!("CCC_OPOR", InstructionId::CccOpor.client_identifier());
+        assert_eq!(Some("CCC_BOX"), InstructionId::CccBox.client_identifier());
+        assert_eq!(Some("CCC_OPOR"), InstructionId::CccOpor.client_identifier());
         assert_eq!(
-            "CCC_GREENBLOCK",
+            Some("CCC_GREENBLOCK"),
             InstructionId::CccGreenBlock.client_identifier()
         );
-        assert_eq!("VAR_MORE", InstructionId::VarMore.client_identifier());
-        assert_eq!("VAR_LESS", InstructionId::VarLess.client_identifier());
-        assert_eq!("VAR_EQUAL", InstructionId::VarEqual.client_identifier());
-        assert_eq!("CELL_WW", InstructionId::CellWw.client_identifier());
-        assert_eq!("CB_HP50", InstructionId::CbHp50.client_identifier());
+        assert_eq!(Some("VAR_MORE"), InstructionId::VarMore.client_identifier());
+        assert_eq!(Some("VAR_LESS"), InstructionId::VarLess.client_identifier());
+        assert_eq!(
+            Some("VAR_EQUAL"),
+            InstructionId::VarEqual.client_identifier()
+        );
+        assert_eq!(Some("CELL_WW"), InstructionId::CellWw.client_identifier());
+        assert_eq!(Some("CB_HP50"), InstructionId::CbHp50.client_identifier());
         assert_eq!(
-            "CELL_RIGHT_HAND",
+            Some("CELL_RIGHT_HAND"),
             InstructionId::CellRightHand.client_identifier()
         );
-        assert_eq!("DEBUG_SET", InstructionId::DebugSet.client_identifier());
+        assert_eq!(
+            Some("DEBUG_SET"),
+            InstructionId::DebugSet.client_identifier()
+        );
+        assert_eq!(Some("RESTART"), InstructionId::Restart.client_identifier());
+        assert_eq!(None, InstructionId::Unknown(250).client_identifier());
+    }
+
+    #[test]
+    fn instruction_id_from_client_identifier() {
+        assert_eq!(
+            Some(InstructionId::Empty),
+            InstructionId::from_client_identifier("EMPTY")
+        );
+        assert_eq!(
+            Some(InstructionId::CccGreenBlock),
+            InstructionId::from_client_identifier("CCC_GREENBLOCK")
+        );
+        assert_eq!(
+            Some(InstructionId::DebugSet),
+            InstructionId::from_client_identifier("DEBUG_SET")
+        );
+        assert_eq!(
+            Some(InstructionId::Restart),
+            InstructionId::from_client_identifier("RESTART")
+        );
+        assert_eq!(None, InstructionId::from_client_identifier(""));
+        assert_eq!(None, InstructionId::from_client_identifier("move_w"));
+        assert_eq!(
+            Some(InstructionId::MoveW),
+            InstructionId::from_client_identifier("4")
+        );
+        assert_eq!(
+            Some(InstructionId::Unknown(250)),
+            InstructionId::from_client_identifier("250")
+        );
+        assert_eq!(None, InstructionId::from_client_identifier("256"));
+        assert_eq!(None, InstructionId::from_client_identifier("4a"));
     }
 
     #[test]
@@ -657,9 +959,9 @@ mod tests {
         let ip2 = InstructionPosition::new(10, 11, 12).unwrap();
 
         ip1.write_all(&mut buf, false).unwrap();
-        buf.write_all(&[b'_']).unwrap();
+        buf.write_all(b"_").unwrap();
         ip2.write_all(&mut buf, false).unwrap();
-        buf.write_all(&[b'_']).unwrap();
+        buf.write_all(b"_").unwrap();
         ip2.write_all(&mut buf, true).unwrap();
 
         assert_eq!(
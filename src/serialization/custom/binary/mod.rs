@@ -0,0 +1,292 @@
+//! Serializer and deserializer for the compact binary format.
+//!
+//! It's only available to serialize from [Internal format](crate::formats::internal)
+//! and deserialize into [Internal format](crate::formats::internal).
+//!
+//! # Format
+//!
+//! * the header: the [magic](MAGIC), the format [version](VERSION) and the number of encoded
+//!   cells (`u16`, little-endian). The cells after the encoded ones are empty
+//! * the encoded cells, every record is either:
+//!   * a run of empty cells: [`EMPTY_RUN`] and the number of cells (`u16`, little-endian)
+//!   * an instruction: the id value and the literals of its data. Label, variable and string
+//!     literals are the length in bytes and the UTF-8 chars, variable values are `i32`,
+//!     little-endian
+//!   * the [unknown](InstructionId::Unknown) id with the value of [`EMPTY_RUN`]: a run of `0`
+//!     cells
+//!
+//! [Unknown](InstructionId::Unknown) ids are kept.
+//!
+//! Available submodules:
+//! * [share] - share codes: the binary format in URL-safe base64 with a checksum
+
+pub mod share;
+
+use std::{error::Error, fmt, io, str, str::FromStr};
+
+use crate::formats::internal::literals::{
+    LabelIdentifierLiteral, Literal, StringLiteral, VariableIdentifierLiteral, VariableValueLiteral,
+};
+use crate::formats::internal::{
+    Instruction, InstructionData, InstructionId, InstructionKind, Program,
+};
+
+/// The first bytes of the binary format.
+pub const MAGIC: [u8; 3] = *b"M3C";
+/// The version of the binary format.
+pub const VERSION: u8 = 1;
+/// The first byte of a run of empty cells.
+pub const EMPTY_RUN: u8 = 0xFF;
+
+// region: errors
+
+/// An error returned when deserializing the binary format.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DeserializeError {
+    /// The data doesn't start with the [magic](MAGIC).
+    NoMagic,
+    /// The format version isn't supported. Contains the version.
+    UnsupportedVersion(u8),
+    /// The data ends in the middle of the header or a record.
+    UnexpectedEnd,
+    /// The literal at the given offset is illegal.
+    IllegalLiteral(usize),
+    /// The records encode more cells than the header or a program has.
+    TooManyCells,
+    /// There are bytes after the last record. Contains offset of the first one.
+    TrailingBytes(usize),
+}
+
+impl fmt::Display for DeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoMagic => write!(f, "the magic was not found"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported format version {}", version)
+            }
+            Self::UnexpectedEnd => write!(f, "unexpected end of data"),
+            Self::IllegalLiteral(offset) => write!(f, "illegal literal at offset {}", offset),
+            Self::TooManyCells => write!(f, "too many cells are encoded"),
+            Self::TrailingBytes(offset) => write!(f, "trailing bytes at offset {}", offset),
+        }
+    }
+}
+
+impl Error for DeserializeError {}
+
+// endregion: errors
+
+/// Serializer for the binary format.
+///
+/// # Examples
+///
+/// ```
+/// use m3c::formats::internal::{Instruction, InstructionId, Program};
+/// use m3c::serialization::custom::binary::{Deserializer, Serializer};
+///
+/// let mut program = Program::default();
+/// program[0] = Instruction::new_simple(InstructionId::MoveW).unwrap();
+/// program[100] = Instruction::new_simple(InstructionId::End).unwrap();
+///
+/// let bytes = Serializer::new(&program).to_bytes();
+/// assert_eq!(b"M3C\x01\x65\x00\x04\xff\x63\x00\x03", &bytes[..]);
+///
+/// let mut deserialized = Program::default();
+/// Deserializer::new(&bytes).deserialize(&mut deserialized).unwrap();
+/// assert_eq!(program, deserialized);
+/// ```
+#[derive(Debug)]
+pub struct Serializer<'p> {
+    program: &'p Program,
+}
+
+impl<'p> Serializer<'p> {
+    /// Creates a serializer of the `program`.
+    pub fn new(program: &'p Program) -> Self {
+        Self { program }
+    }
+    /// Returns the serialized program.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = vec![];
+        self.serialize(&mut buf)
+            .expect("writing to Vec never fails");
+        buf
+    }
+    /// Writes the serialized program to the `writer`.
+    ///
+    /// # Errors
+    ///
+    /// See the [`write_all`]'s `Errors` sections.
+    ///
+    /// [`write_all`]: io::Write::write_all
+    pub fn serialize<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        let cells = (0..Program::INSTRUCTIONS_PER_PROGRAM)
+            .rposition(|i| self.program[i].id() != InstructionId::Empty)
+            .map_or(0, |i| i + 1);
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        writer.write_all(&(cells as u16).to_le_bytes())?;
+
+        let mut i = 0;
+        while i < cells {
+            let instruction = self.program[i];
+            if instruction.id() == InstructionId::Empty {
+                let run = (i..cells)
+                    .take_while(|&j| self.program[j].id() == InstructionId::Empty)
+                    .count();
+                writer.write_all(&[EMPTY_RUN])?;
+                writer.write_all(&(run as u16).to_le_bytes())?;
+                i += run;
+                continue;
+            }
+            if instruction.id() == InstructionId::Unknown(EMPTY_RUN) {
+                // escaped as a run of `0` cells
+                writer.write_all(&[EMPTY_RUN, 0, 0])?;
+                i += 1;
+                continue;
+            }
+            writer.write_all(&[instruction.id().value()])?;
+            match instruction.data() {
+                InstructionData::Simple => {}
+                InstructionData::Label(label) => write_chars(writer, label.as_str())?,
+                InstructionData::VarCmp((variable, value)) => {
+                    write_chars(writer, variable.as_str())?;
+                    writer.write_all(&value.data().to_le_bytes())?;
+                }
+                InstructionData::String(string) => write_chars(writer, string.as_str())?,
+            }
+            i += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Writes the length in bytes and the UTF-8 chars of a literal.
+fn write_chars<W>(writer: &mut W, s: &str) -> io::Result<()>
+where
+    W: io::Write,
+{
+    writer.write_all(&[s.len() as u8])?;
+    writer.write_all(s.as_bytes())
+}
+
+/// Deserializer for the binary format.
+///
+/// See [`Serializer`] for an example.
+#[derive(Debug)]
+pub struct Deserializer<'b> {
+    bytes: &'b [u8],
+    // offset of the next byte to read
+    offset: usize,
+}
+
+impl<'b> Deserializer<'b> {
+    /// Creates a deserializer of the `bytes`.
+    pub fn new(bytes: &'b [u8]) -> Self {
+        Self { bytes, offset: 0 }
+    }
+    /// Deserializes the bytes into the `program`.
+    ///
+    /// # Errors
+    ///
+    /// If the bytes aren't a valid program, the [`DeserializeError`] will be returned and the
+    /// `program` won't be changed.
+    pub fn deserialize(&mut self, program: &mut Program) -> Result<(), DeserializeError> {
+        self.offset = 0;
+        if self.take(MAGIC.len()).ok() != Some(&MAGIC[..]) {
+            return Err(DeserializeError::NoMagic);
+        }
+        let version = self.byte()?;
+        if version != VERSION {
+            return Err(DeserializeError::UnsupportedVersion(version));
+        }
+        let cells = self.u16()? as usize;
+        if cells > Program::INSTRUCTIONS_PER_PROGRAM {
+            return Err(DeserializeError::TooManyCells);
+        }
+
+        let mut deserialized = Program::default();
+        let mut i = 0;
+        while i < cells {
+            let value = self.byte()?;
+            if value == EMPTY_RUN {
+                match self.u16()? as usize {
+                    0 => {
+                        deserialized[i] = Instruction::new_simple(InstructionId::Unknown(value))
+                            .expect("unknown ids are `Simple`");
+                        i += 1;
+                    }
+                    run => i += run,
+                }
+                if i > cells {
+                    return Err(DeserializeError::TooManyCells);
+                }
+                continue;
+            }
+            let id = InstructionId::from_value(value);
+            deserialized[i] = match id.kind() {
+                InstructionKind::Simple => Instruction::new_simple(id),
+                InstructionKind::Label => {
+                    let label = self.literal::<LabelIdentifierLiteral>()?;
+                    Instruction::new_label(id, label)
+                }
+                InstructionKind::VarCmp => {
+                    let variable = self.literal::<VariableIdentifierLiteral>()?;
+                    let offset = self.offset;
+                    let value = i32::from_le_bytes(self.array()?);
+                    let value = VariableValueLiteral::new_from_value(value)
+                        .map_err(|_| DeserializeError::IllegalLiteral(offset))?;
+                    Instruction::new_var_cmp(id, variable, value)
+                }
+                InstructionKind::String => {
+                    let string = self.literal::<StringLiteral>()?;
+                    Instruction::new_string(id, string)
+                }
+            }
+            .expect("the data matches the kind of the id");
+            i += 1;
+        }
+        if self.offset < self.bytes.len() {
+            return Err(DeserializeError::TrailingBytes(self.offset));
+        }
+        *program = deserialized;
+        Ok(())
+    }
+    /// Reads the next `len` bytes.
+    fn take(&mut self, len: usize) -> Result<&'b [u8], DeserializeError> {
+        let bytes = self
+            .bytes
+            .get(self.offset..self.offset + len)
+            .ok_or(DeserializeError::UnexpectedEnd)?;
+        self.offset += len;
+        Ok(bytes)
+    }
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], DeserializeError> {
+        Ok(self.take(N)?.try_into().expect("`take` returns `N` bytes"))
+    }
+    fn byte(&mut self) -> Result<u8, DeserializeError> {
+        Ok(self.array::<1>()?[0])
+    }
+    fn u16(&mut self) -> Result<u16, DeserializeError> {
+        Ok(u16::from_le_bytes(self.array()?))
+    }
+    /// Reads the length in bytes and the UTF-8 chars of a literal.
+    fn literal<L>(&mut self) -> Result<L, DeserializeError>
+    where
+        L: Literal + FromStr,
+    {
+        let offset = self.offset;
+        let len = self.byte()? as usize;
+        // a char takes up to 4 bytes
+        if len > 4 * L::MAX_CHAR_LEN {
+            return Err(DeserializeError::IllegalLiteral(offset));
+        }
+        str::from_utf8(self.take(len)?)
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .ok_or(DeserializeError::IllegalLiteral(offset))
+    }
+}
@@ -0,0 +1,181 @@
+//! Share codes: the [binary format](super) in URL-safe base64 with a checksum.
+//!
+//! A share code is the unpadded URL-safe base64 (`A-Z`, `a-z`, `0-9`, `-` and `_`) of the binary
+//! format followed by its CRC-32 (big-endian). It's safe to paste into chats, URLs and issue
+//! trackers.
+
+use std::{error::Error, fmt};
+
+use super::{DeserializeError, Deserializer, Serializer};
+use crate::formats::internal::Program;
+
+/// The base64 alphabet.
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+// region: errors
+
+/// An error returned when decoding a share code.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The code contains a char which isn't in the alphabet. Contains index of the char.
+    IllegalChar(usize),
+    /// The code has a length no base64 string can have, or is too short for a checksum.
+    IllegalLength,
+    /// The checksum doesn't match, the code is damaged.
+    ChecksumMismatch,
+    /// The checksum matches, but the binary format is invalid.
+    Binary(DeserializeError),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::IllegalChar(index) => write!(f, "an illegal char was found at index {}", index),
+            Self::IllegalLength => write!(f, "the code has an illegal length"),
+            Self::ChecksumMismatch => write!(f, "the checksum doesn't match"),
+            Self::Binary(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for DecodeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Binary(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<DeserializeError> for DecodeError {
+    fn from(value: DeserializeError) -> Self {
+        Self::Binary(value)
+    }
+}
+
+// endregion: errors
+
+/// Returns the share code of the `program`.
+///
+/// # Examples
+///
+/// ```
+/// use m3c::formats::internal::{Instruction, InstructionId, Program};
+/// use m3c::serialization::custom::binary::share::{decode, encode};
+///
+/// let mut program = Program::default();
+/// program[0] = Instruction::new_simple(InstructionId::MoveW).unwrap();
+///
+/// let code = encode(&program);
+/// assert_eq!("TTNDAQEABJseVRY", code);
+///
+/// let mut decoded = Program::default();
+/// decode(&code, &mut decoded).unwrap();
+/// assert_eq!(program, decoded);
+/// ```
+pub fn encode(program: &Program) -> String {
+    let mut bytes = Serializer::new(program).to_bytes();
+    let checksum = crc32(&bytes);
+    bytes.extend_from_slice(&checksum.to_be_bytes());
+    to_base64(&bytes)
+}
+
+/// Decodes the share `code` into the `program`. Leading and trailing whitespace is ignored.
+///
+/// # Errors
+///
+/// If the code is damaged or isn't a valid program, the [`DecodeError`] will be returned and the
+/// `program` won't be changed.
+pub fn decode(code: &str, program: &mut Program) -> Result<(), DecodeError> {
+    let bytes = from_base64(code.trim())?;
+    if bytes.len() < 4 {
+        return Err(DecodeError::IllegalLength);
+    }
+    let (data, checksum) = bytes.split_at(bytes.len() - 4);
+    if crc32(data).to_be_bytes() != checksum {
+        return Err(DecodeError::ChecksumMismatch);
+    }
+    Deserializer::new(data).deserialize(program)?;
+    Ok(())
+}
+
+/// Returns the unpadded URL-safe base64 of the `bytes`.
+fn to_base64(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity((bytes.len() * 4).div_ceil(3));
+    for chunk in bytes.chunks(3) {
+        let mut group = [0; 3];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let bits = u32::from_be_bytes([0, group[0], group[1], group[2]]);
+        for i in 0..=chunk.len() {
+            s.push(ALPHABET[(bits >> (18 - 6 * i) & 0x3F) as usize] as char);
+        }
+    }
+    s
+}
+
+/// Decodes the unpadded URL-safe base64.
+fn from_base64(s: &str) -> Result<Vec<u8>, DecodeError> {
+    if s.len() % 4 == 1 {
+        return Err(DecodeError::IllegalLength);
+    }
+    let mut bytes = Vec::with_capacity(s.len() * 3 / 4);
+    let mut bits = 0_u32;
+    let mut len = 0;
+    for (index, ch) in s.char_indices() {
+        let value = ALPHABET
+            .iter()
+            .position(|&c| c as char == ch)
+            .ok_or(DecodeError::IllegalChar(index))?;
+        bits = bits << 6 | value as u32;
+        len += 6;
+        if len >= 8 {
+            len -= 8;
+            bytes.push((bits >> len) as u8);
+        }
+    }
+    Ok(bytes)
+}
+
+/// Returns the CRC-32 (IEEE 802.3) of the `bytes`.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0_u32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                crc >> 1 ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{crc32, from_base64, to_base64, DecodeError};
+
+    #[test]
+    fn crc() {
+        assert_eq!(0, crc32(b""));
+        assert_eq!(0xCBF4_3926, crc32(b"123456789"));
+    }
+
+    #[test]
+    fn base64() {
+        for (bytes, s) in [
+            (&b""[..], ""),
+            (b"f", "Zg"),
+            (b"fo", "Zm8"),
+            (b"foo", "Zm9v"),
+            (b"foob", "Zm9vYg"),
+            (b"\xfb\xff", "-_8"),
+        ] {
+            assert_eq!(s, to_base64(bytes));
+            assert_eq!(Ok(bytes.to_vec()), from_base64(s));
+        }
+        assert_eq!(Err(DecodeError::IllegalLength), from_base64("Zm9vY"));
+        assert_eq!(Err(DecodeError::IllegalChar(2)), from_base64("Zm+v"));
+    }
+}
@@ -2,5 +2,7 @@
 //!
 //! Available submodules:
 //! * [assembly] - serializer and deserializer for assembly-like custom format.
+//! * [binary] - serializer and deserializer for compact binary custom format and share codes.
 
 pub mod assembly;
+pub mod binary;
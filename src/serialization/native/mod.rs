@@ -2,5 +2,8 @@
 //!
 //! Available submodules:
 //! * [new] - serializer and deserializer for New Text format.
+//!
+//! The old text format of the client isn't supported yet: there is no specification of it to
+//! implement against.
 
 pub mod new;
@@ -10,9 +10,11 @@ use std::{
     str::Chars,
 };
 
+use crate::formats::internal::registry::InstructionInfo;
 use crate::formats::internal::{
     literals::{
-        LabelIdentifierLiteral, Literal, LiteralType, StringLiteral, VariableIdentifierLiteral,
+        BoundedIdentifier, IdentifierRules, LabelIdentifierLiteral, Literal, LiteralTooLongError,
+        LiteralType, ParseLiteralError, StringLiteral, VariableIdentifierLiteral,
         VariableValueLiteral,
     },
     InstructionData, InstructionId,
@@ -20,13 +22,15 @@ use crate::formats::internal::{
 use crate::formats::internal::{
     Instruction, InstructionKind, InstructionPosition, InstructionPositionOverflowError, Program,
 };
-use crate::formats::native::new::diagnostics::{Diagnostics, NoMagicFound, UnknownToken};
+use crate::formats::native::new::diagnostics::{
+    Diagnostics, LiteralTooLong as LiteralTooLongDiagnostic, MalformedRowSkip, NoMagicFound,
+    ProgramOverflow, RowOverflow, Suggestion, UnknownToken, UnterminatedLiteral,
+    VariableValueOutOfRange,
+};
 use crate::utils::{CharPosition, EnumerateWithPosition};
 
 mod data;
-use data::{NTF2INode, NTF2I};
-
-use self::data::{I2NTFNode, I2NTF};
+use data::{ntf2i, NTF2INode};
 
 // region: errors
 
@@ -101,13 +105,36 @@ impl Error for UnknownInstruction {}
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct LiteralIsTooLong {
     literal_index: usize,
+    literal_len: usize,
 }
 
-impl LiteralIsTooLong {}
+impl LiteralIsTooLong {
+    /// Returns index of the first char of the literal.
+    pub fn index(&self) -> usize {
+        self.literal_index
+    }
+    /// Returns length of the whole literal in chars.
+    pub fn length(&self) -> usize {
+        self.literal_len
+    }
+}
 
 impl fmt::Display for LiteralIsTooLong {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "found too long literal at index {}", self.literal_index)
+        write!(
+            f,
+            "found too long literal of {} chars at index {}",
+            self.literal_len, self.literal_index
+        )
+    }
+}
+
+impl From<LiteralTooLongError> for LiteralIsTooLong {
+    fn from(error: LiteralTooLongError) -> Self {
+        Self {
+            literal_index: error.index(),
+            literal_len: error.length(),
+        }
     }
 }
 
@@ -164,12 +191,104 @@ impl From<InstructionPositionOverflowError> for DeserializeErrors {
     }
 }
 
+/// New Text format has no spelling of the instruction id: [unknown](InstructionId::Unknown) ids,
+/// [`Last`](InstructionId::Last) and [`Restart`](InstructionId::Restart).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct NoSpellingError {
+    id: InstructionId,
+    position: Option<InstructionPosition>,
+}
+
+impl NoSpellingError {
+    /// Returns the id without spelling.
+    pub fn id(&self) -> InstructionId {
+        self.id
+    }
+    /// Returns the position of the instruction in the program, `None` if only one instruction is
+    /// serialized.
+    pub fn position(&self) -> Option<InstructionPosition> {
+        self.position
+    }
+}
+
+impl fmt::Display for NoSpellingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "New Text format has no spelling of the instruction id {}",
+            self.id.value()
+        )?;
+        match self.position {
+            Some(position) => write!(f, " at {}", position.custom_format()),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Error for NoSpellingError {}
+
+/// An error returned by [`TextFormatSerializer`].
+#[derive(Debug)]
+pub enum SerializeError {
+    /// See [`NoSpellingError`].
+    NoSpelling(NoSpellingError),
+    /// Writing failed.
+    Io(io::Error),
+}
+
+impl fmt::Display for SerializeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoSpelling(e) => write!(f, "{}", e),
+            Self::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for SerializeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::NoSpelling(e) => Some(e),
+            Self::Io(e) => Some(e),
+        }
+    }
+}
+
+impl From<NoSpellingError> for SerializeError {
+    fn from(error: NoSpellingError) -> Self {
+        SerializeError::NoSpelling(error)
+    }
+}
+
+impl From<io::Error> for SerializeError {
+    fn from(error: io::Error) -> Self {
+        SerializeError::Io(error)
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 enum Command {
     OneStepForward,
     ThreeStepsForward,
     GoToNextRow,
     GoToNextPage,
+    /// Moves the given number of rows forward, as the same number of [`GoToNextRow`]s does.
+    ///
+    /// [`GoToNextRow`]: Self::GoToNextRow
+    SkipRows(u8),
+}
+
+impl Command {
+    /// Returns the row skip with the given `count` digit.
+    ///
+    /// `.` moves to the next row, the same as `\n` does. `.N.` is a short form of `N - 1` dots
+    /// (`0` stands for 10), so `\n.N.\n` skips `N` empty rows.
+    fn row_skip(count: char) -> Self {
+        match count {
+            '0' => Self::SkipRows(9),
+            _ => Self::SkipRows(count as u8 - b'1'),
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -192,11 +311,78 @@ impl From<Instruction> for Option<InstructionOrCommand> {
 
 // endregion: errors
 
+/// Position of the next instruction to place while deserializing.
+///
+/// Placing an instruction into the last column of a row leaves the cursor at the end of that row,
+/// so the following `\n` moves to the beginning of the next row instead of skipping it. `~`
+/// moves to the end of the last row of the page the same way.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+struct Cursor {
+    position: InstructionPosition,
+    // whether the cursor is at the end of the row before the `position`'s one
+    at_row_end: bool,
+}
+
+impl Cursor {
+    /// Returns position of the next instruction to place.
+    fn position(&self) -> InstructionPosition {
+        self.position
+    }
+    /// Moves the cursor past the just placed instruction.
+    ///
+    /// # Errors
+    ///
+    /// If the instruction is placed into the last position in the program, an
+    /// [`InstructionPositionOverflowError`] will be returned.
+    fn move_past_instruction(&mut self) -> Result<(), InstructionPositionOverflowError> {
+        self.at_row_end = self.position.column() as usize == Program::INSTRUCTIONS_PER_ROW - 1;
+        self.position.move_forward()
+    }
+    /// Moves the cursor as the given `command` says.
+    ///
+    /// # Errors
+    ///
+    /// See the `Errors` section of the appropriate `move_*` method of [`InstructionPosition`].
+    fn apply_command(&mut self, command: Command) -> Result<(), InstructionPositionOverflowError> {
+        match command {
+            Command::OneStepForward => {
+                self.at_row_end = false;
+                self.position.move_forward()
+            }
+            Command::ThreeStepsForward => {
+                self.at_row_end = false;
+                self.position.move_three_steps_forward()
+            }
+            Command::GoToNextRow => self.skip_rows(1),
+            Command::SkipRows(n) => self.skip_rows(n),
+            Command::GoToNextPage => {
+                if self.at_row_end && self.position.row() == 0 {
+                    // already at the end of the last row of the previous page
+                    return Ok(());
+                }
+                self.position.move_to_next_page()?;
+                self.at_row_end = true;
+                Ok(())
+            }
+        }
+    }
+    /// Moves `n` rows forward.
+    fn skip_rows(&mut self, n: u8) -> Result<(), InstructionPositionOverflowError> {
+        for _ in 0..n {
+            if !std::mem::take(&mut self.at_row_end) {
+                self.position.move_to_next_row()?;
+            }
+        }
+        Ok(())
+    }
+}
+
 /// A structure that deserializes New Text format into [Internal format](crate::formats::internal).
 #[derive(Debug)]
 pub struct TextFormatDeserializer<'p, 'e> {
     enumeration: Enumerate<Chars<'e>>,
-    position: InstructionPosition,
+    // value `None` stands for the program is already full
+    cursor: Option<Cursor>,
     program: &'p mut Program,
     index: usize,
 }
@@ -206,7 +392,7 @@ impl<'p, 'e> TextFormatDeserializer<'p, 'e> {
     pub fn new_from_str(program: &'p mut Program, s: &'e str) -> Self {
         Self {
             enumeration: s.chars().enumerate(),
-            position: InstructionPosition::default(),
+            cursor: Some(Cursor::default()),
             program,
             index: 0,
         }
@@ -218,28 +404,23 @@ impl<'p, 'e> TextFormatDeserializer<'p, 'e> {
             match self.parse_next()? {
                 None => break Ok(()),
                 Some(InstructionOrCommand::Instruction(instruction)) => {
-                    self.program[self.position] = instruction;
-                    self.position.move_forward()?;
+                    let cursor = self
+                        .cursor
+                        .as_mut()
+                        .ok_or(InstructionPositionOverflowError {})?;
+                    self.program[cursor.position()] = instruction;
+                    if cursor.move_past_instruction().is_err() {
+                        self.cursor = None;
+                    }
+                    continue;
+                }
+                Some(InstructionOrCommand::Command(command)) => {
+                    self.cursor
+                        .as_mut()
+                        .ok_or(InstructionPositionOverflowError {})?
+                        .apply_command(command)?;
                     continue;
                 }
-                Some(InstructionOrCommand::Command(command)) => match command {
-                    Command::OneStepForward => {
-                        self.position.move_forward()?;
-                        continue;
-                    }
-                    Command::ThreeStepsForward => {
-                        self.position.move_three_steps_forward()?;
-                        continue;
-                    }
-                    Command::GoToNextRow => {
-                        self.position.move_to_next_row()?;
-                        continue;
-                    }
-                    Command::GoToNextPage => {
-                        self.position.move_to_next_page()?;
-                        continue;
-                    }
-                },
             }
         }
     }
@@ -257,12 +438,15 @@ impl<'p, 'e> TextFormatDeserializer<'p, 'e> {
                 let mut value: Option<VariableValueLiteral> = None;
                 let mut next_char: Option<char> = None;
 
-                match &NTF2I.binary_search_by_key(&first_char, |&(ch, _)| ch) {
-                    Err(_) => return Err(UnknownInstruction { index: self.index }.into()),
+                match &ntf2i().binary_search_by_key(&first_char, |&(ch, _)| ch) {
+                    Err(_) => Err(UnknownInstruction { index: self.index }.into()),
                     Ok(x) => {
-                        let mut the_node = &NTF2I[*x].1;
+                        let mut the_node = &ntf2i()[*x].1;
                         loop {
                             match the_node {
+                                NTF2INode::Command(Command::SkipRows(_)) => {
+                                    return Ok(self.read_row_skip()?.into())
+                                }
                                 NTF2INode::Command(command) => {
                                     return Ok(Some(InstructionOrCommand::Command(*command)))
                                 }
@@ -290,13 +474,14 @@ impl<'p, 'e> TextFormatDeserializer<'p, 'e> {
                                         .into()),
                                     }
                                 }
-                                NTF2INode::Literal((literal, node)) => {
+                                NTF2INode::Literal(literal, node) => {
                                     match literal {
                                         LiteralType::LabelIdentifierLiteral => {
                                             let (literal, next_char_) =
                                                 LabelIdentifierLiteral::new_from_enumerate(
                                                     &mut self.enumeration,
-                                                );
+                                                )
+                                                .map_err(|e| self.literal_error(e))?;
                                             label = Some(literal);
                                             match next_char_ {
                                                 None => {}
@@ -309,7 +494,8 @@ impl<'p, 'e> TextFormatDeserializer<'p, 'e> {
                                             let (literal, next_char_) =
                                                 StringLiteral::new_from_enumerate(
                                                     &mut self.enumeration,
-                                                );
+                                                )
+                                                .map_err(|e| self.literal_error(e))?;
                                             string = Some(literal);
                                             match next_char_ {
                                                 None => {}
@@ -322,7 +508,8 @@ impl<'p, 'e> TextFormatDeserializer<'p, 'e> {
                                             let (literal, next_char_) =
                                                 VariableIdentifierLiteral::new_from_enumerate(
                                                     &mut self.enumeration,
-                                                );
+                                                )
+                                                .map_err(|e| self.literal_error(e))?;
                                             name = Some(literal);
                                             match next_char_ {
                                                 None => {}
@@ -335,7 +522,8 @@ impl<'p, 'e> TextFormatDeserializer<'p, 'e> {
                                             let (literal, next_char_) =
                                                 VariableValueLiteral::new_from_enumerate(
                                                     &mut self.enumeration,
-                                                );
+                                                )
+                                                .map_err(|e| self.literal_error(e))?;
                                             value = Some(literal);
                                             match next_char_ {
                                                 None => {}
@@ -345,7 +533,7 @@ impl<'p, 'e> TextFormatDeserializer<'p, 'e> {
                                             }
                                         }
                                     }
-                                    the_node = &node[0]; // in this case only one node can be
+                                    the_node = node;
                                     continue;
                                 }
                                 NTF2INode::Chars(current) => {
@@ -385,6 +573,19 @@ impl<'p, 'e> TextFormatDeserializer<'p, 'e> {
             },
         }
     }
+    /// Reads the optional count of a row skip (`.N.`). The opening `.` is already read.
+    fn read_row_skip(&mut self) -> Result<Command, UnknownInstruction> {
+        match self.enumeration.clone().next() {
+            Some((_, count)) if count.is_ascii_digit() => {
+                self.enumeration.next();
+                match self.enumeration.next() {
+                    Some((_, '.')) => Ok(Command::row_skip(count)),
+                    _ => Err(UnknownInstruction { index: self.index }),
+                }
+            }
+            _ => Ok(Command::SkipRows(1)),
+        }
+    }
     fn get_next_char(&mut self) -> Result<char, UnknownInstruction> {
         let res = self.enumeration.next();
         match res {
@@ -392,8 +593,42 @@ impl<'p, 'e> TextFormatDeserializer<'p, 'e> {
             Some((_, ch)) => Ok(ch),
         }
     }
+    /// Converts an error of a literal of the current instruction. An illegal escape is reported
+    /// as an unknown instruction.
+    fn literal_error(&self, error: ParseLiteralError) -> ParseNextErrors {
+        match error {
+            ParseLiteralError::TooLong(e) => LiteralIsTooLong::from(e).into(),
+            _ => UnknownInstruction { index: self.index }.into(),
+        }
+    }
 }
 
+/// Maps an instruction token in the source to the position of its instruction in the program.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TokenSpan {
+    /// Position of the first char of the token.
+    pub start: CharPosition,
+    /// Position of the last char of the token.
+    pub end: CharPosition,
+    /// Position of the instruction in the program.
+    pub position: InstructionPosition,
+}
+
+/// A best-effort result of [`TextFormatDeserializerV2::deserialize_partial`].
+#[derive(Debug)]
+pub struct PartialProgram {
+    /// The program with all the instructions that could be parsed.
+    pub program: Program,
+    /// All the diagnostics in order of appearance.
+    pub diagnostics: Vec<Diagnostics>,
+    /// A [`TokenSpan`] for every instruction placed into the program, in order of appearance.
+    pub spans: Vec<TokenSpan>,
+}
+
+/// An error-tolerant deserializer for New Text format.
+///
+/// Never stops on an illegal input. Illegal chars are reported and skipped up to the next char a
+/// token can start with, so the rest of instructions are still placed at their positions.
 pub struct TextFormatDeserializerV2<'s> {
     // original source
     source: &'s str,
@@ -406,8 +641,10 @@ pub struct TextFormatDeserializerV2<'s> {
     // tracks the start (`.0`) and the end (`.1`) of the illegal char sequence. Value `None` stands
     // for no sequence detected
     illegal_chars: Option<(CharPosition, CharPosition)>,
-    // instruction position at program
-    ins_pos: InstructionPosition,
+    // position of the next instruction. Value `None` stands for the program is already full
+    cursor: Option<Cursor>,
+    // whether the `ProgramOverflow` diagnostic was already reported
+    overflow_reported: bool,
     // diagnostics
     diagnostics: Vec<Diagnostics>,
 }
@@ -424,11 +661,32 @@ impl<'s> TextFormatDeserializerV2<'s> {
             last_char: CharPosition::default(),
             // NOTE: every time updates before read in `parse_next_token`
             illegal_chars: None,
-            ins_pos: InstructionPosition::default(),
+            cursor: Some(Cursor::default()),
+            overflow_reported: false,
             diagnostics: vec![],
         }
     }
+    /// Deserializes the source into the `program` and returns all the diagnostics.
     pub fn deserialize(&mut self, program: &mut Program) -> Vec<Diagnostics> {
+        self.deserialize_with_spans(program, &mut vec![])
+    }
+    /// Deserializes the source and returns the best-effort program, all the diagnostics and a
+    /// [`TokenSpan`] for every instruction placed into the program.
+    pub fn deserialize_partial(&mut self) -> PartialProgram {
+        let mut program = Program::default();
+        let mut spans = vec![];
+        let diagnostics = self.deserialize_with_spans(&mut program, &mut spans);
+        PartialProgram {
+            program,
+            diagnostics,
+            spans,
+        }
+    }
+    fn deserialize_with_spans(
+        &mut self,
+        program: &mut Program,
+        spans: &mut Vec<TokenSpan>,
+    ) -> Vec<Diagnostics> {
         // resets
         self.reset();
         program.reset();
@@ -436,24 +694,47 @@ impl<'s> TextFormatDeserializerV2<'s> {
         self.check_magic();
 
         loop {
-            match self.parse_next_token() {
+            let token = self.parse_next_token();
+            let (start, end) = (self.token_start.0, self.last_char);
+            match token {
                 None => {
                     return std::mem::take(&mut self.diagnostics);
                 }
-                Some(InstructionOrCommand::Instruction(ins)) => {
-                    program[self.ins_pos] = ins;
-                    match self.ins_pos.move_forward() {
-                        Ok(_) => {
-                            continue;
+                Some(InstructionOrCommand::Instruction(ins)) => match &mut self.cursor {
+                    Some(cursor) => {
+                        program[cursor.position()] = ins;
+                        spans.push(TokenSpan {
+                            start,
+                            end,
+                            position: cursor.position(),
+                        });
+                        if cursor.move_past_instruction().is_err() {
+                            self.cursor = None;
                         }
-                        Err(_) => {
-                            // TODO add to `self.diagnostics`
-                            return std::mem::take(&mut self.diagnostics);
+                    }
+                    None => {
+                        if !self.overflow_reported {
+                            self.overflow_reported = true;
+                            self.diagnostics
+                                .push(ProgramOverflow::new(start, end).into());
                         }
                     }
-                }
-                Some(InstructionOrCommand::Command(_)) => {
-                    todo!()
+                },
+                Some(InstructionOrCommand::Command(command)) => {
+                    let Some(cursor) = &mut self.cursor else {
+                        continue;
+                    };
+                    if cursor.apply_command(command).is_ok() {
+                        continue;
+                    }
+                    if command == Command::ThreeStepsForward {
+                        // the rest of the row is skipped anyway
+                        self.diagnostics.push(RowOverflow::new(start, end).into());
+                        if cursor.apply_command(Command::GoToNextRow).is_ok() {
+                            continue;
+                        }
+                    }
+                    self.cursor = None;
                 }
             }
         }
@@ -466,7 +747,8 @@ impl<'s> TextFormatDeserializerV2<'s> {
     /// + `illegal_chars`
     fn reset(&mut self) {
         self.source_iter = EnumerateWithPosition::new(self.source).peekable();
-        self.ins_pos = InstructionPosition::default();
+        self.cursor = Some(Cursor::default());
+        self.overflow_reported = false;
         // TODO: what about diagnostic?
     }
     /// Peeks one char from `source_iter`. If it's a valid magic advances the iterator. If not
@@ -485,18 +767,20 @@ impl<'s> TextFormatDeserializerV2<'s> {
     ///
     /// Consumes `self.source_iter` until whole token is read or the end of the iterator is
     /// reached. If there is an illegal char sequence before the token or the iterator's end, add
-    /// `UnknownToken` diagnostic.
+    /// `UnknownToken` diagnostic. If a token with literal(s) isn't finished, add
+    /// `UnterminatedLiteral` diagnostic.
     fn parse_next_token(&mut self) -> Option<InstructionOrCommand> {
         self.illegal_chars = None;
 
         // Here it reads the char for the really *first* time. So `self.illegal_chars` is `None`.
         // So no need to call `self.next_char`
         self.token_start = self.source_iter.next()?;
-        self.last_char = self.token_start.0;
 
         // loop to find first valid char to start token with
         loop {
-            match &NTF2I.binary_search_by_key(&self.token_start.1, |&(ch, _)| ch) {
+            self.last_char = self.token_start.0;
+
+            match &ntf2i().binary_search_by_key(&self.token_start.1, |&(ch, _)| ch) {
                 // that isn't a valid char to start token with. It needs to update
                 // `self.illegal_chars` and set up the new value to the `self.token_start`
                 Err(_) => {
@@ -509,47 +793,113 @@ impl<'s> TextFormatDeserializerV2<'s> {
                     }
 
                     // set up the next char to `self.token_start` and run iteration
-                    self.token_start = self.next_char()?;
+                    self.token_start = self.next_char(false)?;
                     continue;
                 }
 
                 // That's a valid char to start token with
                 Ok(i) => {
-                    let mut node = &NTF2I[*i].1;
+                    let mut node = &ntf2i()[*i].1;
+
+                    // "registers" for literals read so far
+                    let mut label: Option<LabelIdentifierLiteral> = None;
+                    let mut string: Option<StringLiteral> = None;
+                    let mut name: Option<VariableIdentifierLiteral> = None;
+                    let mut value: Option<VariableValueLiteral> = None;
+                    // a token with literal(s) read is reported as unterminated, not as unknown
+                    let mut has_literal = false;
 
                     // loop over nodes util full token will be read
                     loop {
                         match node {
                             NTF2INode::Command(command) => {
-                                if let Some((start, end)) = self.illegal_chars {
-                                    self.diagnostics.push(UnknownToken::new(start, end).into())
-                                }
+                                self.flush_illegal_chars();
 
+                                if let Command::SkipRows(_) = command {
+                                    return Some(self.read_row_skip());
+                                }
                                 return Some(InstructionOrCommand::Command(*command));
                             }
                             NTF2INode::Id(id) => {
-                                if let Some((start, end)) = self.illegal_chars {
-                                    self.diagnostics.push(UnknownToken::new(start, end).into())
-                                }
+                                self.flush_illegal_chars();
 
                                 return match id.kind() {
                                     InstructionKind::Simple => {
                                         Instruction::new_simple(*id).unwrap().into()
                                     }
-                                    _ => todo!(),
+                                    InstructionKind::Label => {
+                                        Instruction::new_label(*id, label.unwrap()).unwrap().into()
+                                    }
+                                    InstructionKind::String => {
+                                        Instruction::new_string(*id, string.unwrap())
+                                            .unwrap()
+                                            .into()
+                                    }
+                                    InstructionKind::VarCmp => {
+                                        Instruction::new_var_cmp(*id, name.unwrap(), value.unwrap())
+                                            .unwrap()
+                                            .into()
+                                    }
                                 };
                             }
-                            NTF2INode::Literal(_) => {
-                                todo!()
+                            NTF2INode::Literal(literal_type, next) => {
+                                if !has_literal {
+                                    // keep diagnostics in order of appearance
+                                    self.flush_illegal_chars();
+                                    has_literal = true;
+                                }
+                                match literal_type {
+                                    LiteralType::LabelIdentifierLiteral => {
+                                        label = Some(self.read_identifier_literal());
+                                    }
+                                    LiteralType::StringLiteral => {
+                                        string = Some(self.read_identifier_literal());
+                                    }
+                                    LiteralType::VariableIdentifierLiteral => {
+                                        name = Some(self.read_identifier_literal());
+                                    }
+                                    LiteralType::VariableValueLiteral => {
+                                        value = Some(self.read_value_literal());
+                                    }
+                                }
+                                node = next;
+                                continue;
                             }
                             NTF2INode::Chars(current) => {
-                                let next_char = self.next_char()?;
+                                let next_char = self.next_char(has_literal)?;
                                 match current.binary_search_by_key(&next_char.1, |&(ch, _)| ch) {
                                     // Means we have unknown char at the middle of token (second or further char)
                                     Err(_) => {
-                                        // here `end` updated by `self.last_char` because `next_char` can be
-                                        // a valid char to start token with
-                                        if let Some((_, end)) = &mut self.illegal_chars {
+                                        if has_literal {
+                                            self.diagnostics.push(
+                                                UnterminatedLiteral::new(
+                                                    self.token_start.0,
+                                                    self.last_char,
+                                                )
+                                                .into(),
+                                            );
+                                        } else if let Some(replacement) =
+                                            Self::fix_letter_case(current, next_char.1)
+                                        {
+                                            // a typo like `^w`. `next_char` is still parsed on
+                                            // its own, but it's clear what was meant
+                                            self.flush_illegal_chars();
+                                            let suggestion = Suggestion::new(
+                                                self.token_start.0,
+                                                next_char.0,
+                                                replacement,
+                                            );
+                                            self.diagnostics.push(
+                                                UnknownToken::new_with_suggestion(
+                                                    self.token_start.0,
+                                                    self.last_char,
+                                                    suggestion,
+                                                )
+                                                .into(),
+                                            );
+                                        } else if let Some((_, end)) = &mut self.illegal_chars {
+                                            // here `end` updated by `self.last_char` because
+                                            // `next_char` can be a valid char to start token with
                                             *end = self.last_char;
                                         } else {
                                             self.illegal_chars =
@@ -573,14 +923,164 @@ impl<'s> TextFormatDeserializerV2<'s> {
             }
         }
     }
+    /// Reads an identifier-like literal.
+    ///
+    /// Consumes `self.source_iter` while the next char is allowed by the rules `R` (chars which
+    /// [need escape](IdentifierRules::needs_escape) have to be escaped with `\`), updating
+    /// `self.last_char`. The first char that doesn't belong to the literal isn't consumed. Only the
+    /// first [`MAX_CHAR_LEN`] chars are kept, the rest are ignored and `LiteralTooLong` diagnostic
+    /// is added.
+    ///
+    /// [`MAX_CHAR_LEN`]: IdentifierRules::MAX_CHAR_LEN
+    fn read_identifier_literal<R, const N: usize>(&mut self) -> BoundedIdentifier<R, N>
+    where
+        R: IdentifierRules,
+    {
+        let mut s = String::new();
+        let mut len = 0;
+        let mut start = None;
+        loop {
+            let escape = self
+                .source_iter
+                .next_if(|&(_, ch)| ch == '\\' && R::needs_escape(ch));
+            let next = match escape {
+                Some((pos, _)) => {
+                    start.get_or_insert(pos);
+                    self.last_char = pos;
+                    self.source_iter.next_if(|&(_, ch)| R::is_allowed(ch))
+                }
+                None => self
+                    .source_iter
+                    .next_if(|&(_, ch)| R::is_allowed(ch) && !R::needs_escape(ch)),
+            };
+            let Some((pos, ch)) = next else {
+                break;
+            };
+            if len < R::MAX_CHAR_LEN {
+                s.push(ch);
+            }
+            len += 1;
+            start.get_or_insert(pos);
+            self.last_char = pos;
+        }
+        if let Some(start) = start.filter(|_| len > R::MAX_CHAR_LEN) {
+            self.diagnostics
+                .push(LiteralTooLongDiagnostic::new(start, self.last_char).into());
+        }
+        s.parse().unwrap()
+    }
+    /// Reads a variable value literal (matches the regex `-?[0-9]*`).
+    ///
+    /// Consumes `self.source_iter` the same way as [`read_identifier_literal`] does. Only the first
+    /// [`MAX_CHAR_LEN`] chars (including the minus sign) are kept, so the returned value is always
+    /// valid. If the whole literal is out of range, adds `VariableValueOutOfRange` diagnostic,
+    /// otherwise if it's just too long (e.g. has leading zeros), adds `LiteralTooLong` one.
+    ///
+    /// [`read_identifier_literal`]: Self::read_identifier_literal
+    /// [`MAX_CHAR_LEN`]: VariableValueLiteral::MAX_CHAR_LEN
+    fn read_value_literal(&mut self) -> VariableValueLiteral {
+        let mut is_positive = true;
+        let mut value = 0;
+        let mut whole_value: i64 = 0;
+        let mut len = 0;
+        let mut start = None;
+        if let Some((pos, _)) = self.source_iter.next_if(|(_, ch)| *ch == '-') {
+            is_positive = false;
+            len += 1;
+            start = Some(pos);
+            self.last_char = pos;
+        }
+        while let Some((pos, ch)) = self.source_iter.next_if(|(_, ch)| ch.is_ascii_digit()) {
+            let digit = ch as i32 - '0' as i32;
+            if len < VariableValueLiteral::MAX_CHAR_LEN {
+                value = value * 10 + digit;
+            }
+            whole_value = whole_value
+                .saturating_mul(10)
+                .saturating_add(i64::from(digit));
+            len += 1;
+            start.get_or_insert(pos);
+            self.last_char = pos;
+        }
+        if let Some(start) = start {
+            let whole_value = if is_positive {
+                whole_value
+            } else {
+                -whole_value
+            };
+            if i32::try_from(whole_value)
+                .map(VariableValueLiteral::new_from_value)
+                .map_or(true, |value| value.is_err())
+            {
+                self.diagnostics
+                    .push(VariableValueOutOfRange::new(start, self.last_char).into());
+            } else if len > VariableValueLiteral::MAX_CHAR_LEN {
+                self.diagnostics
+                    .push(LiteralTooLongDiagnostic::new(start, self.last_char).into());
+            }
+        }
+        VariableValueLiteral::new_from_value(if is_positive { value } else { -value }).unwrap()
+    }
+    /// Reads the optional count of a row skip (`.N.`). The opening `.` is already read.
+    ///
+    /// If the count isn't followed by `.`, adds `MalformedRowSkip` diagnostic and ignores the whole
+    /// row skip. The char after the count isn't consumed then.
+    fn read_row_skip(&mut self) -> InstructionOrCommand {
+        let Some((pos, count)) = self.source_iter.next_if(|(_, ch)| ch.is_ascii_digit()) else {
+            return InstructionOrCommand::Command(Command::SkipRows(1));
+        };
+        self.last_char = pos;
+        match self.source_iter.next_if(|(_, ch)| *ch == '.') {
+            Some((pos, _)) => {
+                self.last_char = pos;
+                InstructionOrCommand::Command(Command::row_skip(count))
+            }
+            None => {
+                self.diagnostics
+                    .push(MalformedRowSkip::new(self.token_start.0, self.last_char).into());
+                // moves nowhere
+                InstructionOrCommand::Command(Command::SkipRows(0))
+            }
+        }
+    }
+    /// Checks whether `ch` with the opposite letter case finishes the token.
+    ///
+    /// `current` is the node the mismatched `ch` was looked up at. Returns the whole fixed token
+    /// if it's a simple instruction without literals (e.g. `^W` for `^w`).
+    fn fix_letter_case(current: &[(char, NTF2INode)], ch: char) -> Option<&'static str> {
+        let fixed = if ch.is_ascii_lowercase() {
+            ch.to_ascii_uppercase()
+        } else {
+            ch.to_ascii_lowercase()
+        };
+        let i = current.binary_search_by_key(&fixed, |&(ch, _)| ch).ok()?;
+        let NTF2INode::Id(id) = current[i].1 else {
+            return None;
+        };
+        match id.info()?.ntf() {
+            &[chars] => Some(chars),
+            _ => None,
+        }
+    }
+    /// Pushes `UnknownToken` diagnostic for the tracked illegal char sequence (if any) and stops
+    /// tracking it.
+    fn flush_illegal_chars(&mut self) {
+        if let Some((start, end)) = self.illegal_chars.take() {
+            self.diagnostics.push(UnknownToken::new(start, end).into())
+        }
+    }
     /// Just wrapper around `self.source_iter.next()`.
     ///
-    /// If the result of `self.source_iter.next()` is `None` pushes `UnknownToken` diagnostic.
-    fn next_char(&mut self) -> Option<(CharPosition, char)> {
+    /// If the result of `self.source_iter.next()` is `None` pushes `UnknownToken` diagnostic (or
+    /// `UnterminatedLiteral` one if the token `has_literal`).
+    fn next_char(&mut self, has_literal: bool) -> Option<(CharPosition, char)> {
         match self.source_iter.next() {
             Some(x) => Some(x),
             None => {
-                if let Some((start, _)) = self.illegal_chars {
+                if has_literal {
+                    self.diagnostics
+                        .push(UnterminatedLiteral::new(self.token_start.0, self.last_char).into())
+                } else if let Some((start, _)) = self.illegal_chars {
                     self.diagnostics
                         .push(UnknownToken::new(start, self.last_char).into())
                 } else {
@@ -594,6 +1094,10 @@ impl<'s> TextFormatDeserializerV2<'s> {
 }
 
 /// A structure that serializes New Text format from [Internal format](crate::formats::internal).
+///
+/// New Text format has no spelling of [unknown](InstructionId::Unknown) ids and of
+/// [`Last`](InstructionId::Last) and [`Restart`](InstructionId::Restart), programs with them
+/// can't be serialized (see [`NoSpellingError`]).
 #[derive(Debug, Clone, Copy)]
 pub struct TextFormatSerializer<'p> {
     program: &'p Program,
@@ -602,16 +1106,18 @@ pub struct TextFormatSerializer<'p> {
 impl<'p> TextFormatSerializer<'p> {
     /// Creates a new [`TextFormatSerializer`].
     pub fn new(program: &'p Program) -> Self {
-        Self { program: program }
+        Self { program }
     }
     /// Serializes program from [Internal format](crate::formats::internal).
     ///
-    /// It is guaranteed that all bytes written to the given `writer` are valid ASCII characters.
+    /// It is guaranteed that all bytes written to the given `writer` are valid UTF-8, non-ASCII
+    /// chars come only from string literals.
     ///
     /// # Errors
     ///
-    /// As this function internally uses the [`io::Write::write_all`] method, see the error section
-    /// of this method.
+    /// If the program has an instruction without spelling, the [`NoSpellingError`] with the
+    /// position of the first one will be returned and nothing will be written. Errors of the
+    /// [`io::Write::write_all`] method are returned as [`SerializeError::Io`].
     ///
     /// # Examples
     ///
@@ -633,69 +1139,121 @@ impl<'p> TextFormatSerializer<'p> {
     ///
     /// assert_eq!(b"$^W^S", &buf[..])
     /// ```
-    pub fn serialize<W>(&mut self, writer: &mut W) -> io::Result<()>
+    pub fn serialize<W>(&mut self, writer: &mut W) -> Result<(), SerializeError>
     where
         W: io::Write,
     {
+        if let Some((position, ins)) = self
+            .program
+            .instruction_positions()
+            .find(|(_, ins)| Self::spelling(ins.id()).is_none())
+        {
+            return Err(NoSpellingError {
+                id: ins.id(),
+                position: Some(position),
+            }
+            .into());
+        }
+
         // write magic
         writer.write_all(b"$")?;
 
-        // Here it isn't necessary to check if the first instruction is really not `Empty`,
-        // because this is the beginning of the program
-        let mut last_not_empty = InstructionPosition::default();
+        let mut last_not_empty: Option<InstructionPosition> = None;
 
         for (pos, ins) in self.program.instruction_positions() {
-            match ins.id() {
-                InstructionId::Empty => {
-                    continue;
-                }
-                _ => {
-                    last_not_empty.write_delta(pos, writer)?;
-                    last_not_empty = pos;
-
-                    let i = I2NTF.binary_search_by_key(&ins.id(), |&(a, _)| a).unwrap();
-                    let (_, node_list) = I2NTF[i];
+            if ins.id() == InstructionId::Empty {
+                continue;
+            }
+            match last_not_empty {
+                Some(last) => last.write_delta(pos, writer)?,
+                None => InstructionPosition::write_start_delta(pos, writer)?,
+            }
+            last_not_empty = Some(pos);
 
-                    for node in node_list {
-                        match node {
-                            I2NTFNode::Chars(x) => {
-                                writer.write_all(x)?;
-                            }
-                            I2NTFNode::Literal(lt) => match ins.data() {
-                                InstructionData::Label(literal)
-                                    if matches!(lt, LiteralType::LabelIdentifierLiteral) =>
-                                {
-                                    literal.write_all(writer)?;
-                                }
-                                InstructionData::String(literal)
-                                    if matches!(lt, LiteralType::StringLiteral) =>
-                                {
-                                    literal.write_all(writer)?;
-                                }
-                                InstructionData::VarCmp((literal, _))
-                                    if matches!(lt, LiteralType::VariableIdentifierLiteral) =>
-                                {
-                                    literal.write_all(writer)?;
-                                }
-                                InstructionData::VarCmp((_, literal))
-                                    if matches!(lt, LiteralType::VariableValueLiteral) =>
-                                {
-                                    literal.write_all(writer)?;
-                                }
-                                _ => {
-                                    unreachable!("the I2NTF data is corrupt")
-                                }
-                            },
-                        }
-                    }
-                }
+            Self::serialize_instruction(&ins, writer)?;
+        }
+        Ok(())
+    }
+    /// Serializes only one instruction, without magic and position commands.
+    ///
+    /// It is guaranteed that all bytes written to the given `writer` are valid UTF-8, non-ASCII
+    /// chars come only from string literals.
+    ///
+    /// # Errors
+    ///
+    /// If the instruction has no spelling, the [`NoSpellingError`] will be returned and nothing
+    /// will be written. Errors of the [`io::Write::write_all`] method are returned as
+    /// [`SerializeError::Io`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use m3c::formats::internal::{Instruction, InstructionId};
+    /// use m3c::serialization::native::new::TextFormatSerializer;
+    ///
+    /// let mut buf = vec![];
+    /// let instruction = Instruction::new_simple(InstructionId::MoveW).unwrap();
+    /// TextFormatSerializer::serialize_instruction(&instruction, &mut buf).unwrap();
+    ///
+    /// assert_eq!(b"^W", &buf[..])
+    /// ```
+    pub fn serialize_instruction<W>(ins: &Instruction, writer: &mut W) -> Result<(), SerializeError>
+    where
+        W: io::Write,
+    {
+        let Some(ntf) = Self::spelling(ins.id()) else {
+            return Err(NoSpellingError {
+                id: ins.id(),
+                position: None,
+            }
+            .into());
+        };
+        writer.write_all(ntf[0].as_bytes())?;
+        match ins.data() {
+            InstructionData::Simple => {}
+            InstructionData::Label(literal) => {
+                literal.write_all(writer)?;
+                writer.write_all(ntf[1].as_bytes())?;
+            }
+            InstructionData::String(literal) => {
+                literal.write_all(writer)?;
+                writer.write_all(ntf[1].as_bytes())?;
+            }
+            InstructionData::VarCmp((name, value)) => {
+                name.write_all(writer)?;
+                writer.write_all(ntf[1].as_bytes())?;
+                value.write_all(writer)?;
+                writer.write_all(ntf[2].as_bytes())?;
             }
         }
         Ok(())
     }
+    /// Returns the spelling of the instruction with the given `id`.
+    ///
+    /// Returns `None` if New Text format has no spelling of the `id`.
+    fn spelling(id: InstructionId) -> Option<&'static [&'static str]> {
+        id.info()
+            .map(InstructionInfo::ntf)
+            .filter(|ntf| !ntf.is_empty())
+    }
 }
 
 impl InstructionPosition {
+    /// Writes the delta between the beginning of the program and `other`.
+    fn write_start_delta<W>(other: Self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        match (other.page(), other.row()) {
+            (0, 0) => {}
+            (0, row) => InstructionPosition::write_empty_rows(row - 1, writer)?,
+            (page, row) => {
+                InstructionPosition::write_page_delta(page, writer)?;
+                InstructionPosition::write_empty_rows(row, writer)?;
+            }
+        }
+        InstructionPosition::write_empty_columns(other.column(), writer)
+    }
     /// Writes the delta between `self` and `other`.
     ///
     /// # Safety
@@ -712,7 +1270,7 @@ impl InstructionPosition {
             if self.row() == other.row() {
                 let delta = other.column() - self.column();
                 match delta {
-                    0..=1 => return Ok(()),
+                    0..=1 => Ok(()),
                     _ => InstructionPosition::write_empty_columns(delta - 1, writer),
                 }
             } else {
@@ -750,7 +1308,7 @@ impl InstructionPosition {
                 buf[1] = n + b'0';
             }
             writer.write_all(&buf)?;
-            n -= n - 1;
+            n = 1;
         }
         while n > 1 {
             writer.write_all(b".")?;
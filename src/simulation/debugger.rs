@@ -0,0 +1,157 @@
+//! A step debugger of [simulated](super::Simulator) programs.
+
+use std::collections::{BTreeSet, HashSet};
+
+use crate::formats::internal::literals::StringLiteral;
+use crate::formats::internal::{Instruction, InstructionData, InstructionId, InstructionPosition};
+
+use super::{Simulator, Status};
+
+/// Why the [`Debugger`] stopped.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Stop {
+    /// The requested number of instructions were executed.
+    Step,
+    /// The next instruction is at a breakpoint.
+    Breakpoint(InstructionPosition),
+    /// The next instruction is a [`DebugBreak`](InstructionId::DebugBreak) with this tag.
+    Tag(StringLiteral),
+    /// The simulation isn't [running](Status::Running) anymore.
+    Halted(Status),
+}
+
+/// Runs a [`Simulator`] instruction by instruction, stopping at breakpoints.
+///
+/// There are two kinds of breakpoints:
+/// * positions, the debugger stops before executing the instruction at the position
+/// * tags, the debugger stops before executing a [`DebugBreak`](InstructionId::DebugBreak) with
+///   the tag. A `DebugBreak` with an empty tag always stops the debugger
+///
+/// The call stacks, variables and the [debug tag](crate::interpreter::Interpreter::debug_tag)
+/// are inspected via the [interpreter](Simulator::interpreter), the mode flags - via the
+/// [robot](Simulator::robot).
+///
+/// # Examples
+///
+/// ```
+/// use m3c::formats::internal::literals::StringLiteral;
+/// use m3c::formats::internal::{Instruction, InstructionId, InstructionPosition, Program};
+/// use m3c::simulation::debugger::{Debugger, Stop};
+/// use m3c::simulation::{robot::Robot, world::World, Simulator, Status};
+///
+/// let tag: StringLiteral = "hp".parse().unwrap();
+/// let mut program = Program::default();
+/// program[0] = Instruction::new_simple(InstructionId::MoveS).unwrap();
+/// program[1] = Instruction::new_string(InstructionId::DebugBreak, tag).unwrap();
+/// program[2] = Instruction::new_simple(InstructionId::ModeAutodiggOn).unwrap();
+/// program[3] = Instruction::new_simple(InstructionId::End).unwrap();
+///
+/// let world = World::new(1, 2);
+/// let mut debugger = Debugger::new(Simulator::new(&program, world, Robot::new(0, 0)));
+/// debugger.add_tag(tag);
+/// let third = InstructionPosition::new(0, 0, 3).unwrap();
+/// debugger.add_breakpoint(third);
+///
+/// assert_eq!(Stop::Tag(tag), debugger.resume(100));
+/// assert_eq!((0, 1), debugger.simulator().robot().position());
+/// assert_eq!(Stop::Step, debugger.step(1));
+/// assert_eq!(Stop::Breakpoint(third), debugger.resume(100));
+/// assert!(debugger.simulator().robot().autodigg());
+/// assert_eq!(Stop::Halted(Status::Finished), debugger.resume(100));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Debugger<'p> {
+    simulator: Simulator<'p>,
+    breakpoints: BTreeSet<InstructionPosition>,
+    tags: HashSet<StringLiteral>,
+}
+
+impl<'p> Debugger<'p> {
+    /// Creates a debugger of the `simulator` without breakpoints.
+    pub fn new(simulator: Simulator<'p>) -> Self {
+        Self {
+            simulator,
+            breakpoints: BTreeSet::new(),
+            tags: HashSet::new(),
+        }
+    }
+    /// Returns the simulator.
+    pub fn simulator(&self) -> &Simulator<'p> {
+        &self.simulator
+    }
+    /// Returns the simulator to modify it between steps.
+    pub fn simulator_mut(&mut self) -> &mut Simulator<'p> {
+        &mut self.simulator
+    }
+    /// Returns the next instruction to execute and its position.
+    pub fn next_instruction(&self) -> (InstructionPosition, Instruction) {
+        let position = self.simulator.position();
+        (position, self.simulator.interpreter().program()[position])
+    }
+    /// Adds a breakpoint at the `position`. Returns whether it's new.
+    pub fn add_breakpoint(&mut self, position: InstructionPosition) -> bool {
+        self.breakpoints.insert(position)
+    }
+    /// Removes the breakpoint at the `position`. Returns whether there was one.
+    pub fn remove_breakpoint(&mut self, position: InstructionPosition) -> bool {
+        self.breakpoints.remove(&position)
+    }
+    /// Returns the positions of the breakpoints in order.
+    pub fn breakpoints(&self) -> impl Iterator<Item = InstructionPosition> + '_ {
+        self.breakpoints.iter().copied()
+    }
+    /// Adds a breakpoint at `DebugBreak`s with the `tag`. Returns whether it's new.
+    pub fn add_tag(&mut self, tag: StringLiteral) -> bool {
+        self.tags.insert(tag)
+    }
+    /// Removes the breakpoint at `DebugBreak`s with the `tag`. Returns whether there was one.
+    pub fn remove_tag(&mut self, tag: StringLiteral) -> bool {
+        self.tags.remove(&tag)
+    }
+    /// Returns the tags of the breakpoints in no particular order.
+    pub fn tags(&self) -> impl Iterator<Item = StringLiteral> + '_ {
+        self.tags.iter().copied()
+    }
+    /// Executes `count` instructions ignoring breakpoints.
+    pub fn step(&mut self, count: usize) -> Stop {
+        for _ in 0..count {
+            let status = self.simulator.step();
+            if status != Status::Running {
+                return Stop::Halted(status);
+            }
+        }
+        Stop::Step
+    }
+    /// Executes instructions until the next one is at a breakpoint, at most `max_steps` ones.
+    ///
+    /// The first instruction is executed even if it's at a breakpoint, so the debugger can
+    /// resume from the breakpoint it stopped at.
+    pub fn resume(&mut self, max_steps: usize) -> Stop {
+        for _ in 0..max_steps {
+            let status = self.simulator.step();
+            if status != Status::Running {
+                return Stop::Halted(status);
+            }
+            if let Some(stop) = self.breakpoint() {
+                return stop;
+            }
+        }
+        Stop::Step
+    }
+    /// Returns the breakpoint the next instruction is at.
+    fn breakpoint(&self) -> Option<Stop> {
+        let (position, instruction) = self.next_instruction();
+        if self.breakpoints.contains(&position) {
+            return Some(Stop::Breakpoint(position));
+        }
+        match instruction.data() {
+            InstructionData::String(tag)
+                if instruction.id() == InstructionId::DebugBreak
+                    && (tag.is_empty() || self.tags.contains(&tag)) =>
+            {
+                Some(Stop::Tag(tag))
+            }
+            _ => None,
+        }
+    }
+}
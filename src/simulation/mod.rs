@@ -0,0 +1,163 @@
+//! A deterministic grid-world simulator running [internal](crate::formats::internal) programs.
+//!
+//! The [world] is a rectangle of cells with a single [robot] in it. Every [tick](Simulator::tick)
+//! the program is executed up to the first instruction which takes time (a move, a look, a dig, a
+//! build, ...), then the world is updated: falling cells fall and acid burns the robot.
+//!
+//! It's a simplified model of the game: only the rules described in [`Cell`](world::Cell),
+//! [`Robot`] and [`Sandbox`] are reproduced.
+//!
+//! Available submodules:
+//! * [debugger] - the step debugger
+//! * [robot] - the robot and directions
+//! * [sandbox] - the world and the robot as an [`Environment`](crate::interpreter::Environment)
+//! * [world] - cells and the world
+
+pub mod debugger;
+pub mod robot;
+pub mod sandbox;
+pub mod world;
+
+use crate::formats::internal::{InstructionPosition, Program};
+use crate::interpreter::{Interpreter, Step};
+use robot::Robot;
+use sandbox::Sandbox;
+use world::World;
+
+/// State of the simulation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Status {
+    /// The program is running.
+    Running,
+    /// The program executed [`End`](crate::formats::internal::InstructionId::End).
+    Finished,
+    /// The robot's health dropped to zero.
+    Destroyed,
+}
+
+/// Runs a [`Program`] in a [`World`], one tick at a time.
+///
+/// The [`Interpreter`] executes the program, the [`Sandbox`] performs its checks and actions.
+/// Control flow is described in the interpreter's docs, checks and actions - in the sandbox's ones.
+///
+/// # Examples
+///
+/// ```
+/// use m3c::formats::internal::{Instruction, InstructionId, Program};
+/// use m3c::simulation::{robot::Robot, world::World, Simulator, Status};
+///
+/// let mut program = Program::default();
+/// program[0] = Instruction::new_simple(InstructionId::Digg).unwrap();
+/// program[1] = Instruction::new_simple(InstructionId::MoveS).unwrap();
+/// program[2] = Instruction::new_simple(InstructionId::End).unwrap();
+///
+/// let world: World = ".\n*".parse().unwrap();
+/// let mut simulator = Simulator::new(&program, world, Robot::new(0, 0));
+///
+/// assert_eq!(Status::Finished, simulator.run(10));
+/// assert_eq!((0, 1), simulator.robot().position());
+/// assert_eq!(1, simulator.robot().crystals());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Simulator<'p> {
+    interpreter: Interpreter<'p>,
+    sandbox: Sandbox,
+    status: Status,
+    ticks: u64,
+    // instructions executed in the current tick
+    steps: usize,
+}
+
+impl<'p> Simulator<'p> {
+    /// Creates a simulator running the `program` from its first instruction.
+    pub fn new(program: &'p Program, world: World, robot: Robot) -> Self {
+        Self {
+            interpreter: Interpreter::new(program),
+            sandbox: Sandbox::new(world, robot),
+            status: Status::Running,
+            ticks: 0,
+            steps: 0,
+        }
+    }
+    /// Returns the simulator with the given seed for
+    /// [`ActionRandom`](crate::formats::internal::InstructionId::ActionRandom).
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.sandbox = self.sandbox.with_seed(seed);
+        self
+    }
+    /// Returns the interpreter executing the program.
+    pub fn interpreter(&self) -> &Interpreter<'p> {
+        &self.interpreter
+    }
+    /// Returns the sandbox performing checks and actions.
+    pub fn sandbox(&self) -> &Sandbox {
+        &self.sandbox
+    }
+    /// Returns the world.
+    pub fn world(&self) -> &World {
+        self.sandbox.world()
+    }
+    /// Returns the world to modify it between ticks.
+    pub fn world_mut(&mut self) -> &mut World {
+        self.sandbox.world_mut()
+    }
+    /// Returns the robot.
+    pub fn robot(&self) -> &Robot {
+        self.sandbox.robot()
+    }
+    /// Returns position of the next instruction to execute.
+    pub fn position(&self) -> InstructionPosition {
+        self.interpreter.position()
+    }
+    /// Returns the state of the simulation.
+    pub fn status(&self) -> Status {
+        self.status
+    }
+    /// Returns the number of ticks passed.
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+    /// Runs one tick: executes instructions up to the first action (including) and updates the
+    /// world.
+    ///
+    /// Does nothing if the simulation isn't [running](Status::Running).
+    pub fn tick(&mut self) -> Status {
+        let ticks = self.ticks;
+        while self.status == Status::Running && self.ticks == ticks {
+            self.step();
+        }
+        self.status
+    }
+    /// Executes the next instruction. If it ends the tick (it's an action, `End` or the
+    /// [last one](Interpreter::MAX_STEPS_PER_TICK) the tick can execute), updates the world.
+    ///
+    /// Does nothing if the simulation isn't [running](Status::Running).
+    pub fn step(&mut self) -> Status {
+        if self.status != Status::Running {
+            return self.status;
+        }
+        self.steps += 1;
+        match self.interpreter.step(&mut self.sandbox) {
+            Step::Control if self.steps < Interpreter::MAX_STEPS_PER_TICK => return self.status,
+            Step::Finished => self.status = Status::Finished,
+            _ => {}
+        }
+        self.steps = 0;
+        self.sandbox.update();
+        if self.robot().hp() == 0 {
+            self.status = Status::Destroyed;
+        }
+        self.ticks += 1;
+        self.status
+    }
+    /// Runs at most `max_ticks` ticks, stopping as soon as the simulation isn't
+    /// [running](Status::Running).
+    pub fn run(&mut self, max_ticks: u64) -> Status {
+        for _ in 0..max_ticks {
+            if self.tick() != Status::Running {
+                break;
+            }
+        }
+        self.status
+    }
+}
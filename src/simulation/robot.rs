@@ -0,0 +1,211 @@
+//! The robot executing the program.
+
+/// One of the four directions, named after the keys moving the robot.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Up.
+    W,
+    /// Left.
+    A,
+    /// Down.
+    S,
+    /// Right.
+    D,
+}
+
+impl Direction {
+    /// All the directions clockwise, starting with [`W`](Self::W).
+    pub const ALL: [Self; 4] = [Self::W, Self::D, Self::S, Self::A];
+
+    /// Returns the `(x, y)` offset of the neighbor cell in this direction.
+    pub fn offset(self) -> (i32, i32) {
+        match self {
+            Self::W => (0, -1),
+            Self::A => (-1, 0),
+            Self::S => (0, 1),
+            Self::D => (1, 0),
+        }
+    }
+    /// Returns the direction rotated 90 degrees clockwise.
+    pub fn clockwise(self) -> Self {
+        match self {
+            Self::W => Self::D,
+            Self::D => Self::S,
+            Self::S => Self::A,
+            Self::A => Self::W,
+        }
+    }
+    /// Returns the direction rotated 90 degrees counterclockwise.
+    pub fn counterclockwise(self) -> Self {
+        match self {
+            Self::W => Self::A,
+            Self::A => Self::S,
+            Self::S => Self::D,
+            Self::D => Self::W,
+        }
+    }
+    /// Returns the opposite direction.
+    pub fn opposite(self) -> Self {
+        self.clockwise().clockwise()
+    }
+    /// Returns index of the direction in [`ALL`](Self::ALL).
+    fn index(self) -> usize {
+        match self {
+            Self::W => 0,
+            Self::D => 1,
+            Self::S => 2,
+            Self::A => 3,
+        }
+    }
+}
+
+/// The robot: where it is, where it looks, its health, crystals and modes.
+///
+/// # Examples
+///
+/// ```
+/// use m3c::simulation::robot::{Direction, Robot};
+///
+/// let robot = Robot::new(2, 3).with_facing(Direction::D).with_crystals(10);
+///
+/// assert_eq!((2, 3), robot.position());
+/// assert_eq!((3, 3), robot.front());
+/// assert_eq!(Robot::MAX_HP, robot.hp());
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Robot {
+    x: i32,
+    y: i32,
+    facing: Direction,
+    hp: u32,
+    crystals: u32,
+    autodigg: bool,
+    aggression: bool,
+    hand_mode: bool,
+    // by `Direction::index`
+    inverted: [bool; 4],
+}
+
+impl Robot {
+    /// Health of a new robot.
+    pub const MAX_HP: u32 = 100;
+
+    /// Creates a healthy robot at the given coordinates looking down, without crystals and with
+    /// all the modes off.
+    pub fn new(x: i32, y: i32) -> Self {
+        Self {
+            x,
+            y,
+            facing: Direction::S,
+            hp: Self::MAX_HP,
+            crystals: 0,
+            autodigg: false,
+            aggression: false,
+            hand_mode: false,
+            inverted: [false; 4],
+        }
+    }
+    /// Returns the robot looking in the given direction.
+    pub fn with_facing(mut self, facing: Direction) -> Self {
+        self.facing = facing;
+        self
+    }
+    /// Returns the robot with the given health.
+    pub fn with_hp(mut self, hp: u32) -> Self {
+        self.hp = hp.min(Self::MAX_HP);
+        self
+    }
+    /// Returns the robot with the given number of crystals.
+    pub fn with_crystals(mut self, crystals: u32) -> Self {
+        self.crystals = crystals;
+        self
+    }
+    /// Returns the `(x, y)` coordinates of the robot.
+    pub fn position(&self) -> (i32, i32) {
+        (self.x, self.y)
+    }
+    /// Returns the direction the robot looks in.
+    pub fn facing(&self) -> Direction {
+        self.facing
+    }
+    /// Returns the coordinates of the cell next to the robot in the given direction.
+    pub fn neighbor(&self, direction: Direction) -> (i32, i32) {
+        let (dx, dy) = direction.offset();
+        (self.x + dx, self.y + dy)
+    }
+    /// Returns the coordinates of the cell the robot looks at.
+    pub fn front(&self) -> (i32, i32) {
+        self.neighbor(self.facing)
+    }
+    /// Returns the health. The robot is destroyed at `0`.
+    pub fn hp(&self) -> u32 {
+        self.hp
+    }
+    /// Returns the number of crystals the robot carries.
+    pub fn crystals(&self) -> u32 {
+        self.crystals
+    }
+    /// Whether the robot digs the cell it can't move into.
+    pub fn autodigg(&self) -> bool {
+        self.autodigg
+    }
+    /// Whether the aggression mode is on.
+    pub fn aggression(&self) -> bool {
+        self.aggression
+    }
+    /// Whether the hand mode is on.
+    pub fn hand_mode(&self) -> bool {
+        self.hand_mode
+    }
+    /// Whether moves and looks in the given direction go the opposite way.
+    pub fn is_inverted(&self, direction: Direction) -> bool {
+        self.inverted[direction.index()]
+    }
+    /// Returns the direction moves and looks in the given `direction` actually go.
+    pub fn resolve(&self, direction: Direction) -> Direction {
+        if self.is_inverted(direction) {
+            direction.opposite()
+        } else {
+            direction
+        }
+    }
+    pub(crate) fn set_position(&mut self, (x, y): (i32, i32)) {
+        self.x = x;
+        self.y = y;
+    }
+    pub(crate) fn set_facing(&mut self, facing: Direction) {
+        self.facing = facing;
+    }
+    pub(crate) fn set_autodigg(&mut self, on: bool) {
+        self.autodigg = on;
+    }
+    pub(crate) fn set_aggression(&mut self, on: bool) {
+        self.aggression = on;
+    }
+    pub(crate) fn set_hand_mode(&mut self, on: bool) {
+        self.hand_mode = on;
+    }
+    /// Toggles inversion of the given direction.
+    pub(crate) fn invert(&mut self, direction: Direction) {
+        self.inverted[direction.index()] ^= true;
+    }
+    pub(crate) fn add_crystals(&mut self, crystals: u32) {
+        self.crystals += crystals;
+    }
+    /// Takes the given number of crystals, if the robot has enough.
+    pub(crate) fn spend_crystals(&mut self, crystals: u32) -> bool {
+        match self.crystals.checked_sub(crystals) {
+            Some(left) => {
+                self.crystals = left;
+                true
+            }
+            None => false,
+        }
+    }
+    pub(crate) fn heal(&mut self, hp: u32) {
+        self.hp = (self.hp + hp).min(Self::MAX_HP);
+    }
+    pub(crate) fn damage(&mut self, hp: u32) {
+        self.hp = self.hp.saturating_sub(hp);
+    }
+}
@@ -0,0 +1,282 @@
+//! The world and the robot as an [`Environment`] of the [interpreter](crate::interpreter).
+
+use crate::formats::internal::InstructionId;
+use crate::interpreter::Environment;
+
+use super::robot::{Direction, Robot};
+use super::world::{Cell, World};
+
+/// A [`World`] with a [`Robot`] in it, performing checks and actions of a program.
+///
+/// # Checks
+///
+/// `Cell*` selectors are relative to the robot, [`CellF`](InstructionId::CellF) is the cell in
+/// front of it. `Cc*` instructions check the selected cell, `Cb*` ones - the robot health.
+///
+/// # Actions
+///
+/// Every action takes a tick, even if it fails (e.g. the robot can't move into a rock):
+/// * `Move*` turns the robot and moves it to the neighbor cell if the cell is passable. Otherwise,
+///   in the autodigg mode, digs the cell instead. [`MoveF`] moves forward
+/// * `Look*`, [`RotateCw`] and [`RotateCcw`] turn the robot
+/// * [`Digg`] digs the cell in front of the robot, the crystals of the destroyed cell are
+///   collected
+/// * [`ActionBuild`], [`ActionB1`], [`ActionB2`], [`ActionB3`], [`ActionQuadro`] and
+///   [`ActionRoad`] build an [opor](Cell::Opor), a [green](Cell::GreenBlock),
+///   [yellow](Cell::YellowBlock), [red](Cell::RedBlock) block, a [quadro](Cell::Quadro) and a
+///   [road](Cell::Road) in the empty cell in front of the robot, spending
+///   [crystals](Cell::build_cost)
+/// * [`ActionHeal`] spends a crystal to restore [`HEAL_HP`](Self::HEAL_HP) health
+/// * [`ActionRandom`] moves in a random direction
+/// * the rest of the actions (e.g. [`ActionBibika`] or [`StdDigg`]) do nothing
+///
+/// Mode instructions (e.g. [`ModeAutodiggOn`] or [`InvDirW`]) don't take time.
+///
+/// # Responses
+///
+/// The sandbox asks to [respond](Environment::poll_response) once after the robot is hurt.
+///
+/// [`MoveF`]: InstructionId::MoveF
+/// [`RotateCw`]: InstructionId::RotateCw
+/// [`RotateCcw`]: InstructionId::RotateCcw
+/// [`Digg`]: InstructionId::Digg
+/// [`ActionBuild`]: InstructionId::ActionBuild
+/// [`ActionB1`]: InstructionId::ActionB1
+/// [`ActionB2`]: InstructionId::ActionB2
+/// [`ActionB3`]: InstructionId::ActionB3
+/// [`ActionQuadro`]: InstructionId::ActionQuadro
+/// [`ActionRoad`]: InstructionId::ActionRoad
+/// [`ActionHeal`]: InstructionId::ActionHeal
+/// [`ActionRandom`]: InstructionId::ActionRandom
+/// [`ActionBibika`]: InstructionId::ActionBibika
+/// [`StdDigg`]: InstructionId::StdDigg
+/// [`ModeAutodiggOn`]: InstructionId::ModeAutodiggOn
+/// [`InvDirW`]: InstructionId::InvDirW
+#[derive(Debug, Clone)]
+pub struct Sandbox {
+    world: World,
+    robot: Robot,
+    // xorshift state
+    seed: u64,
+    // the robot was hurt since the last poll
+    hurt: bool,
+}
+
+impl Sandbox {
+    /// Health [`ActionHeal`](InstructionId::ActionHeal) restores.
+    pub const HEAL_HP: u32 = 20;
+    /// Health the robot loses every [update](Self::update) for every neighbor [acid](Cell::Acid)
+    /// cell.
+    pub const ACID_DAMAGE: u32 = 10;
+
+    /// Creates a sandbox with the `robot` in the `world`.
+    pub fn new(world: World, robot: Robot) -> Self {
+        Self {
+            world,
+            robot,
+            seed: 0x2545_F491_4F6C_DD1D,
+            hurt: false,
+        }
+    }
+    /// Returns the sandbox with the given seed for [`ActionRandom`](InstructionId::ActionRandom).
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        // xorshift never leaves zero
+        self.seed = seed.max(1);
+        self
+    }
+    /// Returns the world.
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+    /// Returns the world to modify it between actions.
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+    /// Returns the robot.
+    pub fn robot(&self) -> &Robot {
+        &self.robot
+    }
+    /// Applies gravity and acid. The simulator calls it after every tick.
+    pub fn update(&mut self) {
+        self.world.apply_gravity(self.robot.position());
+        let acid = Direction::ALL
+            .iter()
+            .filter(|&&direction| {
+                let (x, y) = self.robot.neighbor(direction);
+                self.world.get(x, y) == Cell::Acid
+            })
+            .count() as u32;
+        if acid > 0 && self.robot.hp() > 0 {
+            self.robot.damage(acid * Self::ACID_DAMAGE);
+            self.hurt = true;
+        }
+    }
+    /// Returns the coordinates of the cell the `Cell*` instruction `id` selects.
+    fn select(&self, id: InstructionId) -> (i32, i32) {
+        let facing = self.robot.facing();
+        let steps: &[Direction] = match id {
+            InstructionId::CellW => &[Direction::W],
+            InstructionId::CellA => &[Direction::A],
+            InstructionId::CellS => &[Direction::S],
+            InstructionId::CellD => &[Direction::D],
+            InstructionId::CellWa => &[Direction::W, Direction::A],
+            InstructionId::CellDw => &[Direction::D, Direction::W],
+            InstructionId::CellSd => &[Direction::S, Direction::D],
+            InstructionId::CellAs => &[Direction::A, Direction::S],
+            InstructionId::CellWw => &[Direction::W, Direction::W],
+            InstructionId::CellAa => &[Direction::A, Direction::A],
+            InstructionId::CellSs => &[Direction::S, Direction::S],
+            InstructionId::CellDd => &[Direction::D, Direction::D],
+            InstructionId::CellFf => &[facing, facing],
+            InstructionId::CellRightHand => &[facing.clockwise()],
+            InstructionId::CellLeftHand => &[facing.counterclockwise()],
+            _ => &[facing],
+        };
+        steps
+            .iter()
+            .fold(self.robot.position(), |(x, y), direction| {
+                let (dx, dy) = direction.offset();
+                (x + dx, y + dy)
+            })
+    }
+    /// Turns the robot and moves it in the (resolved) `direction`.
+    fn move_to(&mut self, direction: Direction) {
+        let direction = self.robot.resolve(direction);
+        self.robot.set_facing(direction);
+        let (x, y) = self.robot.neighbor(direction);
+        if self.world.get(x, y).is_passable() {
+            self.robot.set_position((x, y));
+        } else if self.robot.autodigg() {
+            self.dig((x, y));
+        }
+    }
+    /// Turns the robot in the (resolved) `direction`.
+    fn look(&mut self, direction: Direction) {
+        self.robot.set_facing(self.robot.resolve(direction));
+    }
+    /// Digs the cell, collecting its crystals if it's destroyed.
+    fn dig(&mut self, (x, y): (i32, i32)) {
+        if let Some(cell) = self.world.dig(x, y) {
+            self.robot.add_crystals(cell.crystals());
+        }
+    }
+    /// Builds the `cell` in front of the robot, if the cell there is empty and the robot has
+    /// enough crystals.
+    fn build(&mut self, cell: Cell) {
+        let (x, y) = self.robot.front();
+        let cost = cell.build_cost().expect("only buildable cells are built");
+        if self.world.get(x, y) == Cell::Empty && self.robot.spend_crystals(cost) {
+            self.world.set(x, y, cell);
+        }
+    }
+    /// Spends a crystal to restore health, if the robot is hurt.
+    fn heal(&mut self) {
+        if self.robot.hp() < Robot::MAX_HP && self.robot.spend_crystals(1) {
+            self.robot.heal(Self::HEAL_HP);
+        }
+    }
+    /// Returns the next pseudo-random number.
+    fn next_random(&mut self) -> u64 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        self.seed
+    }
+}
+
+impl Environment for Sandbox {
+    fn act(&mut self, id: InstructionId) -> bool {
+        match id {
+            InstructionId::MoveW => self.move_to(Direction::W),
+            InstructionId::MoveA => self.move_to(Direction::A),
+            InstructionId::MoveS => self.move_to(Direction::S),
+            InstructionId::MoveD => self.move_to(Direction::D),
+            InstructionId::MoveF => self.move_to(self.robot.facing()),
+            InstructionId::LookW => self.look(Direction::W),
+            InstructionId::LookA => self.look(Direction::A),
+            InstructionId::LookS => self.look(Direction::S),
+            InstructionId::LookD => self.look(Direction::D),
+            InstructionId::RotateCw => self.robot.set_facing(self.robot.facing().clockwise()),
+            InstructionId::RotateCcw => {
+                self.robot
+                    .set_facing(self.robot.facing().counterclockwise());
+            }
+            InstructionId::Digg => self.dig(self.robot.front()),
+            InstructionId::ActionBuild => self.build(Cell::Opor),
+            InstructionId::ActionB1 => self.build(Cell::GreenBlock),
+            InstructionId::ActionB2 => self.build(Cell::YellowBlock),
+            InstructionId::ActionB3 => self.build(Cell::RedBlock),
+            InstructionId::ActionQuadro => self.build(Cell::Quadro),
+            InstructionId::ActionRoad => self.build(Cell::Road),
+            InstructionId::ActionHeal => self.heal(),
+            InstructionId::ActionRandom => {
+                let direction = Direction::ALL[(self.next_random() % 4) as usize];
+                self.move_to(direction);
+            }
+            // modes don't take time
+            InstructionId::ModeAutodiggOn | InstructionId::ModeAutodiggOff => {
+                self.robot.set_autodigg(id == InstructionId::ModeAutodiggOn);
+                return false;
+            }
+            InstructionId::ModeAgrOn | InstructionId::ModeAgrOff => {
+                self.robot.set_aggression(id == InstructionId::ModeAgrOn);
+                return false;
+            }
+            InstructionId::HandModeOn | InstructionId::HandModeOff => {
+                self.robot.set_hand_mode(id == InstructionId::HandModeOn);
+                return false;
+            }
+            InstructionId::InvDirW => {
+                self.robot.invert(Direction::W);
+                return false;
+            }
+            InstructionId::InvDirA => {
+                self.robot.invert(Direction::A);
+                return false;
+            }
+            InstructionId::InvDirS => {
+                self.robot.invert(Direction::S);
+                return false;
+            }
+            InstructionId::InvDirD => {
+                self.robot.invert(Direction::D);
+                return false;
+            }
+            // the rest of the actions do nothing
+            _ => {}
+        }
+        true
+    }
+    fn check(&mut self, id: InstructionId, selector: InstructionId) -> bool {
+        let (x, y) = self.select(selector);
+        let cell = self.world.get(x, y);
+        match id {
+            InstructionId::CcEmpty => cell.is_passable(),
+            InstructionId::CcNotEmpty => !cell.is_passable(),
+            InstructionId::CcGravity => cell.has_gravity(),
+            InstructionId::CcCrystall => cell == Cell::Crystal,
+            InstructionId::CcAlive => cell == Cell::AliveCrystal,
+            InstructionId::CcBolder => cell == Cell::Bolder,
+            InstructionId::CcSand => cell == Cell::Sand,
+            InstructionId::CcRock => cell.is_rock(),
+            InstructionId::CcDead => cell == Cell::Dead,
+            InstructionId::CccRedRock => cell == Cell::RedRock,
+            InstructionId::CccBlackRock => cell == Cell::BlackRock,
+            InstructionId::CcAcid => cell == Cell::Acid,
+            InstructionId::CccQuadro => cell == Cell::Quadro,
+            InstructionId::CccRoad => cell == Cell::Road,
+            InstructionId::CccRedBlock => cell == Cell::RedBlock,
+            InstructionId::CccYellowBlock => cell == Cell::YellowBlock,
+            InstructionId::CccGreenBlock => cell == Cell::GreenBlock,
+            InstructionId::CccBox => cell == Cell::Box,
+            InstructionId::CccOpor => cell == Cell::Opor,
+            InstructionId::CcGun => cell == Cell::Gun,
+            InstructionId::CbHp => self.robot.hp() < Robot::MAX_HP,
+            InstructionId::CbHp50 => self.robot.hp() < Robot::MAX_HP / 2,
+            _ => false,
+        }
+    }
+    fn poll_response(&mut self) -> bool {
+        std::mem::take(&mut self.hurt)
+    }
+}
@@ -0,0 +1,296 @@
+//! The cell world the robot lives in.
+
+use std::{error::Error, fmt, str::FromStr};
+
+// region: errors
+
+/// An error returned when parsing a [`World`] from its map.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ParseWorldError {
+    /// The map has no cells.
+    Empty,
+    /// Lines of the map have different lengths. Contains index of the first such line.
+    RaggedLine(usize),
+    /// The map contains a char which isn't a [cell](Cell::from_char).
+    UnknownCell(char),
+}
+
+impl fmt::Display for ParseWorldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "the map has no cells"),
+            Self::RaggedLine(line) => write!(f, "line {} has a different length", line),
+            Self::UnknownCell(ch) => write!(f, "unknown cell \"{}\"", ch),
+        }
+    }
+}
+
+impl Error for ParseWorldError {}
+
+// endregion: errors
+
+/// A cell of the world.
+///
+/// The simulator's model of the game cells:
+/// * [`Empty`](Self::Empty) and [`Road`](Self::Road) cells are passable, all the others aren't
+/// * every cell but passable ones, [`Acid`](Self::Acid) and [`Dead`](Self::Dead) can be dug. It
+///   takes [`durability`](Self::durability) digs to destroy a cell
+/// * [`Sand`](Self::Sand) and [`Bolder`](Self::Bolder) fall down into empty cells
+/// * acid burns the robot standing next to it
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub enum Cell {
+    #[default]
+    Empty,
+    Road,
+    Sand,
+    Bolder,
+    Rock,
+    RedRock,
+    BlackRock,
+    Crystal,
+    AliveCrystal,
+    Acid,
+    Box,
+    Quadro,
+    GreenBlock,
+    YellowBlock,
+    RedBlock,
+    Opor,
+    Gun,
+    /// An indestructible cell. Everything beyond the world edges is dead too.
+    Dead,
+}
+
+impl Cell {
+    /// All the cells and their map chars.
+    const CHARS: [(Self, char); 18] = [
+        (Self::Empty, '.'),
+        (Self::Road, '='),
+        (Self::Sand, ':'),
+        (Self::Bolder, 'o'),
+        (Self::Rock, '#'),
+        (Self::RedRock, 'r'),
+        (Self::BlackRock, 'b'),
+        (Self::Crystal, '*'),
+        (Self::AliveCrystal, '+'),
+        (Self::Acid, '~'),
+        (Self::Box, 'B'),
+        (Self::Quadro, 'Q'),
+        (Self::GreenBlock, 'G'),
+        (Self::YellowBlock, 'Y'),
+        (Self::RedBlock, 'R'),
+        (Self::Opor, 'O'),
+        (Self::Gun, 'T'),
+        (Self::Dead, 'X'),
+    ];
+
+    /// Returns the cell for the given map char.
+    ///
+    /// Besides `.`, a space stands for [`Empty`](Self::Empty) too.
+    pub fn from_char(ch: char) -> Option<Self> {
+        if ch == ' ' {
+            return Some(Self::Empty);
+        }
+        Self::CHARS
+            .iter()
+            .find(|&&(_, c)| c == ch)
+            .map(|&(cell, _)| cell)
+    }
+    /// Returns the map char of this cell.
+    pub fn to_char(self) -> char {
+        Self::CHARS
+            .iter()
+            .find(|&&(cell, _)| cell == self)
+            .map(|&(_, c)| c)
+            .expect("every cell has a char")
+    }
+    /// Whether the robot can move into this cell.
+    pub fn is_passable(self) -> bool {
+        matches!(self, Self::Empty | Self::Road)
+    }
+    /// Whether this cell falls down into an empty cell.
+    pub fn has_gravity(self) -> bool {
+        matches!(self, Self::Sand | Self::Bolder)
+    }
+    /// Whether this cell is a rock of any color.
+    pub fn is_rock(self) -> bool {
+        matches!(self, Self::Rock | Self::RedRock | Self::BlackRock)
+    }
+    /// Returns the number of digs to destroy this cell. Value `None` stands for the cell can't be
+    /// dug.
+    pub fn durability(self) -> Option<u8> {
+        match self {
+            Self::Empty | Self::Road | Self::Acid | Self::Dead => None,
+            Self::Sand | Self::Crystal | Self::Box => Some(1),
+            Self::Bolder | Self::AliveCrystal | Self::GreenBlock => Some(2),
+            Self::Rock | Self::Quadro | Self::Opor | Self::Gun => Some(3),
+            Self::YellowBlock => Some(4),
+            Self::RedRock => Some(5),
+            Self::RedBlock => Some(6),
+            Self::BlackRock => Some(8),
+        }
+    }
+    /// Returns the number of crystals the robot gets for destroying this cell.
+    pub fn crystals(self) -> u32 {
+        match self {
+            Self::Crystal => 1,
+            Self::AliveCrystal => 3,
+            Self::Box => 5,
+            _ => 0,
+        }
+    }
+    /// Returns the number of crystals it costs to build this cell. Value `None` stands for the
+    /// cell can't be built.
+    pub fn build_cost(self) -> Option<u32> {
+        match self {
+            Self::Road | Self::GreenBlock => Some(1),
+            Self::YellowBlock | Self::Quadro | Self::Opor => Some(2),
+            Self::RedBlock => Some(3),
+            _ => None,
+        }
+    }
+}
+
+/// A rectangle of [cells](Cell).
+///
+/// Coordinates grow to the right (`x`) and down (`y`), `(0, 0)` is the top left cell.
+///
+/// # Examples
+///
+/// ```
+/// use m3c::simulation::world::{Cell, World};
+///
+/// let world: World = "..#\n.*:".parse().unwrap();
+///
+/// assert_eq!((3, 2), (world.width(), world.height()));
+/// assert_eq!(Cell::Crystal, world.get(1, 1));
+/// assert_eq!(Cell::Dead, world.get(3, 0));
+/// assert_eq!("..#\n.*:\n", world.to_string());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct World {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+    // digs left to destroy every cell
+    durability: Vec<u8>,
+}
+
+impl World {
+    /// Creates a world of [`Empty`](Cell::Empty) cells.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` or `height` is zero.
+    pub fn new(width: usize, height: usize) -> Self {
+        assert!(width > 0 && height > 0, "the world must have cells");
+        Self {
+            width,
+            height,
+            cells: vec![Cell::Empty; width * height],
+            durability: vec![0; width * height],
+        }
+    }
+    /// Returns the number of columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    /// Returns the number of rows.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+    /// Returns index of the cell, if it's in the world.
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        let x = usize::try_from(x).ok().filter(|&x| x < self.width)?;
+        let y = usize::try_from(y).ok().filter(|&y| y < self.height)?;
+        Some(y * self.width + x)
+    }
+    /// Returns the cell at the given coordinates. Cells beyond the world edges are
+    /// [`Dead`](Cell::Dead).
+    pub fn get(&self, x: i32, y: i32) -> Cell {
+        self.index(x, y).map_or(Cell::Dead, |i| self.cells[i])
+    }
+    /// Replaces the cell at the given coordinates, restoring its durability.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the coordinates are beyond the world edges.
+    pub fn set(&mut self, x: i32, y: i32, cell: Cell) {
+        let i = self
+            .index(x, y)
+            .expect("the coordinates are beyond the world edges");
+        self.cells[i] = cell;
+        self.durability[i] = cell.durability().unwrap_or(0);
+    }
+    /// Returns the number of digs left to destroy the cell at the given coordinates.
+    pub fn durability(&self, x: i32, y: i32) -> u8 {
+        self.index(x, y).map_or(0, |i| self.durability[i])
+    }
+    /// Digs the cell at the given coordinates once.
+    ///
+    /// Returns the destroyed cell if that was the last dig it could stand.
+    pub(crate) fn dig(&mut self, x: i32, y: i32) -> Option<Cell> {
+        let i = self.index(x, y)?;
+        let cell = self.cells[i];
+        cell.durability()?;
+        self.durability[i] = self.durability[i].saturating_sub(1);
+        if self.durability[i] > 0 {
+            return None;
+        }
+        self.cells[i] = Cell::Empty;
+        Some(cell)
+    }
+    /// Moves every falling cell one cell down, if the cell below is empty and isn't `occupied`.
+    pub(crate) fn apply_gravity(&mut self, occupied: (i32, i32)) {
+        // bottom-up, so a column of sand falls by one cell only
+        for y in (0..self.height as i32 - 1).rev() {
+            for x in 0..self.width as i32 {
+                if !self.get(x, y).has_gravity()
+                    || self.get(x, y + 1) != Cell::Empty
+                    || (x, y + 1) == occupied
+                {
+                    continue;
+                }
+                let (from, to) = (self.index(x, y).unwrap(), self.index(x, y + 1).unwrap());
+                self.cells.swap(from, to);
+                self.durability.swap(from, to);
+            }
+        }
+    }
+}
+
+impl FromStr for World {
+    type Err = ParseWorldError;
+    /// Parses the map: one line per row, one [char](Cell::from_char) per cell.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<&str> = s.lines().collect();
+        let width = lines.first().map_or(0, |line| line.chars().count());
+        if width == 0 {
+            return Err(ParseWorldError::Empty);
+        }
+        let mut world = Self::new(width, lines.len());
+        for (y, line) in lines.iter().enumerate() {
+            if line.chars().count() != width {
+                return Err(ParseWorldError::RaggedLine(y));
+            }
+            for (x, ch) in line.chars().enumerate() {
+                let cell = Cell::from_char(ch).ok_or(ParseWorldError::UnknownCell(ch))?;
+                world.set(x as i32, y as i32, cell);
+            }
+        }
+        Ok(world)
+    }
+}
+
+impl fmt::Display for World {
+    /// Writes the map the way [`FromStr`] parses it, every row ends with `\n`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.cells.chunks(self.width) {
+            for cell in row {
+                write!(f, "{}", cell.to_char())?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+use m3c::analysis::cfg::{ControlFlowGraph, Edge, EdgeKind};
+use m3c::analysis::diagnostics::{Diagnostics, UnreachableCode};
+use m3c::formats::internal::{Instruction, InstructionId, Program};
+
+use crate::common::{self, at, label};
+
+/// Edges going from the block with the given `start`.
+fn successors(cfg: &ControlFlowGraph, start: usize) -> Vec<(usize, EdgeKind)> {
+    let block = cfg.block_of(at(start));
+    assert_eq!(at(start), cfg.blocks()[block].start());
+    cfg.successors(block)
+        .map(|edge| (cfg.blocks()[edge.to].start().index(), edge.kind))
+        .collect()
+}
+
+/// A program without control transfers is one block falling through all the pages and starting
+/// over.
+#[test]
+fn straight_line() {
+    let mut program = common::internal::moves_wsf();
+    program[Program::INSTRUCTIONS_PER_PROGRAM - 1] =
+        Instruction::new_simple(InstructionId::MoveD).unwrap();
+
+    let cfg = ControlFlowGraph::new(&program);
+
+    assert_eq!(1, cfg.blocks().len());
+    assert_eq!(
+        at(Program::INSTRUCTIONS_PER_PROGRAM - 1),
+        cfg.blocks()[0].end()
+    );
+    assert_eq!(
+        &[Edge {
+            from: 0,
+            to: 0,
+            kind: EdgeKind::Restart
+        }],
+        cfg.edges()
+    );
+    assert!(cfg.diagnostics().is_empty());
+}
+
+/// A program running to the end of page 0 falls through the empty pages and starts over.
+#[test]
+fn wrap_around() {
+    let end_of_page = Program::ROWS_PER_PAGE * Program::INSTRUCTIONS_PER_ROW - 1;
+    let mut program = Program::default();
+    program[0] = Instruction::new_label(InstructionId::IfGoTo, label("a")).unwrap();
+    program[1] = Instruction::new_simple(InstructionId::MoveW).unwrap();
+    program[2] = Instruction::new_simple(InstructionId::End).unwrap();
+    program[end_of_page] = Instruction::new_label(InstructionId::Label, label("a")).unwrap();
+
+    let cfg = ControlFlowGraph::new(&program);
+
+    assert_eq!(
+        at(Program::INSTRUCTIONS_PER_PROGRAM - 1),
+        cfg.blocks()[cfg.block_of(at(end_of_page))].end()
+    );
+    assert_eq!(vec![(0, EdgeKind::Restart)], successors(&cfg, end_of_page));
+    assert!(cfg.diagnostics().is_empty());
+}
+
+/// ```text
+///  0: IfGoTo a     ; branch
+///  1: GoSub f      ; call
+///  2: End
+///  3: MoveW        ; unreachable
+///  4: Label a
+///  5: GoTo a       ; loop
+///  6: Label f
+///  7: OnResp h
+///  8: Return
+///  9: Label h
+/// 10: Back
+/// 11: Label x      ; unreachable label
+/// 12: MoveS
+/// ```
+#[test]
+fn control_transfers() {
+    let mut program = Program::default();
+    program[0] = Instruction::new_label(InstructionId::IfGoTo, label("a")).unwrap();
+    program[1] = Instruction::new_label(InstructionId::GoSub, label("f")).unwrap();
+    program[2] = Instruction::new_simple(InstructionId::End).unwrap();
+    program[3] = Instruction::new_simple(InstructionId::MoveW).unwrap();
+    program[4] = Instruction::new_label(InstructionId::Label, label("a")).unwrap();
+    program[5] = Instruction::new_label(InstructionId::GoTo, label("a")).unwrap();
+    program[6] = Instruction::new_label(InstructionId::Label, label("f")).unwrap();
+    program[7] = Instruction::new_label(InstructionId::OnResp, label("h")).unwrap();
+    program[8] = Instruction::new_simple(InstructionId::Return).unwrap();
+    program[9] = Instruction::new_label(InstructionId::Label, label("h")).unwrap();
+    program[10] = Instruction::new_simple(InstructionId::Back).unwrap();
+    program[11] = Instruction::new_label(InstructionId::Label, label("x")).unwrap();
+    program[12] = Instruction::new_simple(InstructionId::MoveS).unwrap();
+
+    let cfg = ControlFlowGraph::new(&program);
+
+    let starts: Vec<usize> = cfg.blocks().iter().map(|b| b.start().index()).collect();
+    assert_eq!(vec![0, 1, 2, 3, 4, 6, 8, 9, 11], starts);
+
+    assert_eq!(
+        vec![(4, EdgeKind::Branch), (1, EdgeKind::FallThrough)],
+        successors(&cfg, 0)
+    );
+    assert_eq!(
+        vec![(6, EdgeKind::Call), (2, EdgeKind::CallReturn)],
+        successors(&cfg, 1)
+    );
+    assert!(successors(&cfg, 2).is_empty());
+    assert_eq!(vec![(4, EdgeKind::FallThrough)], successors(&cfg, 3));
+    assert_eq!(vec![(4, EdgeKind::Jump)], successors(&cfg, 4));
+    assert_eq!(
+        vec![(8, EdgeKind::FallThrough), (9, EdgeKind::Handler)],
+        successors(&cfg, 6)
+    );
+    assert!(successors(&cfg, 8).is_empty());
+    assert_eq!(vec![(0, EdgeKind::Restart)], successors(&cfg, 9));
+    assert_eq!(
+        Some(&Edge {
+            from: 4,
+            to: 4,
+            kind: EdgeKind::Jump
+        }),
+        cfg.predecessors(4).last()
+    );
+
+    assert!(!cfg.is_reachable(at(3)));
+    assert!(cfg.is_reachable(at(10)));
+    assert!(!cfg.is_reachable(at(12)));
+    assert_eq!(
+        vec![
+            Diagnostics::from(UnreachableCode::new(at(3))),
+            UnreachableCode::new(at(11)).into(),
+        ],
+        cfg.diagnostics()
+    );
+}
+
+/// `Restart` restarts the program the same way `Back` does.
+#[test]
+fn restart() {
+    let mut program = Program::default();
+    program[0] = Instruction::new_simple(InstructionId::MoveW).unwrap();
+    program[1] = Instruction::new_simple(InstructionId::Restart).unwrap();
+    program[2] = Instruction::new_simple(InstructionId::MoveS).unwrap();
+
+    let cfg = ControlFlowGraph::new(&program);
+
+    assert_eq!(at(1), cfg.blocks()[0].end());
+    assert_eq!(vec![(0, EdgeKind::Restart)], successors(&cfg, 0));
+    assert_eq!(
+        vec![Diagnostics::from(UnreachableCode::new(at(2)))],
+        cfg.diagnostics()
+    );
+}
+
+/// Jumps to undefined labels have no edges, so an undefined `GoTo` ends the reachable code.
+#[test]
+fn undefined_label() {
+    let mut program = Program::default();
+    program[0] = Instruction::new_label(InstructionId::GoTo, label("a")).unwrap();
+    program[Program::INSTRUCTIONS_PER_ROW] = Instruction::new_simple(InstructionId::MoveD).unwrap();
+
+    let cfg = ControlFlowGraph::new(&program);
+
+    assert!(successors(&cfg, 0).is_empty());
+    assert_eq!(
+        vec![Diagnostics::from(UnreachableCode::new(at(
+            Program::INSTRUCTIONS_PER_ROW
+        )))],
+        cfg.diagnostics()
+    );
+}
@@ -0,0 +1,72 @@
+use m3c::analysis::dot::to_dot;
+use m3c::formats::internal::{Instruction, InstructionId, Program};
+
+use crate::common::label;
+
+#[test]
+fn loop_with_call() {
+    let mut program = Program::default();
+    program[0] = Instruction::new_label(InstructionId::Label, label("a")).unwrap();
+    program[1] = Instruction::new_simple(InstructionId::CcEmpty).unwrap();
+    program[2] = Instruction::new_label(InstructionId::IfGoTo, label("end")).unwrap();
+    program[3] = Instruction::new_label(InstructionId::GoSub, label("f")).unwrap();
+    program[4] = Instruction::new_label(InstructionId::GoTo, label("a")).unwrap();
+    program[5] = Instruction::new_simple(InstructionId::MoveW).unwrap();
+    program[16] = Instruction::new_label(InstructionId::Label, label("f")).unwrap();
+    program[17] = Instruction::new_simple(InstructionId::Digg).unwrap();
+    program[18] = Instruction::new_simple(InstructionId::Return).unwrap();
+    program[32] = Instruction::new_label(InstructionId::Label, label("end")).unwrap();
+    program[33] = Instruction::new_simple(InstructionId::End).unwrap();
+
+    let expected = concat!(
+        "digraph program {\n",
+        "    node [shape=box, fontname=\"monospace\"];\n",
+        "    b0 [label=\"0:0:0  a:\\l0:0:1  CC_EMPTY\\l0:0:2  IF_GOTO end\\l\"];\n",
+        "    b1 [label=\"0:0:3  GOSUB f\\l\"];\n",
+        "    b2 [label=\"0:0:4  GOTO a\\l\"];\n",
+        "    b3 [label=\"0:0:5  MOVE_W\\l0:0:6  EMPTY x10\\l\", color=gray, fontcolor=gray];\n",
+        "    b4 [label=\"0:1:0  f:\\l0:1:1  DIGG\\l0:1:2  RETURN\\l\"];\n",
+        "    b6 [label=\"0:2:0  end:\\l0:2:1  END\\l\"];\n",
+        "    b0 -> b6 [label=\"conditional\", style=dashed, color=darkorange];\n",
+        "    b0 -> b1 [label=\"fall-through\"];\n",
+        "    b1 -> b4 [label=\"call\", color=blue];\n",
+        "    b1 -> b2 [label=\"return\", style=dotted, color=blue];\n",
+        "    b2 -> b0 [label=\"jump\", style=bold];\n",
+        "    b3 -> b4 [label=\"fall-through\"];\n",
+        "}\n",
+    );
+    assert_eq!(expected, to_dot(&program));
+}
+
+/// The execution starts over after the last cell.
+#[test]
+fn wrap_around() {
+    let mut program = Program::default();
+    program[0] = Instruction::new_simple(InstructionId::MoveW).unwrap();
+
+    let expected = concat!(
+        "digraph program {\n",
+        "    node [shape=box, fontname=\"monospace\"];\n",
+        "    b0 [label=\"0:0:0  MOVE_W\\l0:0:1  EMPTY x3071\\l\"];\n",
+        "    b0 -> b0 [label=\"restart\", style=bold, color=red];\n",
+        "}\n",
+    );
+    assert_eq!(expected, to_dot(&program));
+}
+
+/// Non-ASCII string literals are kept, quotes and backslashes are escaped.
+#[test]
+fn non_ascii_strings() {
+    let mut program = Program::default();
+    program[0] = Instruction::new_string(InstructionId::DebugSet, "фів".parse().unwrap()).unwrap();
+    program[1] = Instruction::new_string(InstructionId::DebugSet, "\"\\".parse().unwrap()).unwrap();
+    program[2] = Instruction::new_simple(InstructionId::End).unwrap();
+
+    let dot = to_dot(&program);
+    assert!(dot.contains("0:0:0  DEBUG_SET 'фів'\\l"), "{}", dot);
+    assert!(
+        dot.contains("0:0:1  DEBUG_SET '\\\"\\\\\\\\'\\l"),
+        "{}",
+        dot
+    );
+}
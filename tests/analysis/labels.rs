@@ -0,0 +1,83 @@
+use m3c::analysis::diagnostics::{Diagnostics, DuplicateLabel, UndefinedLabel, UnusedLabel};
+use m3c::analysis::labels::{Jump, LabelAnalysis};
+use m3c::formats::internal::{Instruction, InstructionId, Program};
+
+use crate::common::{self, at, label};
+
+/// The `LITERALS` fixture: jumps to `abc`, `zxc`, `s12`, ... which aren't defined, and labels
+/// ``, `hi`, `012` which aren't used.
+#[test]
+fn literals() {
+    let program = common::internal::literals();
+
+    let analysis = LabelAnalysis::new(&program);
+
+    assert_eq!(
+        vec![
+            (label(""), at(0)),
+            (label("hi"), at(1)),
+            (label("012"), at(2))
+        ],
+        analysis.labels()
+    );
+    assert!(analysis.jumps().iter().all(|jump| jump.target.is_none()));
+    assert_eq!(
+        &[
+            Diagnostics::from(UnusedLabel::new(at(0), label(""))),
+            UnusedLabel::new(at(1), label("hi")).into()
+        ],
+        &analysis.diagnostics()[..2]
+    );
+    assert_eq!(
+        Diagnostics::from(UndefinedLabel::new(at(3), label("abc"))),
+        analysis.diagnostics()[3]
+    );
+}
+
+#[test]
+fn resolution() {
+    let mut program = Program::default();
+    program[0] = Instruction::new_label(InstructionId::GoSub, label("f")).unwrap();
+    program[1] = Instruction::new_label(InstructionId::IfGoTo, label("end")).unwrap();
+    program[2] = Instruction::new_label(InstructionId::Label, label("f")).unwrap();
+    program[3] = Instruction::new_simple(InstructionId::MoveW).unwrap();
+    program[20] = Instruction::new_label(InstructionId::Label, label("end")).unwrap();
+    program[30] = Instruction::new_label(InstructionId::Label, label("f")).unwrap();
+    program[40] = Instruction::new_label(InstructionId::OnResp, label("x")).unwrap();
+
+    let analysis = LabelAnalysis::new(&program);
+
+    assert_eq!(
+        &[
+            Jump {
+                position: at(0),
+                id: InstructionId::GoSub,
+                label: label("f"),
+                target: Some(at(2)),
+            },
+            Jump {
+                position: at(1),
+                id: InstructionId::IfGoTo,
+                label: label("end"),
+                target: Some(at(20)),
+            },
+            Jump {
+                position: at(40),
+                id: InstructionId::OnResp,
+                label: label("x"),
+                target: None,
+            },
+        ],
+        analysis.jumps()
+    );
+    assert_eq!(Some(at(20)), analysis.target(at(1)));
+    assert_eq!(None, analysis.target(at(3)));
+    assert_eq!(None, analysis.target(at(40)));
+    assert_eq!(
+        &[
+            Diagnostics::from(DuplicateLabel::new(at(30), at(2), label("f"))),
+            UndefinedLabel::new(at(40), label("x")).into(),
+        ],
+        analysis.diagnostics()
+    );
+}
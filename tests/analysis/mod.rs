@@ -0,0 +1,3 @@
+mod cfg;
+mod dot;
+mod labels;
@@ -0,0 +1,235 @@
+//! Tests for the `m3c` command-line tool.
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+
+use m3c::serialization::custom::binary::share;
+
+use crate::common;
+
+/// Runs `m3c` with the given `args` and `stdin`.
+fn m3c(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_m3c"))
+        .args(args)
+        .env("NO_COLOR", "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // `m3c` may exit (e.g. on usage errors) before reading stdin, so the pipe can be closed
+    _ = child.stdin.take().unwrap().write_all(stdin.as_bytes());
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn convert_round_trip() {
+    let given_string = common::native::new::LITERALS;
+
+    let asm = m3c(&["convert", "--to", "asm"], given_string);
+    assert!(asm.status.success());
+
+    let ntf = m3c(
+        &["convert", "--from", "asm", "--to=ntf"],
+        &String::from_utf8(asm.stdout).unwrap(),
+    );
+    assert!(ntf.status.success());
+    assert_eq!(given_string.as_bytes(), &ntf.stdout[..]);
+}
+
+#[test]
+fn convert_share() {
+    let given_string = common::native::new::LITERALS;
+
+    let share = m3c(&["convert", "--to", "share"], given_string);
+    assert!(share.status.success());
+    let code = String::from_utf8(share.stdout).unwrap();
+    assert!(code
+        .trim_end()
+        .bytes()
+        .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_'));
+
+    let ntf = m3c(&["convert", "--from", "share", "--to", "ntf"], &code);
+    assert!(ntf.status.success());
+    assert_eq!(given_string.as_bytes(), &ntf.stdout[..]);
+
+    let damaged = m3c(&["convert", "--from", "share", "--to", "ntf"], &code[1..]);
+    assert_eq!(Some(1), damaged.status.code());
+    assert!(String::from_utf8(damaged.stderr)
+        .unwrap()
+        .starts_with("error: illegal share code"));
+}
+
+#[test]
+fn convert_fails_on_errors() {
+    let output = m3c(&["convert", "--to", "asm"], "$^W]");
+
+    assert_eq!(Some(1), output.status.code());
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("error[N02]: unknown token found"));
+}
+
+#[test]
+fn convert_fails_without_spelling() {
+    let output = m3c(
+        &["convert", "--from", "asm", "--to", "ntf"],
+        "MOVE_W\nRESTART\nLAST\n",
+    );
+
+    assert_eq!(Some(1), output.status.code());
+    assert!(output.stdout.is_empty());
+    assert_eq!(
+        "error: New Text format has no spelling of the instruction id 200 at 0:0:1\n",
+        String::from_utf8(output.stderr).unwrap()
+    );
+}
+
+/// Unknown ids are kept by share codes and reported when converted to New Text format.
+#[test]
+fn convert_fails_on_unknown_ids() {
+    let code = share::encode(&common::internal::unknown());
+
+    let output = m3c(&["convert", "--from", "share", "--to", "ntf"], &code);
+    assert_eq!(Some(1), output.status.code());
+    assert!(output.stdout.is_empty());
+    assert_eq!(
+        "error: New Text format has no spelling of the instruction id 34 at 0:0:1\n",
+        String::from_utf8(output.stderr).unwrap()
+    );
+
+    let output = m3c(&["convert", "--from", "share", "--to", "asm"], &code);
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout).unwrap().contains("34\n"));
+}
+
+#[test]
+fn check() {
+    let output = m3c(&["check"], "$^W^w");
+
+    assert_eq!(Some(1), output.status.code());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("0 | $^W^w\n  |    ^\n  = help: did you mean `^W`?\n"));
+    assert!(stdout.ends_with("1 error(s), 0 warning(s)\n"));
+}
+
+#[test]
+fn check_only_warnings() {
+    let output = m3c(&["check", "--json"], "$>abcd|");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with(r#"[{"id":"N05","severity":"warning","#));
+}
+
+#[test]
+fn check_clean() {
+    let output = m3c(&["check"], common::native::new::ALL_SIMPLE);
+
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn fmt() {
+    let output = m3c(&["fmt"], "$^W^S>abcd|");
+
+    assert!(output.status.success());
+    assert_eq!(b"$^W^S>abc|", &output.stdout[..]);
+}
+
+#[test]
+fn dump() {
+    let output = m3c(&["dump"], "$^W_^D\n>ab|");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let mut lines = stdout.lines();
+    assert_eq!(Some("page 0"), lines.next());
+    assert_eq!(
+        Some("   | 0    1    2    3    4    5    6    7    8    9    10   11   12   13   14   15"),
+        lines.next()
+    );
+    assert_eq!(
+        Some(" 0 | ^W   .    .    .    ^D   ."),
+        lines.next().map(|line| &line[..31])
+    );
+    assert_eq!(Some(" 1 | >ab| ."), lines.next().map(|line| &line[..11]));
+    assert_eq!(12, stdout.lines().count() - 2);
+}
+
+#[test]
+fn dump_non_ascii() {
+    let output = m3c(&["dump"], "$!{фів}^W");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    // the columns are as wide as the widest cell in chars
+    assert_eq!(
+        Some("   | 0      1      2"),
+        stdout.lines().nth(1).map(|line| &line[..20])
+    );
+    assert!(stdout
+        .lines()
+        .nth(2)
+        .unwrap()
+        .starts_with(" 0 | !{фів} ^W     .      ."));
+}
+
+#[test]
+fn dot() {
+    let output = m3c(&["dot"], "$^W~");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("digraph program {\n"));
+    assert!(stdout.contains(r#"b0 [label="0:0:0  MOVE_W\l0:0:1  EMPTY x3071\l"];"#));
+}
+
+#[test]
+fn debug() {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let (program, map) = (dir.join("debug.m3a"), dir.join("debug.map"));
+    fs::write(
+        &program,
+        "GOSUB a\nEND\na:\nMOVE_S\nDEBUG_BREAK 'x'\nRETURN\n",
+    )
+    .unwrap();
+    fs::write(&map, "..\n.*\n").unwrap();
+    let program = program.to_str().unwrap();
+    let map = map.to_str().unwrap();
+
+    let commands = "break x\nc\nbt\nworld\nstep 2\nbogus\nc\n";
+    let output = m3c(&["debug", "--world", map, "--at", "0,0", program], commands);
+
+    assert!(output.status.success());
+    assert_eq!(
+        concat!(
+            "0:0:0  :>a>  (tick 0)\n",
+            "breakpoint at `x`\n",
+            "breakpoint `x`\n",
+            "0:0:4  !{x}  (tick 1)\n",
+            "GOSUB:   0:0:1\nGOSUBF:  -\nGOSUB1:  -\nON_RESP: -\n",
+            "..\n@*\nrobot at (0, 1) facing S, 100 hp, 0 crystals\n",
+            "0:0:1  #E  (tick 1)\n",
+            "unknown command `bogus`, type `help`\n",
+            "the program is finished\n",
+        ),
+        String::from_utf8(output.stdout).unwrap()
+    );
+
+    // commands are read from stdin
+    assert_eq!(Some(2), m3c(&["debug"], "$^W").status.code());
+}
+
+#[test]
+fn usage_errors() {
+    assert_eq!(Some(2), m3c(&[], "").status.code());
+    assert_eq!(Some(2), m3c(&["bogus"], "").status.code());
+    assert_eq!(Some(2), m3c(&["convert"], "$").status.code());
+    assert_eq!(Some(2), m3c(&["fmt", "--json"], "$").status.code());
+    assert_eq!(Some(2), m3c(&["convert", "--to", "xml"], "$").status.code());
+}
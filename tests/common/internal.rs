@@ -35,6 +35,38 @@ pub fn moves_and_looks() -> Program {
     program
 }
 
+/// Returns a program with unknown ids between known ones.
+pub fn unknown() -> Program {
+    let mut program = Program::default();
+    program[0] = Instruction::new_simple(InstructionId::MoveW).unwrap();
+    program[1] = Instruction::new_simple(InstructionId::Unknown(34)).unwrap();
+    program[2] = Instruction::new_simple(InstructionId::Unknown(250)).unwrap();
+    program[3] = Instruction::new_simple(InstructionId::MoveS).unwrap();
+    program[20] = Instruction::new_simple(InstructionId::Unknown(199)).unwrap();
+    program
+}
+
+/// Returns a program with the known ids New Text format has no spelling of.
+pub fn without_ntf() -> Program {
+    let mut program = Program::default();
+    program[0] = Instruction::new_simple(InstructionId::MoveW).unwrap();
+    program[1] = Instruction::new_simple(InstructionId::Last).unwrap();
+    program[2] = Instruction::new_simple(InstructionId::MoveS).unwrap();
+    program[20] = Instruction::new_simple(InstructionId::Restart).unwrap();
+    program
+}
+
+/// Returns a program with non-ASCII string literals and ones with chars which need escape.
+pub fn strings() -> Program {
+    let mut program = Program::default();
+    program[0] = Instruction::new_string(InstructionId::DebugSet, "фів".parse().unwrap()).unwrap();
+    program[1] =
+        Instruction::new_string(InstructionId::DebugBreak, "a b".parse().unwrap()).unwrap();
+    program[2] = Instruction::new_string(InstructionId::DebugSet, "}'\\".parse().unwrap()).unwrap();
+    program[3] = Instruction::new_string(InstructionId::DebugBreak, "".parse().unwrap()).unwrap();
+    program
+}
+
 /// Returns a program which contains all `Simple` kind instructions.
 pub fn all_simple() -> Program {
     let mut program = Program::default();
@@ -314,12 +346,12 @@ pub fn literals() -> Program {
     .unwrap();
     program[13] = Instruction::new_string(
         InstructionId::DebugSet,
-        StringLiteral::new_from_array([b'd', b's', b't', 0]).unwrap(),
+        StringLiteral::new_from_array([b'd', b's', b't', 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]).unwrap(),
     )
     .unwrap();
     program[14] = Instruction::new_string(
         InstructionId::DebugBreak,
-        StringLiteral::new_from_array([b'b', b'p', 0, 0]).unwrap(),
+        StringLiteral::new_from_array([b'b', b'p', 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]).unwrap(),
     )
     .unwrap();
     program
@@ -2,6 +2,33 @@
 
 pub mod internal;
 
+use m3c::formats::internal::literals::{LabelIdentifierLiteral, VariableIdentifierLiteral};
+use m3c::formats::internal::{InstructionPosition, Program};
+use m3c::serialization::custom::assembly::Deserializer;
+
+/// Parses a program in assembly-like format.
+pub fn asm(source: &str) -> Program {
+    let mut program = Program::default();
+    let diagnostics = Deserializer::new(source).deserialize(&mut program);
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    program
+}
+
+/// Returns the position with the given "flat index".
+pub fn at(index: usize) -> InstructionPosition {
+    InstructionPosition::try_from(index).unwrap()
+}
+
+/// Parses a label literal, panics if it's illegal.
+pub fn label(s: &str) -> LabelIdentifierLiteral {
+    s.parse().unwrap()
+}
+
+/// Parses a variable literal, panics if it's illegal.
+pub fn var(s: &str) -> VariableIdentifierLiteral {
+    s.parse().unwrap()
+}
+
 pub mod native {
     pub mod new {
         /// Totally empty string.
@@ -51,6 +78,9 @@ pub mod native {
             "^S~\n~\n~\n^S~\n~\n~\n~\n~\n~\n^S"
         );
 
+        /// A string with non-ASCII string literals and ones with escaped chars.
+        pub static STRINGS: &str = r"${фів}!{a b}{\}\'\\}!{}";
+
         /// A string for testing all not-`Simple` kind instruction.
         pub static LITERALS: &str = concat!(
             "$",
@@ -1,4 +1,8 @@
 //! The integration (and just big) tests.
 
+mod analysis;
+mod cli;
 mod common;
+mod interpreter;
 mod serialization;
+mod simulation;
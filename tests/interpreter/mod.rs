@@ -0,0 +1,199 @@
+use m3c::formats::internal::{InstructionId, InstructionPosition, Program};
+use m3c::interpreter::{BoolMode, Environment, Interpreter, Step};
+
+use crate::common::{asm, var};
+
+/// Records actions and checks, answers checks with `CcEmpty` being true and responds when asked.
+#[derive(Default)]
+struct Script {
+    actions: Vec<InstructionId>,
+    checks: Vec<(InstructionId, InstructionId)>,
+    respond: bool,
+}
+
+impl Environment for Script {
+    fn act(&mut self, id: InstructionId) -> bool {
+        self.actions.push(id);
+        // modes don't take time
+        id != InstructionId::ModeAgrOn
+    }
+    fn check(&mut self, id: InstructionId, selector: InstructionId) -> bool {
+        self.checks.push((id, selector));
+        id == InstructionId::CcEmpty
+    }
+    fn poll_response(&mut self) -> bool {
+        std::mem::take(&mut self.respond)
+    }
+}
+
+/// Runs the program until it's finished, returning the actions.
+fn run(program: &Program) -> Vec<InstructionId> {
+    let mut interpreter = Interpreter::new(program);
+    let mut script = Script::default();
+    for _ in 0..1000 {
+        if interpreter.tick(&mut script) == Step::Finished {
+            return script.actions;
+        }
+    }
+    panic!("the program isn't finished");
+}
+
+#[test]
+fn steps_and_ticks() {
+    let program = asm("MODE_AGR_ON\nMOVE_W\na:\nMOVE_S\nEND");
+    let mut interpreter = Interpreter::new(&program);
+    let mut script = Script::default();
+
+    assert_eq!(Step::Control, interpreter.step(&mut script));
+    assert_eq!(Step::Action, interpreter.step(&mut script));
+    assert_eq!(
+        InstructionPosition::new(0, 0, 2).unwrap(),
+        interpreter.position()
+    );
+    assert_eq!(Step::Action, interpreter.tick(&mut script));
+    assert_eq!(Step::Finished, interpreter.tick(&mut script));
+    assert!(interpreter.is_finished());
+    assert_eq!(Step::Finished, interpreter.step(&mut script));
+    assert_eq!(
+        vec![
+            InstructionId::ModeAgrOn,
+            InstructionId::MoveW,
+            InstructionId::MoveS
+        ],
+        script.actions
+    );
+}
+
+#[test]
+fn wraps_and_restarts() {
+    // an empty program idles
+    let program = Program::default();
+    let mut interpreter = Interpreter::new(&program);
+    assert_eq!(Step::Control, interpreter.tick(&mut Script::default()));
+    assert_eq!(InstructionPosition::default(), interpreter.position());
+
+    let program = asm("GOSUB a\na:\nMOVE_W\nBACK");
+    let mut interpreter = Interpreter::new(&program);
+    let mut script = Script::default();
+    interpreter.tick(&mut script);
+    assert_eq!(1, interpreter.calls().len());
+    interpreter.tick(&mut script);
+    // `BACK` forgot the call
+    assert_eq!(1, interpreter.calls().len());
+    assert_eq!(2, script.actions.len());
+
+    // `RESTART` works the same way and `LAST` does nothing
+    let program = asm("GOSUB a\na:\nLAST\nMOVE_W\nRESTART");
+    let mut interpreter = Interpreter::new(&program);
+    let mut script = Script::default();
+    interpreter.tick(&mut script);
+    assert_eq!(1, interpreter.calls().len());
+    interpreter.tick(&mut script);
+    assert_eq!(1, interpreter.calls().len());
+    assert_eq!(vec![InstructionId::MoveW; 2], script.actions);
+}
+
+#[test]
+fn subroutines() {
+    let program = asm(concat!(
+        "GOSUB a\nMOVE_W\nGOSUBF b\nMOVE_A\nGOSUB1 c\nMOVE_S\nEND\n",
+        "a:\nMOVE_D\nGOSUB b\nRETURN\n",
+        "b:\nDIGG\nRETURNF\nRETURN\n",
+        "c:\nGOSUB1 d\nRETURN1\n",
+        "d:\nROTATE_CW\nRETURN1\nEND",
+    ));
+
+    assert_eq!(
+        vec![
+            // `GOSUB a`, `GOSUB b` returns with `RETURN` after `RETURNF` does nothing
+            InstructionId::MoveD,
+            InstructionId::Digg,
+            InstructionId::MoveW,
+            // `GOSUBF b`
+            InstructionId::Digg,
+            InstructionId::MoveA,
+            // `GOSUB1` remembers the last call only, the second `RETURN1` does nothing
+            InstructionId::RotateCw,
+            InstructionId::RotateCw,
+        ],
+        run(&program)
+    );
+}
+
+#[test]
+fn call_depth() {
+    let program = asm("a:\nGOSUB a");
+    let mut interpreter = Interpreter::new(&program);
+    let mut script = Script::default();
+    interpreter.tick(&mut script);
+
+    assert_eq!(Interpreter::MAX_CALL_DEPTH, interpreter.calls().len());
+    assert!(interpreter.calls_f().is_empty());
+    assert_eq!(None, interpreter.call1());
+}
+
+#[test]
+fn conditions() {
+    let program = asm(concat!(
+        "CELL_W\nCC_EMPTY\nCC_SAND\nIF_GOTO a\nMOVE_W\na:\n",
+        "BOOLMODE_AND\nCC_EMPTY\nCC_SAND\nIF_NOT_GOTO b\nMOVE_A\nb:\n",
+        "IF_GOTO c\nMOVE_S\nc:\nEND",
+    ));
+    let mut interpreter = Interpreter::new(&program);
+    let mut script = Script::default();
+    while interpreter.tick(&mut script) != Step::Finished {}
+
+    assert_eq!(vec![InstructionId::MoveS], script.actions);
+    assert_eq!(
+        (InstructionId::CcEmpty, InstructionId::CellW),
+        script.checks[0]
+    );
+    assert_eq!(BoolMode::And, interpreter.bool_mode());
+    assert_eq!(None, interpreter.condition());
+}
+
+#[test]
+fn variables() {
+    let program = asm("VAR_MORE x, 2\nVAR_EQUAL y, 0\nIF_GOTO a\nMOVE_W\na:\nEND");
+    assert_eq!(Vec::<InstructionId>::new(), run(&program));
+
+    let program = asm("BOOLMODE_AND\nVAR_MORE x, 2\nVAR_LESS x, 5\nIF_GOTO a\nMOVE_W\na:\nEND");
+    let mut interpreter = Interpreter::new(&program);
+    let mut script = Script::default();
+    interpreter.set_variable(var("x"), 3);
+    while interpreter.tick(&mut script) != Step::Finished {}
+
+    assert!(script.actions.is_empty());
+    assert_eq!(3, interpreter.variable(var("x")));
+    assert_eq!(0, interpreter.variable(var("y")));
+    assert_eq!(
+        vec![(var("x"), 3)],
+        interpreter.variables().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn responses() {
+    let program = asm("ON_RESP h\na:\nMOVE_W\nGOTO a\nh:\nDIGG\nRETURN");
+    let mut interpreter = Interpreter::new(&program);
+    let mut script = Script::default();
+
+    interpreter.tick(&mut script);
+    assert_eq!(
+        Some(InstructionPosition::new(0, 0, 4).unwrap()),
+        interpreter.handler()
+    );
+    script.respond = true;
+    interpreter.tick(&mut script);
+    interpreter.tick(&mut script);
+
+    assert_eq!(
+        vec![
+            InstructionId::MoveW,
+            InstructionId::Digg,
+            InstructionId::MoveW
+        ],
+        script.actions
+    );
+    assert!(interpreter.calls().is_empty());
+}
@@ -0,0 +1,224 @@
+use m3c::formats::custom::assembly::diagnostics::{
+    Diagnostics, IllegalLiteral, IllegalOperand, ProgramOverflow, UnknownMnemonic,
+};
+use m3c::formats::internal::{Instruction, InstructionId, Program};
+use m3c::serialization::custom::assembly::{Deserializer, ParseInstructionError, Serializer};
+use m3c::utils::CharPosition;
+
+use crate::common;
+
+/// Serializes the given program with the [`Serializer`] and deserializes it back.
+fn round_trip(given_program: &Program) {
+    let mut s = String::new();
+    Serializer::new(given_program).serialize_to_string(&mut s, "    ");
+
+    let mut actual_program = common::internal::empty();
+
+    let mut de = Deserializer::new(&s);
+    let actual_diagnostics = de.deserialize(&mut actual_program);
+
+    assert_eq!(*given_program, actual_program);
+    assert_eq!(Vec::<Diagnostics>::new(), actual_diagnostics);
+}
+
+#[test]
+fn round_trip_simple_instructions() {
+    round_trip(&common::internal::all_simple());
+}
+
+#[test]
+fn round_trip_commands() {
+    round_trip(&common::internal::commands());
+}
+
+#[test]
+fn round_trip_literals() {
+    round_trip(&common::internal::literals());
+}
+
+/// Unknown ids are written as their values.
+#[test]
+fn round_trip_unknown() {
+    let mut s = String::new();
+    Serializer::new(&common::internal::unknown()).serialize_to_string(&mut s, "");
+    assert!(s.contains("\n34\n250\n"), "{:?}", s);
+
+    round_trip(&common::internal::unknown());
+}
+
+#[test]
+fn round_trip_without_ntf() {
+    round_trip(&common::internal::without_ntf());
+}
+
+/// Strings are quoted and escaped.
+#[test]
+fn round_trip_strings() {
+    let mut s = String::new();
+    Serializer::new(&common::internal::strings()).serialize_to_string(&mut s, "");
+    assert!(s.starts_with("DEBUG_SET 'фів'\n"), "{:?}", s);
+    assert!(s.contains(r"DEBUG_SET '\}\'\\'"), "{:?}", s);
+
+    round_trip(&common::internal::strings());
+}
+
+/// The output of `serialize_to_writer` must be readable too.
+#[test]
+fn round_trip_writer() {
+    let given_program = common::internal::literals();
+
+    let mut buf = vec![];
+    Serializer::new(&given_program)
+        .serialize_to_writer(&mut buf, "\t")
+        .unwrap();
+    let s = String::from_utf8(buf).unwrap();
+
+    let mut actual_program = common::internal::empty();
+
+    let mut de = Deserializer::new(&s);
+    let actual_diagnostics = de.deserialize(&mut actual_program);
+
+    assert_eq!(given_program, actual_program);
+    assert_eq!(Vec::<Diagnostics>::new(), actual_diagnostics);
+}
+
+/// Erroneous lines are reported and still occupy their cells.
+///
+/// ```text
+/// 0         1         2
+/// 012345678901234567890
+/// MOVE_X
+/// GOTO ab cd ; comment
+/// VAR_LESS abc, 100000
+/// abcd:
+/// MOVE_S
+/// ```
+#[test]
+fn diagnostics() {
+    let given_string = "MOVE_X\nGOTO ab cd ; comment\nVAR_LESS abc, 100000\nabcd:\nMOVE_S";
+
+    let mut expected_program = common::internal::empty();
+    expected_program[4] = Instruction::new_simple(InstructionId::MoveS).unwrap();
+    let expected_diagnostics: Vec<Diagnostics> = vec![
+        UnknownMnemonic::new(
+            CharPosition {
+                index: 0,
+                line: 0,
+                column: 0,
+            },
+            CharPosition {
+                index: 5,
+                line: 0,
+                column: 5,
+            },
+        )
+        .into(),
+        IllegalOperand::new(
+            CharPosition {
+                index: 12,
+                line: 1,
+                column: 5,
+            },
+            CharPosition {
+                index: 16,
+                line: 1,
+                column: 9,
+            },
+        )
+        .into(),
+        IllegalLiteral::new(
+            CharPosition {
+                index: 42,
+                line: 2,
+                column: 14,
+            },
+            CharPosition {
+                index: 47,
+                line: 2,
+                column: 19,
+            },
+        )
+        .into(),
+        IllegalLiteral::new(
+            CharPosition {
+                index: 49,
+                line: 3,
+                column: 0,
+            },
+            CharPosition {
+                index: 52,
+                line: 3,
+                column: 3,
+            },
+        )
+        .into(),
+    ];
+
+    let mut actual_program = common::internal::empty();
+
+    let mut de = Deserializer::new(given_string);
+    let actual_diagnostics = de.deserialize(&mut actual_program);
+
+    assert_eq!(expected_program, actual_program);
+    assert_eq!(expected_diagnostics, actual_diagnostics);
+}
+
+/// Instructions that don't fit into the program are reported once.
+#[test]
+fn program_overflow() {
+    let given_string = "BACK\n".repeat(Program::INSTRUCTIONS_PER_PROGRAM + 2);
+
+    let mut actual_program = common::internal::empty();
+
+    let mut de = Deserializer::new(&given_string);
+    let actual_diagnostics = de.deserialize(&mut actual_program);
+
+    let expected_diagnostics: Vec<Diagnostics> = vec![ProgramOverflow::new(CharPosition {
+        index: 5 * Program::INSTRUCTIONS_PER_PROGRAM,
+        line: Program::INSTRUCTIONS_PER_PROGRAM,
+        column: 0,
+    })
+    .into()];
+
+    assert_eq!(expected_diagnostics, actual_diagnostics);
+    assert_eq!(
+        InstructionId::Back,
+        actual_program[Program::INSTRUCTIONS_PER_PROGRAM - 1].id()
+    );
+}
+
+/// Every instruction is parsed back from its `Display`.
+#[test]
+fn instruction_from_str() {
+    for program in [
+        common::internal::all_simple(),
+        common::internal::literals(),
+        common::internal::unknown(),
+        common::internal::without_ntf(),
+        common::internal::strings(),
+    ] {
+        for i in 0..Program::INSTRUCTIONS_PER_PROGRAM {
+            let s = program[i].to_string();
+            assert_eq!(Ok(program[i]), s.parse::<Instruction>(), "{}", s);
+        }
+    }
+
+    assert_eq!(
+        Ok(Instruction::new_simple(InstructionId::MoveW).unwrap()),
+        Instruction::try_from("  MOVE_W ; go")
+    );
+    for (s, error) in [
+        ("", ParseInstructionError::Empty),
+        (" ; a comment", ParseInstructionError::Empty),
+        ("MOVE_Q", ParseInstructionError::UnknownMnemonic(0)),
+        ("MOVE_W\nMOVE_S", ParseInstructionError::IllegalOperand(6)),
+        ("GOTO 'a'", ParseInstructionError::IllegalOperand(5)),
+        ("VAR_MORE abcd, 1", ParseInstructionError::IllegalLiteral(9)),
+        (
+            "VAR_MORE x, 100000",
+            ParseInstructionError::IllegalLiteral(12),
+        ),
+    ] {
+        assert_eq!(Err(error), s.parse::<Instruction>(), "{:?}", s);
+    }
+}
@@ -0,0 +1,147 @@
+use m3c::formats::internal::{Instruction, InstructionId, Program};
+use m3c::serialization::custom::binary::share::{self, DecodeError};
+use m3c::serialization::custom::binary::{DeserializeError, Deserializer, Serializer};
+
+use crate::common;
+
+fn deserialize(bytes: &[u8]) -> Result<Program, DeserializeError> {
+    let mut program = Program::default();
+    Deserializer::new(bytes).deserialize(&mut program)?;
+    Ok(program)
+}
+
+#[test]
+fn round_trip() {
+    for program in [
+        common::internal::empty(),
+        common::internal::only_move_w(),
+        common::internal::all_simple(),
+        common::internal::commands(),
+        common::internal::literals(),
+        common::internal::unknown(),
+        common::internal::without_ntf(),
+        common::internal::strings(),
+    ] {
+        let bytes = Serializer::new(&program).to_bytes();
+        assert_eq!(program, deserialize(&bytes).unwrap());
+
+        let code = share::encode(&program);
+        let mut decoded = Program::default();
+        share::decode(&code, &mut decoded).unwrap();
+        assert_eq!(program, decoded);
+    }
+}
+
+#[test]
+fn compact() {
+    assert_eq!(
+        b"M3C\x01\x00\x00",
+        &Serializer::new(&common::internal::empty()).to_bytes()[..]
+    );
+
+    let mut program = Program::default();
+    program[Program::INSTRUCTIONS_PER_PROGRAM - 1] =
+        Instruction::new_simple(InstructionId::End).unwrap();
+    assert_eq!(
+        b"M3C\x01\x00\x0c\xff\xff\x0b\x03",
+        &Serializer::new(&program).to_bytes()[..]
+    );
+
+    let literals = Serializer::new(&common::internal::literals()).to_bytes();
+    assert!(literals.len() < 200, "{} bytes", literals.len());
+}
+
+#[test]
+fn deserialize_errors() {
+    assert_eq!(Err(DeserializeError::NoMagic), deserialize(b"M3"));
+    assert_eq!(
+        Err(DeserializeError::NoMagic),
+        deserialize(b"NTF\x01\x00\x00")
+    );
+    assert_eq!(
+        Err(DeserializeError::UnsupportedVersion(2)),
+        deserialize(b"M3C\x02\x00\x00")
+    );
+    assert_eq!(
+        Err(DeserializeError::UnexpectedEnd),
+        deserialize(b"M3C\x01\x02\x00\x04")
+    );
+    // `GOTO` with a label of 4 chars
+    assert_eq!(
+        Err(DeserializeError::IllegalLiteral(7)),
+        deserialize(b"M3C\x01\x01\x00\x18\x04abcd")
+    );
+    // `GOTO` with an illegal char
+    assert_eq!(
+        Err(DeserializeError::IllegalLiteral(7)),
+        deserialize(b"M3C\x01\x01\x00\x18\x01-")
+    );
+    // `VAR_MORE x, 100000`
+    assert_eq!(
+        Err(DeserializeError::IllegalLiteral(9)),
+        deserialize(b"M3C\x01\x01\x00\x77\x01x\xa0\x86\x01\x00")
+    );
+    assert_eq!(
+        Err(DeserializeError::TooManyCells),
+        deserialize(b"M3C\x01\x02\x00\xff\x03\x00")
+    );
+    assert_eq!(
+        Err(DeserializeError::TooManyCells),
+        deserialize(b"M3C\x01\x01\x0c")
+    );
+    assert_eq!(
+        Err(DeserializeError::TrailingBytes(7)),
+        deserialize(b"M3C\x01\x01\x00\x04\x04")
+    );
+}
+
+#[test]
+fn unknown_ids() {
+    let mut program = Program::default();
+    program[0] = Instruction::new_simple(InstructionId::Unknown(34)).unwrap();
+    program[1] = Instruction::new_simple(InstructionId::Unknown(254)).unwrap();
+    program[2] = Instruction::new_simple(InstructionId::Unknown(255)).unwrap();
+    program[3] = Instruction::new_simple(InstructionId::End).unwrap();
+
+    let bytes = Serializer::new(&program).to_bytes();
+    // the value of `EMPTY_RUN` is escaped as a run of 0 cells
+    assert_eq!(b"M3C\x01\x04\x00\x22\xfe\xff\x00\x00\x03", &bytes[..]);
+    assert_eq!(program, deserialize(&bytes).unwrap());
+
+    program[3] = Instruction::default();
+    let bytes = Serializer::new(&program).to_bytes();
+    assert_eq!(b"M3C\x01\x03\x00\x22\xfe\xff\x00\x00", &bytes[..]);
+    assert_eq!(program, deserialize(&bytes).unwrap());
+}
+
+#[test]
+fn damaged_share_codes() {
+    let code = share::encode(&common::internal::commands());
+    let mut program = common::internal::only_move_w();
+
+    let mut damaged = code.clone().into_bytes();
+    damaged[10] = if damaged[10] == b'A' { b'B' } else { b'A' };
+    let damaged = String::from_utf8(damaged).unwrap();
+    assert_eq!(
+        Err(DecodeError::ChecksumMismatch),
+        share::decode(&damaged, &mut program)
+    );
+    assert_eq!(
+        Err(DecodeError::IllegalChar(3)),
+        share::decode("TTN+AQEABJseVRY", &mut program)
+    );
+    assert_eq!(
+        Err(DecodeError::ChecksumMismatch),
+        share::decode(&code[..code.len() - 4], &mut program)
+    );
+    assert_eq!(
+        Err(DecodeError::IllegalLength),
+        share::decode("TTN", &mut program)
+    );
+    // the program isn't changed on errors
+    assert_eq!(common::internal::only_move_w(), program);
+
+    // whitespace around is ignored
+    share::decode(&format!("  {}\n", code), &mut program).unwrap();
+    assert_eq!(common::internal::commands(), program);
+}
@@ -1 +1,4 @@
+mod custom;
 mod native;
+#[cfg(feature = "serde")]
+mod serde;
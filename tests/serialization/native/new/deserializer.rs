@@ -1,5 +1,14 @@
-use m3c::formats::native::new::diagnostics::{Diagnostics, NoMagicFound, UnknownToken};
-use m3c::serialization::native::new::{TextFormatDeserializer, TextFormatDeserializerV2};
+use m3c::formats::internal::literals::{
+    LabelIdentifierLiteral, StringLiteral, VariableIdentifierLiteral, VariableValueLiteral,
+};
+use m3c::formats::internal::{Instruction, InstructionId, InstructionPosition, Program};
+use m3c::formats::native::new::diagnostics::{
+    Diagnostics, LiteralTooLong, MalformedRowSkip, NoMagicFound, ProgramOverflow, RowOverflow,
+    Suggestion, UnknownToken, UnterminatedLiteral, VariableValueOutOfRange,
+};
+use m3c::serialization::native::new::{
+    DeserializeErrors, TextFormatDeserializer, TextFormatDeserializerV2, TokenSpan,
+};
 use m3c::utils::CharPosition;
 
 use crate::common;
@@ -31,6 +40,20 @@ fn deserialize_not_all_commands() {
     assert_eq!(expected_program, actual_program);
 }
 
+/// Tests all commands, including row skips (`.` and `.N.`).
+#[test]
+fn deserialize_commands() {
+    let given_string = common::native::new::COMMANDS;
+    let expected_program = common::internal::commands();
+
+    let mut actual_program = common::internal::empty();
+
+    let mut de = TextFormatDeserializer::new_from_str(&mut actual_program, given_string);
+    de.deserialize().unwrap();
+
+    assert_eq!(expected_program, actual_program);
+}
+
 #[test]
 fn deserialize_literals() {
     let given_string = common::native::new::LITERALS;
@@ -44,6 +67,27 @@ fn deserialize_literals() {
     assert_eq!(expected_program, actual_program);
 }
 
+/// Too long literals are errors, not silently truncated.
+#[test]
+fn deserialize_literal_too_long() {
+    for (given_string, index, length) in [
+        ("$>abcd|", 2, 4),
+        ("$^W!{abcde}", 5, 5),
+        ("$(va01=1)", 2, 4),
+        ("$(va=000007)", 5, 6),
+    ] {
+        let mut actual_program = common::internal::empty();
+
+        let mut de = TextFormatDeserializer::new_from_str(&mut actual_program, given_string);
+        match de.deserialize() {
+            Err(DeserializeErrors::LiteralIsTooLong(e)) => {
+                assert_eq!((index, length), (e.index(), e.length()), "{}", given_string)
+            }
+            result => panic!("{}: {:?}", given_string, result),
+        }
+    }
+}
+
 /// Totally empty string without magic.
 #[test]
 fn deserialize_v2_empty_string() {
@@ -112,6 +156,114 @@ fn deserialize_v2_simple_instructions() {
     assert_eq!(expected_diagnostics, actual_diagnostics);
 }
 
+/// Tests all not `Simple` kind instuctions.
+#[test]
+fn deserialize_v2_literals() {
+    let given_string = common::native::new::LITERALS;
+
+    let expected_program = common::internal::literals();
+    let expected_diagnostics: Vec<Diagnostics> = vec![];
+
+    let mut actual_program = common::internal::empty();
+
+    let mut de = TextFormatDeserializerV2::new(given_string);
+    let actual_diagnostics = de.deserialize(&mut actual_program);
+
+    assert_eq!(expected_program, actual_program);
+    assert_eq!(expected_diagnostics, actual_diagnostics);
+}
+
+/// Tests commands (`\n`, `~`, `_`, ` `).
+#[test]
+fn deserialize_v2_not_all_commands() {
+    let given_string = common::native::new::NOT_ALL_COMMANDS;
+
+    let expected_program = common::internal::not_all_commands();
+    let expected_diagnostics: Vec<Diagnostics> = vec![];
+
+    let mut actual_program = common::internal::empty();
+
+    let mut de = TextFormatDeserializerV2::new(given_string);
+    let actual_diagnostics = de.deserialize(&mut actual_program);
+
+    assert_eq!(expected_program, actual_program);
+    assert_eq!(expected_diagnostics, actual_diagnostics);
+}
+
+/// Tests all commands, including row skips (`.` and `.N.`).
+#[test]
+fn deserialize_v2_commands() {
+    let given_string = common::native::new::COMMANDS;
+
+    let expected_program = common::internal::commands();
+    let expected_diagnostics: Vec<Diagnostics> = vec![];
+
+    let mut actual_program = common::internal::empty();
+
+    let mut de = TextFormatDeserializerV2::new(given_string);
+    let actual_diagnostics = de.deserialize(&mut actual_program);
+
+    assert_eq!(expected_program, actual_program);
+    assert_eq!(expected_diagnostics, actual_diagnostics);
+}
+
+/// A row skip count without the closing `.` is reported and ignored.
+///
+/// ```text
+/// 0123456789
+/// $.3^W.12.^S
+///  ^^   ^^
+/// ```
+#[test]
+fn deserialize_v2_malformed_row_skip() {
+    let given_string = "$.3^W.12.^S";
+
+    let mut expected_program = common::internal::empty();
+    expected_program[0] = Instruction::new_simple(InstructionId::MoveW).unwrap();
+    // `.` after `2` moves to the next row
+    expected_program[Program::INSTRUCTIONS_PER_ROW] =
+        Instruction::new_simple(InstructionId::MoveS).unwrap();
+    let expected_diagnostics: Vec<Diagnostics> = vec![
+        MalformedRowSkip::new(at(1), at(2)).into(),
+        MalformedRowSkip::new(at(5), at(6)).into(),
+        UnknownToken::new(at(7), at(7)).into(),
+    ];
+
+    let mut actual_program = common::internal::empty();
+
+    let mut de = TextFormatDeserializerV2::new(given_string);
+    let actual_diagnostics = de.deserialize(&mut actual_program);
+
+    assert_eq!(expected_program, actual_program);
+    assert_eq!(expected_diagnostics, actual_diagnostics);
+}
+
+/// A test for a literal-bearing token with an illegal char instead of the closing one.
+///
+/// ```text
+/// 0123456
+/// $>ab)^W
+///  ^^^^
+/// ```
+#[test]
+fn deserialize_v2_literal_with_illegal_continuation() {
+    let given_string = "$>ab)^W";
+
+    let expected_program = common::internal::only_move_w();
+    let expected_diagnostics: Vec<Diagnostics> = vec![
+        UnterminatedLiteral::new(at(1), at(3)).into(),
+        UnknownToken::new(at(4), at(4)).into(),
+    ];
+
+    let mut actual_program = common::internal::empty();
+
+    let mut de = TextFormatDeserializerV2::new(given_string);
+    let actual_diagnostics = de.deserialize(&mut actual_program);
+
+    assert_eq!(expected_program, actual_program);
+    assert_eq!(expected_diagnostics, actual_diagnostics);
+}
+
 /// Tests that Deserializer can go the next char if this char is illegal to start the token with.
 ///
 /// ```text
@@ -162,7 +314,8 @@ fn deserialize_v2_simple_instructions_with_illegal_start_chars() {
     assert_eq!(expected_diagnostics, actual_diagnostics);
 }
 
-/// A test for illegal token continuation ([`UnknownToken`]).
+/// A test for illegal token continuation ([`UnknownToken`]). `^a` is a letter case typo, so
+/// `^A` is suggested.
 ///
 /// ```text
 /// 01234567890123
@@ -175,19 +328,7 @@ fn deserialize_v2_unknown_token_continuation() {
 
     let expected_program = common::internal::moves_and_looks();
     let expected_diagnostics: Vec<Diagnostics> = vec![
-        UnknownToken::new(
-            CharPosition {
-                index: 3,
-                line: 0,
-                column: 3,
-            },
-            CharPosition {
-                index: 3,
-                line: 0,
-                column: 3,
-            },
-        )
-        .into(),
+        UnknownToken::new_with_suggestion(at(3), at(3), Suggestion::new(at(3), at(4), "^A")).into(),
         UnknownToken::new(
             CharPosition {
                 index: 7,
@@ -224,3 +365,299 @@ fn deserialize_v2_unknown_token_continuation() {
     assert_eq!(expected_program, actual_program);
     assert_eq!(expected_diagnostics, actual_diagnostics);
 }
+
+/// Illegal chars at the end of the string.
+///
+/// ```text
+/// 0123456
+/// $^W]]]]
+///    ^^^^
+/// ```
+#[test]
+fn deserialize_v2_trailing_illegal_chars() {
+    let given_string = "$^W]]]]";
+
+    let expected_program = common::internal::only_move_w();
+    let expected_diagnostics: Vec<Diagnostics> = vec![UnknownToken::new(at(3), at(6)).into()];
+
+    let mut actual_program = common::internal::empty();
+
+    let mut de = TextFormatDeserializerV2::new(given_string);
+    let actual_diagnostics = de.deserialize(&mut actual_program);
+
+    assert_eq!(expected_program, actual_program);
+    assert_eq!(expected_diagnostics, actual_diagnostics);
+}
+
+/// One instruction more than the program can contain. Only the first extra one is reported.
+#[test]
+fn deserialize_v2_program_overflow() {
+    let given_string = format!("${}", "^W".repeat(Program::INSTRUCTIONS_PER_PROGRAM + 2));
+
+    let mut expected_program = common::internal::empty();
+    for i in 0..Program::INSTRUCTIONS_PER_PROGRAM {
+        expected_program[i] = Instruction::new_simple(InstructionId::MoveW).unwrap();
+    }
+    let first_extra = 1 + 2 * Program::INSTRUCTIONS_PER_PROGRAM;
+    let expected_diagnostics: Vec<Diagnostics> =
+        vec![ProgramOverflow::new(at(first_extra), at(first_extra + 1)).into()];
+
+    let mut actual_program = common::internal::empty();
+
+    let mut de = TextFormatDeserializerV2::new(&given_string);
+    let actual_diagnostics = de.deserialize(&mut actual_program);
+
+    assert_eq!(expected_program, actual_program);
+    assert_eq!(expected_diagnostics, actual_diagnostics);
+}
+
+/// `_` at the 14th column can't move three steps forward, so it moves to the next row.
+#[test]
+fn deserialize_v2_row_overflow() {
+    let given_string = format!("${}_^S", "^W".repeat(14));
+
+    let mut expected_program = common::internal::empty();
+    for i in 0..14 {
+        expected_program[i] = Instruction::new_simple(InstructionId::MoveW).unwrap();
+    }
+    expected_program[Program::INSTRUCTIONS_PER_ROW] =
+        Instruction::new_simple(InstructionId::MoveS).unwrap();
+    let expected_diagnostics: Vec<Diagnostics> = vec![RowOverflow::new(at(29), at(29)).into()];
+
+    let mut actual_program = common::internal::empty();
+
+    let mut de = TextFormatDeserializerV2::new(&given_string);
+    let actual_diagnostics = de.deserialize(&mut actual_program);
+
+    assert_eq!(expected_program, actual_program);
+    assert_eq!(expected_diagnostics, actual_diagnostics);
+}
+
+/// Literals are truncated to their `MAX_CHAR_LEN`.
+///
+/// ```text
+/// 012345678901234567890123456
+/// $>abcd|(va01=000007)!{abcd}
+///   ^^^^  ^^^^ ^^^^^^   ^^^^
+/// ```
+#[test]
+fn deserialize_v2_literal_too_long() {
+    let given_string = "$>abcd|(va01=000007)!{abcd}";
+
+    let mut expected_program = common::internal::empty();
+    expected_program[0] = Instruction::new_label(
+        InstructionId::GoTo,
+        LabelIdentifierLiteral::new_from_array([b'a', b'b', b'c', 0]).unwrap(),
+    )
+    .unwrap();
+    expected_program[1] = Instruction::new_var_cmp(
+        InstructionId::VarEqual,
+        VariableIdentifierLiteral::new_from_array([b'v', b'a', b'0', 0]).unwrap(),
+        VariableValueLiteral::new_from_value(0).unwrap(),
+    )
+    .unwrap();
+    expected_program[2] = Instruction::new_string(
+        InstructionId::DebugBreak,
+        "abc".parse::<StringLiteral>().unwrap(),
+    )
+    .unwrap();
+    let expected_diagnostics: Vec<Diagnostics> = vec![
+        LiteralTooLong::new(at(2), at(5)).into(),
+        LiteralTooLong::new(at(8), at(11)).into(),
+        LiteralTooLong::new(at(13), at(18)).into(),
+        LiteralTooLong::new(at(22), at(25)).into(),
+    ];
+
+    let mut actual_program = common::internal::empty();
+
+    let mut de = TextFormatDeserializerV2::new(given_string);
+    let actual_diagnostics = de.deserialize(&mut actual_program);
+
+    assert_eq!(expected_program, actual_program);
+    assert_eq!(expected_diagnostics, actual_diagnostics);
+}
+
+/// String literals can contain non-ASCII and escaped chars, but only 3 of them.
+///
+/// ```text
+/// 012345678901234
+/// $!{фівіаві}{\}}
+///    ^^^^^^^
+/// ```
+#[test]
+fn deserialize_v2_unicode_string_too_long() {
+    let given_string = r"$!{фівіаві}{\}}";
+
+    let mut expected_program = common::internal::empty();
+    expected_program[0] =
+        Instruction::new_string(InstructionId::DebugBreak, "фів".parse().unwrap()).unwrap();
+    expected_program[1] =
+        Instruction::new_string(InstructionId::DebugSet, "}".parse().unwrap()).unwrap();
+    let expected_diagnostics: Vec<Diagnostics> = vec![LiteralTooLong::new(at(3), at(9)).into()];
+
+    let mut actual_program = common::internal::empty();
+
+    let mut de = TextFormatDeserializerV2::new(given_string);
+    let actual_diagnostics = de.deserialize(&mut actual_program);
+
+    assert_eq!(expected_program, actual_program);
+    assert_eq!(expected_diagnostics, actual_diagnostics);
+}
+
+/// Variable values must be in range `[-9_999, 99_999]`.
+///
+/// ```text
+/// 012345678901234567890
+/// $(a=100000)(a=-10000)
+///     ^^^^^^    ^^^^^^
+/// ```
+#[test]
+fn deserialize_v2_variable_value_out_of_range() {
+    let given_string = "$(a=100000)(a=-10000)";
+
+    let mut expected_program = common::internal::empty();
+    expected_program[0] = Instruction::new_var_cmp(
+        InstructionId::VarEqual,
+        VariableIdentifierLiteral::new_from_array([b'a', 0, 0, 0]).unwrap(),
+        VariableValueLiteral::new_from_value(10000).unwrap(),
+    )
+    .unwrap();
+    expected_program[1] = Instruction::new_var_cmp(
+        InstructionId::VarEqual,
+        VariableIdentifierLiteral::new_from_array([b'a', 0, 0, 0]).unwrap(),
+        VariableValueLiteral::new_from_value(-1000).unwrap(),
+    )
+    .unwrap();
+    let expected_diagnostics: Vec<Diagnostics> = vec![
+        VariableValueOutOfRange::new(at(4), at(9)).into(),
+        VariableValueOutOfRange::new(at(14), at(19)).into(),
+    ];
+
+    let mut actual_program = common::internal::empty();
+
+    let mut de = TextFormatDeserializerV2::new(given_string);
+    let actual_diagnostics = de.deserialize(&mut actual_program);
+
+    assert_eq!(expected_program, actual_program);
+    assert_eq!(expected_diagnostics, actual_diagnostics);
+}
+
+/// A literal-bearing token at the end of the string.
+///
+/// ```text
+/// 01234567
+/// $^W(va0<
+///    ^^^^^
+/// ```
+#[test]
+fn deserialize_v2_unterminated_literal_at_end() {
+    let given_string = "$^W(va0<";
+
+    let expected_program = common::internal::only_move_w();
+    let expected_diagnostics: Vec<Diagnostics> =
+        vec![UnterminatedLiteral::new(at(3), at(7)).into()];
+
+    let mut actual_program = common::internal::empty();
+
+    let mut de = TextFormatDeserializerV2::new(given_string);
+    let actual_diagnostics = de.deserialize(&mut actual_program);
+
+    assert_eq!(expected_program, actual_program);
+    assert_eq!(expected_diagnostics, actual_diagnostics);
+}
+
+/// Returns position of the char at `column` of the first line.
+fn at(column: usize) -> CharPosition {
+    CharPosition {
+        index: column,
+        line: 0,
+        column,
+    }
+}
+
+/// A letter case typo in a token with several chars.
+///
+/// ```text
+/// 012345
+/// $]^w^S
+///  ^^
+/// ```
+#[test]
+fn deserialize_v2_letter_case_typo() {
+    let given_string = "$]^w^S";
+
+    let mut expected_program = common::internal::empty();
+    expected_program[0] = Instruction::new_simple(InstructionId::LookW).unwrap();
+    expected_program[1] = Instruction::new_simple(InstructionId::MoveS).unwrap();
+    let expected_diagnostics: Vec<Diagnostics> = vec![
+        UnknownToken::new(at(1), at(1)).into(),
+        UnknownToken::new_with_suggestion(at(2), at(2), Suggestion::new(at(2), at(3), "^W")).into(),
+    ];
+
+    let mut actual_program = common::internal::empty();
+
+    let mut de = TextFormatDeserializerV2::new(given_string);
+    let actual_diagnostics = de.deserialize(&mut actual_program);
+
+    assert_eq!(expected_program, actual_program);
+    assert_eq!(expected_diagnostics, actual_diagnostics);
+}
+
+/// Instructions after illegal tokens are still placed at their positions.
+///
+/// ```text
+/// 0123456 7890123456
+/// $^W]]^S\n^A(a=5^D
+///    ^^      ^^^^
+/// ```
+#[test]
+fn deserialize_v2_partial() {
+    let given_string = "$^W]]^S\n^A(a=5^D";
+
+    let mut expected_program = common::internal::empty();
+    expected_program[0] = Instruction::new_simple(InstructionId::MoveW).unwrap();
+    expected_program[1] = Instruction::new_simple(InstructionId::MoveS).unwrap();
+    expected_program[16] = Instruction::new_simple(InstructionId::MoveA).unwrap();
+    expected_program[17] = Instruction::new_simple(InstructionId::MoveD).unwrap();
+    let expected_diagnostics: Vec<Diagnostics> = vec![
+        UnknownToken::new(at(3), at(4)).into(),
+        UnterminatedLiteral::new(at_line(1, 2, 10), at_line(1, 5, 13)).into(),
+    ];
+    let expected_spans = vec![
+        TokenSpan {
+            start: at(1),
+            end: at(2),
+            position: InstructionPosition::new(0, 0, 0).unwrap(),
+        },
+        TokenSpan {
+            start: at(5),
+            end: at(6),
+            position: InstructionPosition::new(0, 0, 1).unwrap(),
+        },
+        TokenSpan {
+            start: at_line(1, 0, 8),
+            end: at_line(1, 1, 9),
+            position: InstructionPosition::new(0, 1, 0).unwrap(),
+        },
+        TokenSpan {
+            start: at_line(1, 6, 14),
+            end: at_line(1, 7, 15),
+            position: InstructionPosition::new(0, 1, 1).unwrap(),
+        },
+    ];
+
+    let actual = TextFormatDeserializerV2::new(given_string).deserialize_partial();
+
+    assert_eq!(expected_program, actual.program);
+    assert_eq!(expected_diagnostics, actual.diagnostics);
+    assert_eq!(expected_spans, actual.spans);
+}
+
+/// Returns position of the char at `line` and `column` with the given `index`.
+fn at_line(line: usize, column: usize, index: usize) -> CharPosition {
+    CharPosition {
+        index,
+        line,
+        column,
+    }
+}
@@ -1,2 +1,3 @@
 mod deserializer;
+mod round_trip;
 mod serializer;
@@ -0,0 +1,113 @@
+use m3c::formats::internal::{Instruction, InstructionId, InstructionPosition, Program};
+use m3c::serialization::native::new::{
+    TextFormatDeserializer, TextFormatDeserializerV2, TextFormatSerializer,
+};
+
+use crate::common;
+
+/// Serializes the `program` and checks both deserializers read it back.
+fn assert_round_trip(program: &Program) {
+    let mut buf = vec![];
+    TextFormatSerializer::new(program)
+        .serialize(&mut buf)
+        .unwrap();
+    let string = String::from_utf8(buf).unwrap();
+
+    let mut actual_program = common::internal::empty();
+    let mut de = TextFormatDeserializer::new_from_str(&mut actual_program, &string);
+    de.deserialize().unwrap();
+    assert_eq!(program, &actual_program, "{:?}", string);
+
+    let mut actual_program = common::internal::empty();
+    let actual_diagnostics =
+        TextFormatDeserializerV2::new(&string).deserialize(&mut actual_program);
+    assert_eq!(program, &actual_program, "{:?}", string);
+    assert!(actual_diagnostics.is_empty(), "{:?}", string);
+}
+
+/// Returns all the instructions used by the fixtures.
+fn instruction_pool() -> Vec<Instruction> {
+    let all_simple = common::internal::all_simple();
+    let literals = common::internal::literals();
+    all_simple
+        .instruction_positions()
+        .chain(literals.instruction_positions())
+        .map(|(_, ins)| ins)
+        .filter(|ins| ins.id() != InstructionId::Empty)
+        .collect()
+}
+
+#[test]
+fn round_trip_fixtures() {
+    assert_round_trip(&common::internal::empty());
+    assert_round_trip(&common::internal::all_simple());
+    assert_round_trip(&common::internal::not_all_commands());
+    assert_round_trip(&common::internal::commands());
+    assert_round_trip(&common::internal::literals());
+    assert_round_trip(&common::internal::strings());
+}
+
+/// Single instructions at the positions where the cursor is at the edge of a row or a page.
+#[test]
+fn round_trip_edges() {
+    let ins = instruction_pool()[0];
+    let edges = [
+        (0, 0, 1),
+        (0, 0, 15),
+        (0, 1, 0),
+        (0, 11, 15),
+        (1, 0, 0),
+        (1, 0, 7),
+        (3, 11, 0),
+        (15, 11, 15),
+    ];
+    for (page, row, column) in edges {
+        let mut program = common::internal::empty();
+        program[InstructionPosition::new(page, row, column).unwrap()] = ins;
+        assert_round_trip(&program);
+    }
+    for (first, second) in [
+        (15, 16),
+        (15, 17),
+        (15, 32),
+        (191, 192),
+        (191, 193),
+        (191, 400),
+    ] {
+        let mut program = common::internal::empty();
+        program[first] = ins;
+        program[second] = ins;
+        assert_round_trip(&program);
+    }
+
+    let mut program = common::internal::empty();
+    for i in 0..Program::INSTRUCTIONS_PER_PROGRAM {
+        program[i] = ins;
+    }
+    assert_round_trip(&program);
+}
+
+/// Programs filled with random instructions at random densities.
+#[test]
+fn round_trip_random() {
+    let pool = instruction_pool();
+    // xorshift, to keep the test deterministic
+    let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    for density in [1, 2, 5, 20, 50, 90] {
+        for _ in 0..20 {
+            let mut program = common::internal::empty();
+            for i in 0..Program::INSTRUCTIONS_PER_PROGRAM {
+                if next() % 100 < density {
+                    program[i] = pool[next() as usize % pool.len()];
+                }
+            }
+            assert_round_trip(&program);
+        }
+    }
+}
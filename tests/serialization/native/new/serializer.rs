@@ -1,6 +1,7 @@
-use m3c::serialization::native::new::TextFormatSerializer;
+use m3c::formats::internal::{Instruction, InstructionId, Program};
+use m3c::serialization::native::new::{NoSpellingError, SerializeError, TextFormatSerializer};
 
-use crate::common;
+use crate::common::{self, at};
 
 /// Tests all `Simple` kind instuctions.
 #[test]
@@ -43,3 +44,61 @@ fn serialize_literals() {
 
     assert_eq!(expected_string, String::from_utf8(buf).unwrap());
 }
+
+/// Non-ASCII strings are kept, `\`, `'` and `}` are escaped.
+#[test]
+fn serialize_strings() {
+    let given_program = common::internal::strings();
+    let expected_string = common::native::new::STRINGS;
+
+    let mut buf = vec![];
+    TextFormatSerializer::new(&given_program)
+        .serialize(&mut buf)
+        .unwrap();
+
+    assert_eq!(expected_string, String::from_utf8(buf).unwrap());
+}
+
+/// Serializes the `program` expecting the `NoSpellingError`, checks nothing is written.
+fn serialize_no_spelling(program: &Program) -> NoSpellingError {
+    let mut buf = vec![];
+    let result = TextFormatSerializer::new(program).serialize(&mut buf);
+    assert!(buf.is_empty());
+    match result {
+        Err(SerializeError::NoSpelling(e)) => e,
+        result => panic!("{:?}", result),
+    }
+}
+
+/// Unknown ids have no spelling, programs with them aren't serialized.
+#[test]
+fn serialize_unknown() {
+    let error = serialize_no_spelling(&common::internal::unknown());
+    assert_eq!(InstructionId::Unknown(34), error.id());
+    assert_eq!(Some(at(1)), error.position());
+}
+
+/// `Last` and `Restart` have no spelling, programs with them aren't serialized.
+#[test]
+fn serialize_without_ntf() {
+    let error = serialize_no_spelling(&common::internal::without_ntf());
+    assert_eq!(InstructionId::Last, error.id());
+    assert_eq!(Some(at(1)), error.position());
+
+    let mut program = common::internal::without_ntf();
+    program[1] = Instruction::default();
+    let error = serialize_no_spelling(&program);
+    assert_eq!(InstructionId::Restart, error.id());
+    assert_eq!(Some(at(20)), error.position());
+
+    let mut buf = vec![];
+    let instruction = Instruction::new_simple(InstructionId::Restart).unwrap();
+    match TextFormatSerializer::serialize_instruction(&instruction, &mut buf) {
+        Err(SerializeError::NoSpelling(e)) => {
+            assert_eq!(InstructionId::Restart, e.id());
+            assert_eq!(None, e.position());
+        }
+        result => panic!("{:?}", result),
+    }
+    assert!(buf.is_empty());
+}
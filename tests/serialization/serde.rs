@@ -0,0 +1,174 @@
+use m3c::formats::internal::literals::{
+    LabelIdentifierLiteral, VariableIdentifierLiteral, VariableValueLiteral,
+};
+use m3c::formats::internal::{Instruction, InstructionId, InstructionPosition, Program};
+use serde_json::json;
+
+use crate::common;
+
+#[test]
+fn round_trip() {
+    for program in [
+        common::internal::empty(),
+        common::internal::all_simple(),
+        common::internal::commands(),
+        common::internal::literals(),
+        common::internal::unknown(),
+        common::internal::without_ntf(),
+        common::internal::strings(),
+    ] {
+        let json = serde_json::to_string(&program).unwrap();
+        assert_eq!(program, serde_json::from_str::<Program>(&json).unwrap());
+    }
+}
+
+#[test]
+fn sparse_program() {
+    let mut program = Program::default();
+    program[0] = Instruction::new_simple(InstructionId::MoveW).unwrap();
+    program[17] = Instruction::new_label(
+        InstructionId::GoTo,
+        LabelIdentifierLiteral::new_from_array(*b"ab\0\0").unwrap(),
+    )
+    .unwrap();
+    program[3071] = Instruction::new_var_cmp(
+        InstructionId::VarMore,
+        VariableIdentifierLiteral::new_from_array(*b"x\0\0\0").unwrap(),
+        VariableValueLiteral::new_from_value(-5).unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        json!([
+            {"page": 0, "row": 0, "column": 0, "instruction": "MOVE_W"},
+            {"page": 0, "row": 1, "column": 1, "instruction": "GOTO", "label": "ab"},
+            {
+                "page": 15, "row": 11, "column": 15,
+                "instruction": "VAR_MORE", "variable": "x", "value": -5
+            },
+        ]),
+        serde_json::to_value(&program).unwrap()
+    );
+    assert_eq!(json!([]), serde_json::to_value(Program::default()).unwrap());
+}
+
+#[test]
+fn parts() {
+    let position = InstructionPosition::new(1, 2, 3).unwrap();
+    assert_eq!(
+        json!({"page": 1, "row": 2, "column": 3}),
+        serde_json::to_value(position).unwrap()
+    );
+    assert_eq!(
+        json!("CCC_REDROCK"),
+        serde_json::to_value(InstructionId::CccRedRock).unwrap()
+    );
+    let instruction: Instruction =
+        serde_json::from_value(json!({"instruction": "DEBUG_BREAK", "string": "t1"})).unwrap();
+    assert_eq!(InstructionId::DebugBreak, instruction.id());
+    assert_eq!(
+        json!({"instruction": "DEBUG_BREAK", "string": "t1"}),
+        serde_json::to_value(instruction).unwrap()
+    );
+}
+
+#[test]
+fn validation() {
+    let error = |value| {
+        serde_json::from_value::<Program>(value)
+            .unwrap_err()
+            .to_string()
+    };
+
+    assert!(
+        error(json!([{"page": 16, "row": 0, "column": 0, "instruction": "END"}]))
+            .contains("illegal position 16:0:0")
+    );
+    assert!(
+        error(json!([{"page": 0, "row": 0, "column": 0, "instruction": "JUMP"}]))
+            .contains("unknown instruction `JUMP`")
+    );
+    assert!(error(json!([
+        {"page": 0, "row": 0, "column": 0, "instruction": "GOTO", "label": "abcd"}
+    ]))
+    .contains("illegal label `abcd`"));
+    assert!(error(json!([
+        {"page": 0, "row": 0, "column": 0, "instruction": "GOTO", "label": "a-"}
+    ]))
+    .contains("illegal label `a-`"));
+    assert!(error(json!([
+        {"page": 0, "row": 0, "column": 0, "instruction": "VAR_LESS", "variable": "x", "value": 100000}
+    ]))
+    .contains("illegal value `100000`"));
+    assert!(
+        error(json!([{"page": 0, "row": 0, "column": 0, "instruction": "GOTO"}]))
+            .contains("`GOTO` takes `label`")
+    );
+    assert!(error(json!([
+        {"page": 0, "row": 0, "column": 0, "instruction": "MOVE_W", "string": "a"}
+    ]))
+    .contains("`MOVE_W` takes no data"));
+    assert!(error(json!([
+        {"page": 0, "row": 0, "column": 0, "instruction": "VAR_LESS", "variable": "x"}
+    ]))
+    .contains("expected no data"));
+    assert!(error(json!([
+        {"page": 0, "row": 0, "column": 0, "instruction": "GOTO", "lable": "a"}
+    ]))
+    .contains("unknown field `lable`"));
+    assert!(error(json!([
+        {"page": 0, "row": 0, "column": 0, "instruction": "END", "colum": 1}
+    ]))
+    .contains("unknown field `colum`"));
+    assert!(error(json!([
+        {"page": 0, "row": 0, "column": 1, "instruction": "END"},
+        {"page": 0, "row": 0, "column": 1, "instruction": "BACK"},
+    ]))
+    .contains("position 0:0:1 is set twice"));
+}
+
+#[test]
+fn unknown_ids() {
+    let mut program = Program::default();
+    program[1] = Instruction::new_simple(InstructionId::Unknown(250)).unwrap();
+    let value = json!([{"page": 0, "row": 0, "column": 1, "instruction": 250}]);
+
+    assert_eq!(value, serde_json::to_value(&program).unwrap());
+    assert_eq!(program, serde_json::from_value::<Program>(value).unwrap());
+    assert!(serde_json::from_value::<Program>(
+        json!([{"page": 0, "row": 0, "column": 1, "instruction": 256}])
+    )
+    .is_err());
+}
+
+#[test]
+fn toml_round_trip() {
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Document {
+        program: Program,
+    }
+
+    for program in [
+        common::internal::literals(),
+        common::internal::unknown(),
+        common::internal::without_ntf(),
+        common::internal::strings(),
+    ] {
+        let document = Document { program };
+        let toml = toml::to_string(&document).unwrap();
+        assert_eq!(document, toml::from_str(&toml).unwrap());
+    }
+
+    // TOML integers are signed
+    let document: Document = toml::from_str(concat!(
+        "[[program]]\n",
+        "page = 0\nrow = 0\ncolumn = 1\ninstruction = 250\n",
+    ))
+    .unwrap();
+    assert_eq!(InstructionId::Unknown(250), document.program[1].id());
+    assert!(toml::from_str::<Document>(concat!(
+        "[[program]]\n",
+        "page = 0\nrow = 0\ncolumn = 1\ninstruction = -1\n",
+    ))
+    .is_err());
+}
@@ -0,0 +1,97 @@
+use m3c::formats::internal::literals::StringLiteral;
+use m3c::formats::internal::{InstructionId, InstructionPosition};
+use m3c::simulation::debugger::{Debugger, Stop};
+use m3c::simulation::robot::{Direction, Robot};
+use m3c::simulation::{Simulator, Status};
+
+use super::world;
+use crate::common::{self, asm};
+
+fn tag(tag: &str) -> StringLiteral {
+    tag.parse().unwrap()
+}
+
+fn position(page: u8, row: u8, column: u8) -> InstructionPosition {
+    InstructionPosition::new(page, row, column).unwrap()
+}
+
+#[test]
+fn steps() {
+    let program = asm("MODE_AUTODIGG_ON\nMOVE_S\nMOVE_S\nEND");
+    let mut debugger = Debugger::new(Simulator::new(&program, world(".\n.\n."), Robot::new(0, 0)));
+
+    assert_eq!(Stop::Step, debugger.step(1));
+    assert!(debugger.simulator().robot().autodigg());
+    // the mode switch doesn't end the tick
+    assert_eq!(0, debugger.simulator().ticks());
+    assert_eq!(Stop::Step, debugger.step(2));
+    assert_eq!(2, debugger.simulator().ticks());
+    assert_eq!(
+        (position(0, 0, 3), InstructionId::End),
+        (
+            debugger.next_instruction().0,
+            debugger.next_instruction().1.id()
+        )
+    );
+    assert_eq!(Stop::Halted(Status::Finished), debugger.step(5));
+}
+
+#[test]
+fn breakpoints() {
+    let program = asm("a:\nMOVE_D\nDEBUG_BREAK ''\nGOTO a");
+    let mut debugger = Debugger::new(Simulator::new(&program, world("...."), Robot::new(0, 0)));
+
+    // a `DEBUG_BREAK` without a tag always stops
+    assert_eq!(Stop::Tag(tag("")), debugger.resume(100));
+    assert_eq!(position(0, 0, 2), debugger.simulator().position());
+
+    assert!(debugger.add_breakpoint(position(0, 0, 1)));
+    assert!(!debugger.add_breakpoint(position(0, 0, 1)));
+    assert_eq!(Stop::Breakpoint(position(0, 0, 1)), debugger.resume(100));
+    assert_eq!(
+        vec![position(0, 0, 1)],
+        debugger.breakpoints().collect::<Vec<_>>()
+    );
+
+    assert!(debugger.remove_breakpoint(position(0, 0, 1)));
+    assert!(!debugger.remove_breakpoint(position(0, 0, 1)));
+    assert_eq!(Stop::Tag(tag("")), debugger.resume(100));
+    assert_eq!((2, 0), debugger.simulator().robot().position());
+
+    // never stops
+    assert_eq!(Stop::Step, debugger.step(3));
+}
+
+#[test]
+fn tags() {
+    let program = asm("DEBUG_BREAK 'a'\nMOVE_D\nDEBUG_BREAK 'b'\nMOVE_D\nDEBUG_SET 'c'\nEND");
+    let mut debugger = Debugger::new(Simulator::new(&program, world("..."), Robot::new(0, 0)));
+    assert!(debugger.add_tag(tag("b")));
+
+    assert_eq!(Stop::Tag(tag("b")), debugger.resume(100));
+    assert_eq!((1, 0), debugger.simulator().robot().position());
+    assert_eq!(vec![tag("b")], debugger.tags().collect::<Vec<_>>());
+    assert!(debugger.remove_tag(tag("b")));
+
+    assert_eq!(Stop::Halted(Status::Finished), debugger.resume(100));
+    assert_eq!(
+        Some(tag("c")),
+        debugger.simulator().interpreter().debug_tag()
+    );
+}
+
+#[test]
+fn inspection() {
+    let program = asm(concat!(
+        "VAR_EQUAL x, 0\nGOSUB a\nEND\n",
+        "a:\nINVDIR_W\nBOOLMODE_AND\nCC_EMPTY\nDEBUG_BREAK ''\nRETURN",
+    ));
+    let mut debugger = Debugger::new(Simulator::new(&program, world(".\n."), Robot::new(0, 0)));
+
+    assert_eq!(Stop::Tag(tag("")), debugger.resume(100));
+    let interpreter = debugger.simulator().interpreter();
+    assert_eq!(&[position(0, 0, 2)], interpreter.calls());
+    assert_eq!(Some(true), interpreter.condition());
+    assert_eq!(0, interpreter.variable(common::var("x")));
+    assert!(debugger.simulator().robot().is_inverted(Direction::W));
+}
@@ -0,0 +1,120 @@
+mod debugger;
+
+use m3c::simulation::robot::{Direction, Robot};
+use m3c::simulation::sandbox::Sandbox;
+use m3c::simulation::world::{Cell, ParseWorldError, World};
+use m3c::simulation::{Simulator, Status};
+
+use crate::common::asm;
+
+fn world(map: &str) -> World {
+    map.parse().unwrap()
+}
+
+#[test]
+fn moves_stop_at_walls() {
+    let program = asm("MOVE_D\nMOVE_D\nMOVE_D\nEND");
+    let mut simulator = Simulator::new(&program, world("..#"), Robot::new(0, 0));
+
+    assert_eq!(Status::Finished, simulator.run(10));
+    assert_eq!((1, 0), simulator.robot().position());
+    assert_eq!(Direction::D, simulator.robot().facing());
+    assert_eq!(4, simulator.ticks());
+}
+
+#[test]
+fn digging() {
+    let program = asm("MODE_AUTODIGG_ON\nMOVE_D\nMOVE_D\nMOVE_D\nMOVE_D\nDIGG\nEND");
+    let mut simulator = Simulator::new(&program, world(".#*"), Robot::new(0, 0));
+
+    // the mode switch doesn't take a tick
+    simulator.tick();
+    assert_eq!(2, simulator.world().durability(1, 0));
+
+    assert_eq!(Status::Finished, simulator.run(10));
+    assert_eq!((1, 0), simulator.robot().position());
+    assert_eq!(1, simulator.robot().crystals());
+    assert_eq!("...\n", simulator.world().to_string());
+}
+
+/// Goes down until the cell below isn't empty.
+#[test]
+fn checks_and_jumps() {
+    let program = asm("a:\nCELL_S\nCC_NOTEMPTY\nCCC_ROAD\nIF_GOTO b\nMOVE_S\nGOTO a\nb:\nEND");
+    let map = ".\n.\n=\n.\n#";
+    let mut simulator = Simulator::new(&program, world(map), Robot::new(0, 0));
+
+    assert_eq!(Status::Finished, simulator.run(100));
+    assert_eq!((0, 1), simulator.robot().position());
+
+    // a road is passable, but `CC_NOTEMPTY AND CCC_ROAD` is never true
+    let program =
+        asm("BOOLMODE_AND\na:\nCELL_S\nCC_NOTEMPTY\nCCC_ROAD\nIF_GOTO b\nMOVE_S\nGOTO a\nb:\nEND");
+    let mut simulator = Simulator::new(&program, world(map), Robot::new(0, 0));
+
+    assert_eq!(Status::Running, simulator.run(100));
+    assert_eq!((0, 3), simulator.robot().position());
+}
+
+#[test]
+fn building_and_healing() {
+    let program = asm("LOOK_D\nACTION_B3\nLOOK_S\nACTION_ROAD\nACTION_HEAL\nACTION_HEAL\nEND");
+    let robot = Robot::new(0, 0).with_crystals(5).with_hp(50);
+    let mut simulator = Simulator::new(&program, world("..\n.."), robot);
+
+    assert_eq!(Status::Finished, simulator.run(10));
+    assert_eq!(Cell::RedBlock, simulator.world().get(1, 0));
+    assert_eq!(Cell::Road, simulator.world().get(0, 1));
+    assert_eq!(0, simulator.robot().crystals());
+    // the second heal has no crystals
+    assert_eq!(50 + Sandbox::HEAL_HP, simulator.robot().hp());
+}
+
+#[test]
+fn inverted_directions() {
+    let program = asm("INVDIR_D\nMOVE_D\nINVDIR_D\nMOVE_D\nMOVE_D\nEND");
+    let mut simulator = Simulator::new(&program, world("...."), Robot::new(1, 0));
+
+    assert_eq!(Status::Finished, simulator.run(10));
+    assert_eq!((2, 0), simulator.robot().position());
+    assert!(!simulator.robot().is_inverted(Direction::D));
+}
+
+/// Sand falls every tick, but not into the robot.
+#[test]
+fn gravity() {
+    let program = asm("MOVE_D\nMOVE_A\nEND");
+    let mut simulator = Simulator::new(&program, world(":.\n..\n.."), Robot::new(0, 2));
+
+    simulator.tick();
+    assert_eq!("..\n:.\n..\n", simulator.world().to_string());
+
+    assert_eq!(Status::Finished, simulator.run(10));
+    assert_eq!((0, 2), simulator.robot().position());
+    assert_eq!("..\n:.\n..\n", simulator.world().to_string());
+}
+
+#[test]
+fn acid_destroys_robot() {
+    let program = asm("");
+    let mut simulator = Simulator::new(&program, world("~.~"), Robot::new(1, 0));
+
+    assert_eq!(Status::Destroyed, simulator.run(100));
+    assert_eq!(
+        Robot::MAX_HP / Sandbox::ACID_DAMAGE / 2,
+        simulator.ticks() as u32
+    );
+}
+
+#[test]
+fn parse_world_errors() {
+    assert_eq!(Err(ParseWorldError::Empty), "".parse::<World>());
+    assert_eq!(
+        Err(ParseWorldError::RaggedLine(1)),
+        "..\n.".parse::<World>()
+    );
+    assert_eq!(
+        Err(ParseWorldError::UnknownCell('?')),
+        ".?".parse::<World>()
+    );
+}